@@ -0,0 +1,228 @@
+use std::{env, fmt::Write, fs, path::PathBuf};
+
+// nothing bigger than the display can ever be shown whole
+const MAX_WIDTH: usize = 84;
+const MAX_HEIGHT: usize = 48;
+
+// whether each pixel is black, row by row
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+// the next whitespace separated token of a PBM header, skipping comments
+fn token<'a>(data: &'a [u8], position: &mut usize) -> &'a [u8] {
+    loop {
+        match data.get(*position) {
+            Some(b'#') => {
+                while data.get(*position).is_some_and(|b| *b != b'\n') {
+                    *position += 1;
+                }
+            }
+            Some(b) if b.is_ascii_whitespace() => *position += 1,
+            _ => break,
+        }
+    }
+    let start = *position;
+    while data
+        .get(*position)
+        .is_some_and(|b| !b.is_ascii_whitespace() && *b != b'#')
+    {
+        *position += 1;
+    }
+    &data[start..*position]
+}
+
+fn number(name: &str, token: &[u8]) -> usize {
+    std::str::from_utf8(token)
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or_else(|| panic!("{name}: bad size in header"))
+}
+
+// plain (P1) and raw (P4) portable bitmaps
+fn parse_pbm(name: &str, data: &[u8]) -> Image {
+    let mut position = 0;
+    let magic = token(data, &mut position);
+    let width = number(name, token(data, &mut position));
+    let height = number(name, token(data, &mut position));
+    let pixels = match magic {
+        b"P1" => data[position..]
+            .split(|b| *b == b'\n')
+            .flat_map(|line| line.split(|b| *b == b'#').next().unwrap_or_default())
+            .filter(|b| !b.is_ascii_whitespace())
+            .map(|b| match b {
+                b'0' => false,
+                b'1' => true,
+                _ => panic!("{name}: bad pixel {}", *b as char),
+            })
+            .collect::<Vec<_>>(),
+        b"P4" => {
+            let bytes_per_row = width.div_ceil(8);
+            let raster = &data[position + 1..];
+            (0..width * height)
+                .map(|i| {
+                    let (x, y) = (i % width, i / width);
+                    raster
+                        .get(y * bytes_per_row + x / 8)
+                        .is_some_and(|b| b & (0x80 >> (x % 8)) != 0)
+                })
+                .collect()
+        }
+        _ => panic!("{name}: neither a PBM nor a PNG"),
+    };
+    assert_eq!(
+        pixels.len(),
+        width * height,
+        "{name}: wrong number of pixels"
+    );
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+// how bright a colour looks, out of 255
+fn luma(r: u8, g: u8, b: u8) -> u32 {
+    (299 * u32::from(r) + 587 * u32::from(g) + 114 * u32::from(b)) / 1000
+}
+
+// black wherever a pixel is more dark than light and not mostly see-through
+fn parse_png(name: &str, data: &[u8]) -> Image {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder
+        .read_info()
+        .unwrap_or_else(|e| panic!("{name}: {e}"));
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader
+        .next_frame(&mut buffer)
+        .unwrap_or_else(|e| panic!("{name}: {e}"));
+    let (width, height) = (frame.width as usize, frame.height as usize);
+    let samples = frame.color_type.samples();
+    let pixels = buffer
+        .chunks(frame.line_size)
+        .take(height)
+        .flat_map(|row| row[..width * samples].chunks(samples))
+        .map(|pixel| {
+            let (luma, alpha) = match *pixel {
+                [l] => (u32::from(l), 255),
+                [l, a] => (u32::from(l), a),
+                [r, g, b] => (luma(r, g, b), 255),
+                [r, g, b, a] => (luma(r, g, b), a),
+                _ => panic!("{name}: unexpected pixel format"),
+            };
+            luma < 128 && alpha >= 128
+        })
+        .collect();
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+fn parse(name: &str, data: &[u8]) -> Image {
+    if data.starts_with(b"\x89PNG") {
+        parse_png(name, data)
+    } else {
+        parse_pbm(name, data)
+    }
+}
+
+fn generate(name: &str, image: &Image, out: &mut String) {
+    assert!(
+        image.width <= MAX_WIDTH && image.height <= MAX_HEIGHT,
+        "{name} is {}x{}, which does not fit the {MAX_WIDTH}x{MAX_HEIGHT} display",
+        image.width,
+        image.height
+    );
+    let bytes_per_row = image.width.div_ceil(8);
+    let mut data = vec![0u8; bytes_per_row * image.height];
+    for (i, black) in image.pixels.iter().enumerate() {
+        // black is ink, which is off
+        if !black {
+            let (x, y) = (i % image.width, i / image.width);
+            data[y * bytes_per_row + x / 8] |= 0x80 >> (x % 8);
+        }
+    }
+    writeln!(
+        out,
+        "pub const {}: ImageRaw<'static, BinaryColor> = ImageRaw::new(&{data:?}, {});",
+        name.to_uppercase(),
+        image.width
+    )
+    .unwrap();
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=images");
+
+    let mut paths: Vec<_> = fs::read_dir("images")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+
+    let mut out = String::new();
+    for path in paths {
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let image = parse(name, &fs::read(&path).unwrap());
+        generate(name, &image, &mut out);
+    }
+
+    let path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("images.rs");
+    fs::write(path, out).unwrap();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn png(color_type: png::ColorType, width: u32, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let height = data.len() as u32 / width / color_type.samples() as u32;
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(color_type);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(data)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn test_png() {
+        let grey = parse(
+            "grey",
+            &png(png::ColorType::Grayscale, 2, &[0, 255, 100, 200]),
+        );
+        assert_eq!((grey.width, grey.height), (2, 2));
+        assert_eq!(grey.pixels, [true, false, true, false]);
+
+        // see-through is background, whatever its colour
+        let rgba = parse(
+            "rgba",
+            &png(png::ColorType::Rgba, 2, &[0, 0, 0, 255, 0, 0, 0, 0]),
+        );
+        assert_eq!(rgba.pixels, [true, false]);
+    }
+
+    #[test]
+    fn test_pbm() {
+        let image = parse("plain", b"P1\n# a comment\n3 1\n1 0 1\n");
+        assert_eq!((image.width, image.height), (3, 1));
+        assert_eq!(image.pixels, [true, false, true]);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit the 84x48 display")]
+    fn test_too_big() {
+        let image = parse("wide", &png(png::ColorType::Grayscale, 85, &[255; 85]));
+        generate("wide", &image, &mut String::new());
+    }
+}
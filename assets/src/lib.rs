@@ -0,0 +1,39 @@
+#![no_std]
+
+// every image under images/ as a constant of the same name, generated by build.rs. images are
+// PBMs or PNGs, where black is ink (`BinaryColor::Off`) and white, or in a PNG anything light or
+// see-through, is background (`BinaryColor::On`).
+use embedded_graphics::{image::ImageRaw, pixelcolor::BinaryColor};
+
+include!(concat!(env!("OUT_DIR"), "/images.rs"));
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        image::Image, mock_display::MockDisplay, prelude::*, primitives::Rectangle,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(PENCIL.size(), Size::new(16, 5));
+        assert_eq!(CASE_UPPER.size(), Size::new(16, 6));
+        assert_eq!(SNAKE.size(), Size::new(8, 24));
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut display = MockDisplay::new();
+        Image::new(
+            &SNAKE.sub_image(&Rectangle::new(Point::new(0, 20), Size::new(4, 4))),
+            Point::zero(),
+        )
+        .draw(&mut display)
+        .unwrap();
+        display.assert_pattern(&[
+            "#.##", //
+            ".#.#", "#.##", "####",
+        ]);
+    }
+}
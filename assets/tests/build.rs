@@ -0,0 +1,4 @@
+// cargo doesn't run the tests of a build script, so its tests are brought in and run here
+#[expect(dead_code)]
+#[path = "../build.rs"]
+mod build;
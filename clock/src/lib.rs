@@ -2,7 +2,7 @@
 
 use chrono::{Datelike, Timelike};
 use embedded_graphics::{
-    mono_font::{MonoTextStyle, ascii::FONT_10X20},
+    mono_font::MonoTextStyle,
     pixelcolor::BinaryColor,
     prelude::*,
     primitives::{PrimitiveStyle, Rectangle},
@@ -88,7 +88,8 @@ impl ClockView {
     }
 
     fn draw_separators(&mut self, device: &mut impl shared::Device) {
-        let character_style = MonoTextStyle::new(&FONT_10X20, BinaryColor::Off);
+        let character_style =
+            MonoTextStyle::new(&fonts::CLOCK, shared::theme::current().foreground);
         Text::with_alignment(":", Point::new(28, 20), character_style, Alignment::Center)
             .draw(device)
             .unwrap();
@@ -219,17 +220,22 @@ impl ClockView {
             }
         };
 
-        let (foreground, background) = if self.selected == Some(segment) {
-            (BinaryColor::On, BinaryColor::Off)
-        } else {
-            (BinaryColor::Off, BinaryColor::On)
-        };
-
-        let character_style = MonoTextStyle::new(&FONT_10X20, foreground);
-        Rectangle::new(point - Point::new(9, 13), Size::new(20, 15))
-            .into_styled(PrimitiveStyle::with_fill(background))
-            .draw(device)
-            .unwrap();
+        let (foreground, background) =
+            shared::theme::current().colors(self.selected == Some(segment));
+
+        let character_style = MonoTextStyle::new(&fonts::CLOCK, foreground);
+        // both digits, centred on `point` and sitting on it, with a pixel spare all round
+        let size = fonts::CLOCK.character_size;
+        Rectangle::new(
+            point - Point::new(size.width as i32 + 1, fonts::CLOCK.baseline as i32 + 1),
+            Size::new(
+                size.width * 2 + fonts::CLOCK.character_spacing + 2,
+                size.height + 2,
+            ),
+        )
+        .into_styled(PrimitiveStyle::with_fill(background))
+        .draw(device)
+        .unwrap();
 
         Text::with_alignment(&text, point, character_style, Alignment::Center)
             .draw(device)
@@ -237,7 +243,7 @@ impl ClockView {
     }
 
     fn draw(&mut self, device: &mut impl shared::Device) {
-        device.clear(BinaryColor::On).unwrap();
+        shared::theme::current().clear(device);
         self.draw_separators(device);
         self.draw_segment(device, Setting::Hour);
         self.draw_segment(device, Setting::Minute);
@@ -0,0 +1,176 @@
+use std::{env, fmt::Write, fs, path::PathBuf};
+
+// every glyph of a font is drawn into a cell of the font's bounding box, all cells side by side
+// in one row of a 1 bit per pixel image, which is what `MonoFont` expects
+struct Font {
+    name: String,
+    width: u32,
+    height: u32,
+    // offset of the bottom left of the bounding box from the origin of a glyph, as in BDF
+    x_offset: i32,
+    y_offset: i32,
+    advance: Option<u32>,
+    glyphs: Vec<(char, Vec<Vec<bool>>)>,
+}
+
+fn numbers(s: &str) -> Vec<i32> {
+    s.split_whitespace()
+        .map(|n| n.parse().unwrap_or_else(|_| panic!("{n} is not a number")))
+        .collect()
+}
+
+fn parse(name: &str, bdf: &str) -> Font {
+    let mut font = Font {
+        name: name.to_uppercase(),
+        width: 0,
+        height: 0,
+        x_offset: 0,
+        y_offset: 0,
+        advance: None,
+        glyphs: Vec::new(),
+    };
+    let mut lines = bdf.lines().map(str::trim);
+    while let Some(line) = lines.next() {
+        let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match keyword {
+            "FONTBOUNDINGBOX" => {
+                let [w, h, x, y] = numbers(rest)[..] else {
+                    panic!("{name}: bad FONTBOUNDINGBOX {rest}")
+                };
+                (font.width, font.height) = (w as u32, h as u32);
+                (font.x_offset, font.y_offset) = (x, y);
+            }
+            "STARTCHAR" => {
+                let mut encoding = None;
+                let mut bbx = [font.width as i32, font.height as i32, 0, 0];
+                let mut cell = vec![vec![false; font.width as usize]; font.height as usize];
+                for line in lines.by_ref() {
+                    let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+                    match keyword {
+                        "ENCODING" => encoding = char::from_u32(numbers(rest)[0] as u32),
+                        "DWIDTH" => {
+                            let advance = numbers(rest)[0] as u32;
+                            assert!(
+                                font.advance.is_none_or(|a| a == advance),
+                                "{name}: only monospaced fonts are supported"
+                            );
+                            font.advance = Some(advance);
+                        }
+                        "BBX" => {
+                            bbx = numbers(rest)[..]
+                                .try_into()
+                                .unwrap_or_else(|_| panic!("{name}: bad BBX {rest}"))
+                        }
+                        "BITMAP" => break,
+                        _ => {}
+                    }
+                }
+                let [w, h, x, y] = bbx;
+                let left = x - font.x_offset;
+                let top = (font.height as i32 + font.y_offset) - (h + y);
+                for (row, line) in lines.by_ref().take(h as usize).enumerate() {
+                    let bits = u64::from_str_radix(line, 16)
+                        .unwrap_or_else(|_| panic!("{name}: bad bitmap row {line}"));
+                    for column in 0..w {
+                        if bits & (1 << (line.len() as i32 * 4 - 1 - column)) != 0 {
+                            let (cx, cy) = (left + column, top + row as i32);
+                            assert!(
+                                cx >= 0
+                                    && cy >= 0
+                                    && cx < font.width as i32
+                                    && cy < font.height as i32,
+                                "{name}: glyph {encoding:?} is outside the bounding box"
+                            );
+                            cell[cy as usize][cx as usize] = true;
+                        }
+                    }
+                }
+                assert_eq!(lines.next(), Some("ENDCHAR"), "{name}: expected ENDCHAR");
+                // glyphs that are not unicode, or control characters, can not be typed anyway
+                if let Some(c) = encoding.filter(|c| !c.is_control()) {
+                    font.glyphs.push((c, cell));
+                }
+            }
+            _ => {}
+        }
+    }
+    font.glyphs.sort_by_key(|(c, _)| *c);
+    font
+}
+
+fn generate(font: &Font, out: &mut String) {
+    let name = &font.name;
+    let image_width = font.width * font.glyphs.len() as u32;
+    let mut data = Vec::new();
+    for y in 0..font.height as usize {
+        let mut row = vec![0u8; image_width.div_ceil(8) as usize];
+        for (i, (_, cell)) in font.glyphs.iter().enumerate() {
+            for (x, on) in cell[y].iter().enumerate() {
+                if *on {
+                    let x = i * font.width as usize + x;
+                    row[x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+        data.extend(row);
+    }
+    let mapping: String = font.glyphs.iter().map(|(c, _)| *c).collect();
+    let replacement = mapping.chars().position(|c| c == '?').unwrap_or(0);
+    // the row of the baseline, counted from the top of a cell
+    let baseline = (font.height as i32 + font.y_offset - 1).max(0) as u32;
+    let spacing = font
+        .advance
+        .unwrap_or(font.width)
+        .saturating_sub(font.width);
+
+    writeln!(out, "const {name}_DATA: &[u8] = &{data:?};").unwrap();
+    writeln!(out, "pub const {name}: MonoFont<'static> = MonoFont {{").unwrap();
+    writeln!(out, "    image: ImageRaw::new({name}_DATA, {image_width}),").unwrap();
+    writeln!(
+        out,
+        "    glyph_mapping: &StrGlyphMapping::new({mapping:?}, {replacement}),"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    character_size: Size::new({}, {}),",
+        font.width, font.height
+    )
+    .unwrap();
+    writeln!(out, "    character_spacing: {spacing},").unwrap();
+    writeln!(out, "    baseline: {baseline},").unwrap();
+    writeln!(
+        out,
+        "    strikethrough: DecorationDimensions::new({}, 1),",
+        baseline / 2
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    underline: DecorationDimensions::new({}, 1),",
+        (baseline + 1).min(font.height - 1)
+    )
+    .unwrap();
+    writeln!(out, "}};").unwrap();
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=bdf");
+
+    let mut paths: Vec<_> = fs::read_dir("bdf")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|e| e == "bdf"))
+        .collect();
+    paths.sort();
+
+    let mut out = String::new();
+    for path in paths {
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let font = parse(name, &fs::read_to_string(&path).unwrap());
+        generate(&font, &mut out);
+    }
+
+    let path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("fonts.rs");
+    fs::write(path, out).unwrap();
+}
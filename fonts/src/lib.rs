@@ -0,0 +1,99 @@
+#![no_std]
+
+// fonts in the style of the ones the 3310 shipped with. each is generated by build.rs from the BDF
+// file of the same name under bdf/, so glyphs can be edited with any BDF editor:
+//
+// SMALL - 3x5 letters, for squeezing a lot onto one line
+// LARGE - 5x7 letters, for menus and messages
+// BOLD - LARGE one pixel heavier, for titles
+// CLOCK - seven segment digits, `:`, `/` and `-` for the clock
+use embedded_graphics::{
+    geometry::Size,
+    image::ImageRaw,
+    mono_font::{DecorationDimensions, MonoFont, mapping::StrGlyphMapping},
+};
+
+include!(concat!(env!("OUT_DIR"), "/fonts.rs"));
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::MonoTextStyle,
+        pixelcolor::BinaryColor,
+        prelude::*,
+        text::{Baseline, Text},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_printable_ascii() {
+        for font in [SMALL, LARGE, BOLD] {
+            let replacement = font.glyph_mapping.index('?');
+            for c in ' '..='~' {
+                assert!(c == '?' || font.glyph_mapping.index(c) != replacement);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sizes() {
+        assert_eq!(SMALL.character_size, Size::new(3, 6));
+        assert_eq!(LARGE.character_size, Size::new(5, 9));
+        assert_eq!(BOLD.character_size, Size::new(6, 9));
+        assert_eq!(CLOCK.character_size, Size::new(9, 14));
+        assert_eq!(LARGE.baseline, 6);
+        assert_eq!(CLOCK.baseline, 13);
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut display = MockDisplay::new();
+        Text::with_baseline(
+            "H1",
+            Point::zero(),
+            MonoTextStyle::new(&LARGE, BinaryColor::On),
+            Baseline::Top,
+        )
+        .draw(&mut display)
+        .unwrap();
+        display.assert_pattern(&[
+            "#   #   #  ",
+            "#   #  ##  ",
+            "#   #   #  ",
+            "#####   #  ",
+            "#   #   #  ",
+            "#   #   #  ",
+            "#   #  ### ",
+        ]);
+    }
+
+    #[test]
+    fn test_clock_digit() {
+        let mut display = MockDisplay::new();
+        Text::with_baseline(
+            "4",
+            Point::zero(),
+            MonoTextStyle::new(&CLOCK, BinaryColor::On),
+            Baseline::Top,
+        )
+        .draw(&mut display)
+        .unwrap();
+        display.assert_pattern(&[
+            "         ",
+            "##     ##",
+            "##     ##",
+            "##     ##",
+            "##     ##",
+            "##     ##",
+            "#########",
+            " ########",
+            "       ##",
+            "       ##",
+            "       ##",
+            "       ##",
+            "       ##",
+        ]);
+    }
+}
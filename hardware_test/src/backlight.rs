@@ -1,12 +1,9 @@
 use crate::Status;
 
 #[derive(Clone, PartialEq)]
-pub struct BacklightTest<'a>(
-    shared::console::Console<'a>,
-    shared::confirmation::Confirmation,
-);
+pub struct BacklightTest(shared::console::Console, shared::confirmation::Confirmation);
 
-impl BacklightTest<'_> {
+impl BacklightTest {
     pub fn new() -> Self {
         Self(
             shared::console::Console::new(),
@@ -15,13 +12,13 @@ impl BacklightTest<'_> {
     }
 }
 
-impl Default for BacklightTest<'_> {
+impl Default for BacklightTest {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl BacklightTest<'_> {
+impl BacklightTest {
     pub async fn run(&mut self, device: &mut impl shared::Device) -> Status {
         device.on();
         match self.1.run(device).await {
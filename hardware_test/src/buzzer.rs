@@ -1,12 +1,9 @@
 use crate::Status;
 
 #[derive(Clone, PartialEq)]
-pub struct BuzzerTest<'a>(
-    shared::console::Console<'a>,
-    shared::confirmation::Confirmation,
-);
+pub struct BuzzerTest(shared::console::Console, shared::confirmation::Confirmation);
 
-impl BuzzerTest<'_> {
+impl BuzzerTest {
     pub fn new() -> Self {
         Self(
             shared::console::Console::new(),
@@ -20,13 +17,13 @@ impl BuzzerTest<'_> {
     }
 }
 
-impl Default for BuzzerTest<'_> {
+impl Default for BuzzerTest {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl BuzzerTest<'_> {
+impl BuzzerTest {
     pub async fn run(&mut self, device: &mut impl shared::Device) -> Status {
         if let Err(_e) = device.unmute_buzzer() {
             return Status::Failed;
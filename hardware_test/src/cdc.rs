@@ -1,14 +1,14 @@
 use crate::Status;
 
 #[derive(Clone, PartialEq)]
-pub struct CdcTest<'a>(
-    shared::console::Console<'a>,
+pub struct CdcTest(
+    shared::console::Console,
     shared::confirmation::Confirmation,
     bool,
     bool,
 );
 
-impl CdcTest<'_> {
+impl CdcTest {
     pub fn new() -> Self {
         Self(
             shared::console::Console::new(),
@@ -24,7 +24,7 @@ impl CdcTest<'_> {
     }
 }
 
-impl Default for CdcTest<'_> {
+impl Default for CdcTest {
     fn default() -> Self {
         Self::new()
     }
@@ -33,7 +33,7 @@ impl Default for CdcTest<'_> {
 const SECRET: &str = "abxvn";
 const CDC_PROMPT: &str = "Enter secret: abxvn\n";
 
-impl CdcTest<'_> {
+impl CdcTest {
     pub async fn run(
         &mut self,
         device: &mut impl shared::Device,
@@ -2,7 +2,7 @@ use crate::Status;
 
 #[derive(Clone, PartialEq)]
 pub struct HidTest<'a>(
-    shared::console::Console<'a>,
+    shared::console::Console,
     shared::confirmation::Confirmation,
     bool,
     bool,
@@ -77,10 +77,7 @@ impl Default for HidTest<'_> {
 const SECRET: &str = "oevdhr";
 
 impl HidTest<'_> {
-    pub async fn run(
-        &mut self,
-        device: &mut impl shared::Device,
-    ) -> Status {
+    pub async fn run(&mut self, device: &mut impl shared::Device) -> Status {
         match (self.1.run(device).await, self.2, self.3) {
             (None, ..) => Status::InProgress(None),
             (Some(true), false, _) => {
@@ -1,34 +1,46 @@
-use enum_iterator::{first, last, next};
+use core::fmt::Write;
+
+use enum_iterator::{first, next};
 use shared::{Key, KeyEvent};
 
 use crate::Status;
 
 #[derive(Clone, PartialEq)]
-pub struct KeypadTest<'a>(Key, shared::console::Console<'a>);
+pub struct KeypadTest(Key, shared::console::Console, Option<(Key, u32)>);
 
-impl KeypadTest<'_> {
+impl KeypadTest {
     pub fn new(key: Key) -> Self {
-        Self(key, shared::console::Console::new())
+        Self(key, shared::console::Console::new(), None)
     }
 }
 
-impl Default for KeypadTest<'_> {
+impl Default for KeypadTest {
     fn default() -> Self {
         Self::new(first().unwrap())
     }
 }
 
-impl KeypadTest<'_> {
+impl KeypadTest {
     pub async fn run(&mut self, device: &mut impl shared::Device) -> Status {
-        self.1.draw(device, self.0.clone().into());
+        let mut text: heapless::String<48> = heapless::String::new();
+        let _ = text.push_str(self.0.clone().into());
+        if let Some((ref key, bounces)) = self.2 {
+            let key: &str = key.clone().into();
+            let _ = write!(text, "\n\n{} bounced {}", key, bounces);
+        }
+        self.1.draw(device, &text);
+
         match device.event().await {
             KeyEvent::Down(key) if key == self.0 => {
-                if self.0 == last::<Key>().unwrap() {
-                    return Status::Passed;
-                } else {
-                    self.0 = next(&self.0).unwrap();
+                self.2 = device.bounce_count(&key).map(|bounces| (key, bounces));
+                // power never reaches apps, the system layer keeps it for itself
+                match next(&self.0) {
+                    Some(Key::Power) | None => Status::Passed,
+                    Some(key) => {
+                        self.0 = key;
+                        Status::InProgress
+                    }
                 }
-                Status::InProgress
             }
             KeyEvent::Down(_key) => Status::Failed,
             _ => Status::InProgress,
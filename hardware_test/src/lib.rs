@@ -2,7 +2,6 @@
 #![feature(ascii_char)]
 #![no_std]
 
-use enum_iterator::Sequence;
 use shared::Application;
 
 mod keypad;
@@ -21,21 +20,27 @@ pub enum Status {
     InProgress,
 }
 
-#[derive(Clone, Sequence, PartialEq)]
-enum Test<'a> {
-    Keypad(KeypadTest<'a>),
-    Vibration(VibrationTest<'a>),
-    Buzzer(BuzzerTest<'a>),
-    Backlight(BacklightTest<'a>),
+#[derive(Clone, PartialEq)]
+enum Test {
+    Keypad(KeypadTest),
+    Vibration(VibrationTest),
+    Buzzer(BuzzerTest),
+    Backlight(BacklightTest),
 }
 
-pub struct HardwareTest<'a>(Status, shared::console::Console<'a>, Test<'a>);
+// results are kept in a log that can be looked back over on the device
+pub struct HardwareTest(Status, shared::console::Log<16, 32>, Test);
+
+impl HardwareTest {
+    fn record(&mut self, text: &str) {
+        log::info!("{}", text);
+        self.1.push(embassy_time::Instant::now(), text);
+    }
 
-impl HardwareTest<'_> {
     pub fn new(test: Status) -> Self {
         Self(
             test,
-            shared::console::Console::new(),
+            shared::console::Log::new(),
             Test::Keypad(Default::default()),
         )
     }
@@ -58,23 +63,24 @@ impl HardwareTest<'_> {
     }
 }
 
-impl Default for HardwareTest<'_> {
+impl Default for HardwareTest {
     fn default() -> Self {
         Self::new(Status::InProgress)
     }
 }
 
-impl Application for HardwareTest<'_> {
+impl Application for HardwareTest {
     async fn run(&mut self, device: &mut impl shared::Device) -> Result<(), ()> {
         match self.0.clone() {
             Status::InProgress => match self.2 {
                 Test::Keypad(ref mut test) => match test.run(device).await {
                     Status::Passed => {
-                        log::info!("Passed keypad");
+                        self.record("Passed keypad");
                         self.next();
                         Ok(())
                     }
                     Status::Failed => {
+                        self.record("Failed keypad");
                         self.0 = Status::Failed;
                         Ok(())
                     }
@@ -82,11 +88,12 @@ impl Application for HardwareTest<'_> {
                 },
                 Test::Vibration(ref mut test) => match test.run(device).await {
                     Status::Passed => {
-                        log::info!("Passed vibration");
+                        self.record("Passed vibration");
                         self.next();
                         Ok(())
                     }
                     Status::Failed => {
+                        self.record("Failed vibration");
                         self.0 = Status::Failed;
                         Ok(())
                     }
@@ -94,11 +101,12 @@ impl Application for HardwareTest<'_> {
                 },
                 Test::Buzzer(ref mut test) => match test.run(device).await {
                     Status::Passed => {
-                        log::info!("Passed buzzer");
+                        self.record("Passed buzzer");
                         self.next();
                         Ok(())
                     }
                     Status::Failed => {
+                        self.record("Failed buzzer");
                         self.0 = Status::Failed;
                         Ok(())
                     }
@@ -106,26 +114,20 @@ impl Application for HardwareTest<'_> {
                 },
                 Test::Backlight(ref mut test) => match test.run(device).await {
                     Status::Passed => {
-                        log::info!("Passed backlight");
+                        self.record("Passed backlight");
                         self.next();
                         Ok(())
                     }
                     Status::Failed => {
+                        self.record("Failed backlight");
                         self.0 = Status::Failed;
                         Ok(())
                     }
                     _ => Ok(()),
                 },
             },
-            Status::Passed => {
-                log::info!("Passed all tests");
-                self.1.draw(device, "Passed");
-                embassy_time::Timer::after_millis(10).await;
-                Ok(())
-            }
-            Status::Failed => {
-                log::info!("Failed");
-                self.1.draw(device, "Failed");
+            Status::Passed | Status::Failed => {
+                self.1.run(device).await;
                 Ok(())
             }
         }
@@ -1,12 +1,9 @@
 use crate::Status;
 
 #[derive(Clone, PartialEq)]
-pub struct VibrationTest<'a>(
-    shared::console::Console<'a>,
-    shared::confirmation::Confirmation,
-);
+pub struct VibrationTest(shared::console::Console, shared::confirmation::Confirmation);
 
-impl VibrationTest<'_> {
+impl VibrationTest {
     pub fn new() -> Self {
         Self(
             shared::console::Console::new(),
@@ -15,13 +12,13 @@ impl VibrationTest<'_> {
     }
 }
 
-impl Default for VibrationTest<'_> {
+impl Default for VibrationTest {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl VibrationTest<'_> {
+impl VibrationTest {
     pub async fn run(&mut self, device: &mut impl shared::Device) -> Status {
         device.start_vibrating();
         match self.1.run(device).await {
@@ -4,26 +4,99 @@
 
 use core::fmt::Debug;
 
+use embassy_time::{Duration, Instant};
 use embedded_graphics::{
     Drawable,
     draw_target::{DrawTarget, DrawTargetExt},
-    image::{Image, ImageRaw, ImageRawBE},
+    image::Image,
     pixelcolor::BinaryColor,
     prelude::*,
     primitives::Rectangle,
 };
-use shared::{Application, multitap, multitap::Case};
+use shared::{Application, multitap, multitap::Case, t9};
 
-pub struct Keyboard<'a, const N: usize> {
-    textbox: shared::textbox::Textbox<'a, heapless::String<N>>,
+const BLINK: Duration = Duration::from_millis(500);
+
+pub struct Keyboard<const N: usize> {
+    textbox: shared::textbox::Textbox<heapless::String<N>>,
     case: crate::multitap::Case,
+    t9: t9::T9<8>,
+    predictive: bool,
+    // length of the word at the end of the buffer that predictive text may still replace
+    composing: usize,
+    // when the cursor next blinks, kept here so that keys in between don't put it off
+    next_blink: Instant,
 }
 
-impl<'a, const N: usize> Keyboard<'a, N> {
+impl<const N: usize> Keyboard<N> {
     pub fn new(buffer: heapless::String<N>) -> Self {
         Self {
             textbox: shared::textbox::Textbox::new(buffer),
             case: crate::multitap::Case::Upper,
+            t9: t9::T9::new(),
+            predictive: false,
+            composing: 0,
+            next_blink: Instant::MIN,
+        }
+    }
+
+    fn blink<D: DrawTarget<Color = BinaryColor>>(&mut self, draw_target: &mut D) {
+        let mut translated = draw_target.translated(Point::new(0, 20));
+        let mut clipped = translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
+        self.textbox.blink(&mut clipped);
+    }
+
+    fn compose<D: DrawTarget<Color = BinaryColor>>(
+        &mut self,
+        draw_target: &mut D,
+        word: &str,
+        decided: bool,
+    ) {
+        let mut translated = draw_target.translated(Point::new(0, 20));
+        let mut clipped = translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
+        for _ in 0..self.composing {
+            self.textbox.backspace(&mut clipped);
+        }
+        for c in word.chars() {
+            self.textbox.push(&mut clipped, c, !decided);
+        }
+        self.composing = if decided { 0 } else { word.chars().count() };
+    }
+
+    fn predictive_event<D: DrawTarget<Color = BinaryColor>>(
+        &mut self,
+        draw_target: &mut D,
+        event: Option<t9::Event>,
+    ) {
+        match event {
+            Some(t9::Event::Composing(word)) | Some(t9::Event::Spelling(word)) => {
+                self.compose(draw_target, &word, false);
+            }
+            Some(t9::Event::Decided(word)) => self.compose(draw_target, &word, true),
+            Some(t9::Event::Backspace) => {
+                let mut translated = draw_target.translated(Point::new(0, 20));
+                let mut clipped =
+                    translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
+                self.textbox.backspace(&mut clipped);
+            }
+            Some(t9::Event::CursorLeft) => {
+                let mut translated = draw_target.translated(Point::new(0, 20));
+                let mut clipped =
+                    translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
+                self.textbox.cursor_left(&mut clipped);
+            }
+            Some(t9::Event::CursorRight) => {
+                let mut translated = draw_target.translated(Point::new(0, 20));
+                let mut clipped =
+                    translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
+                self.textbox.cursor_right(&mut clipped);
+            }
+            Some(t9::Event::TogglePredictive) => {
+                let flushed = self.t9.flush();
+                self.predictive_event(draw_target, flushed);
+                self.predictive = false;
+            }
+            None => {}
         }
     }
 
@@ -31,72 +104,19 @@ impl<'a, const N: usize> Keyboard<'a, N> {
     where
         <D as DrawTarget>::Error: Debug,
     {
-        let pencil = [
-            0b11101010,
-            0b1011_1111,
-            0b11110101,
-            0b0101_1111,
-            0b11111010,
-            0b1010_1111,
-            0b11111101,
-            0b0110_1111,
-            0b11111111,
-            0b0000_1111,
-        ];
-        let raw: ImageRawBE<BinaryColor> = ImageRaw::new(&pencil, 16);
-        let image = Image::new(&raw, Point::zero());
+        let image = Image::new(&assets::PENCIL, Point::zero());
         image.draw(draw_target).unwrap();
         let icon = match self.case {
-            Case::Upper => [
-                0b1000_1100,
-                0b0011_1000,
-                0b0010_0100,
-                0b1001_0011,
-                0b0010_0100,
-                0b0011_0011,
-                0b0000_0100,
-                0b1001_0011,
-                0b0010_0100,
-                0b1001_0011,
-                0b0010_0100,
-                0b0011_1000,
-            ],
-            Case::Lower => [
-                0b1111_1100,
-                0b1111_1111,
-                0b1111_1100,
-                0b1111_1111,
-                0b1000_0100,
-                0b0011_1000,
-                0b0010_0100,
-                0b1001_0011,
-                0b0010_0100,
-                0b1001_0011,
-                0b1000_0100,
-                0b0011_1000,
-            ],
-            Case::Number => [
-                0b1011_1011,
-                0b0001_1111,
-                0b0011_0101,
-                0b1101_1111,
-                0b1011_1101,
-                0b1101_1111,
-                0b1011_1101,
-                0b1011_1111,
-                0b1011_1011,
-                0b1101_1111,
-                0b0001_0001,
-                0b0011_1111,
-            ],
+            Case::Upper => &assets::CASE_UPPER,
+            Case::Lower => &assets::CASE_LOWER,
+            Case::Number => &assets::CASE_NUMBER,
         };
-        let raw: ImageRawBE<BinaryColor> = ImageRaw::new(&icon, 16);
-        let image = Image::new(&raw, Point::new(16, 0));
+        let image = Image::new(icon, Point::new(16, 0));
         image.draw(draw_target).unwrap();
     }
 }
 
-impl<const N: usize> Application for Keyboard<'_, N> {
+impl<const N: usize> Application for Keyboard<N> {
     async fn run(&mut self, device: &mut impl shared::Device) -> Result<(), ()> {
         let mut multitap = crate::multitap::MultiTap::new(1500);
         if let Some(crate::multitap::Event::Case(case)) = multitap.event(device).await {
@@ -107,20 +127,37 @@ impl<const N: usize> Application for Keyboard<'_, N> {
         let mut translated = device.translated(Point::new(0, 20));
         let mut clipped = translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
         self.textbox.draw(&mut clipped, None, false);
+        self.next_blink = Instant::now() + BLINK;
 
         loop {
+            if self.next_blink <= Instant::now() {
+                self.blink(device);
+                self.next_blink = Instant::now() + BLINK;
+            }
+
+            if self.predictive {
+                if let Some(event) = self.t9.event_before(device, self.next_blink).await {
+                    self.predictive_event(device, event);
+                }
+                continue;
+            }
+
             // needs to be just waiting on keypad
             // does not need to be waiting on display as well
-            match multitap.event(device).await {
+            let Some(event) = multitap.event_before(device, self.next_blink).await else {
+                continue;
+            };
+            match event {
                 Some(crate::multitap::Event::ShowSpecialCharacters) => {
                     let char_str = shared::character_select::process(device).await.unwrap();
+                    multitap.reset();
                     let the_char = char_str.chars().next().unwrap();
 
                     // stack is necessary here?
                     // or at least clear
                     // + redrawing decoration
                     // + redrawing all of buffer
-                    let _ = device.clear(BinaryColor::On);
+                    shared::theme::current().clear(device);
                     let mut translated = device.translated(Point::new(0, 20));
                     let mut clipped =
                         translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
@@ -132,7 +169,48 @@ impl<const N: usize> Application for Keyboard<'_, N> {
                     self.case = case;
                     self.draw_titlebar(device);
                 }
-                Some(crate::multitap::Event::Decided(core::ascii::Char::Backspace)) => {
+                Some(crate::multitap::Event::TogglePredictive) => {
+                    self.predictive = true;
+                }
+                Some(crate::multitap::Event::ChooseLocale) => {
+                    let mut locales = [
+                        multitap::Locale::English,
+                        multitap::Locale::German,
+                        multitap::Locale::French,
+                        multitap::Locale::Nordic,
+                        multitap::Locale::Greek,
+                        multitap::Locale::Russian,
+                    ];
+                    let mut menu =
+                        shared::menu::Menu::new(&mut locales, Some("Select"), |a, b, c, d, e| {
+                            shared::menu::row_render(a, b, c, d, e)
+                        });
+                    if let Some(locale) = menu.process(device).await {
+                        multitap.set_locale(locale);
+                        self.textbox.set_font(locale.keymap().font);
+                    }
+                    multitap.reset();
+
+                    shared::theme::current().clear(device);
+                    self.draw_titlebar(device);
+                    let mut translated = device.translated(Point::new(0, 20));
+                    let mut clipped =
+                        translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
+                    self.textbox.draw(&mut clipped, None, false);
+                }
+                Some(crate::multitap::Event::CursorLeft) => {
+                    let mut translated = device.translated(Point::new(0, 20));
+                    let mut clipped =
+                        translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
+                    self.textbox.cursor_left(&mut clipped);
+                }
+                Some(crate::multitap::Event::CursorRight) => {
+                    let mut translated = device.translated(Point::new(0, 20));
+                    let mut clipped =
+                        translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
+                    self.textbox.cursor_right(&mut clipped);
+                }
+                Some(crate::multitap::Event::Decided(multitap::BACKSPACE)) => {
                     let mut translated = device.translated(Point::new(0, 20));
                     let mut clipped =
                         translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
@@ -142,13 +220,13 @@ impl<const N: usize> Application for Keyboard<'_, N> {
                     let mut translated = device.translated(Point::new(0, 20));
                     let mut clipped =
                         translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
-                    self.textbox.push(&mut clipped, c.into(), false);
+                    self.textbox.push(&mut clipped, c, false);
                 }
                 Some(crate::multitap::Event::Tentative(c)) => {
                     let mut translated = device.translated(Point::new(0, 20));
                     let mut clipped =
                         translated.clipped(&Rectangle::new(Point::zero(), Size::new(84, 20)));
-                    self.textbox.push(&mut clipped, c.into(), true);
+                    self.textbox.push(&mut clipped, c, true);
                 }
                 _ => {}
             }
@@ -0,0 +1,92 @@
+#![no_std]
+
+// a frame buffer laid out the way monochrome LCD controllers such as the PCD8544 and ST7567 take
+// it: rows of 8 pixels ("pages"), each byte one column of a page with its lowest bit at the top.
+// drivers keep one of these, draw into it, and send it page by page when flushed.
+
+use embedded_graphics_core::{
+    geometry::{Point, Size},
+    pixelcolor::BinaryColor,
+};
+
+#[cfg(feature = "mock")]
+pub mod mock;
+
+#[derive(Debug, PartialEq)]
+pub struct OutOfBounds;
+
+pub struct PageBuffer<const WIDTH: usize, const PAGES: usize> {
+    pages: [[u8; WIDTH]; PAGES],
+}
+
+impl<const WIDTH: usize, const PAGES: usize> Default for PageBuffer<WIDTH, PAGES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WIDTH: usize, const PAGES: usize> PageBuffer<WIDTH, PAGES> {
+    pub const fn new() -> Self {
+        Self {
+            pages: [[0x00; WIDTH]; PAGES],
+        }
+    }
+
+    pub const fn size(&self) -> Size {
+        Size::new(WIDTH as u32, (PAGES * 8) as u32)
+    }
+
+    pub fn set_pixel(&mut self, point: Point, color: BinaryColor) -> Result<(), OutOfBounds> {
+        let x = usize::try_from(point.x).map_err(|_err| OutOfBounds)?;
+        let y = usize::try_from(point.y).map_err(|_err| OutOfBounds)?;
+        let byte = self
+            .pages
+            .get_mut(y / 8)
+            .and_then(|page| page.get_mut(x))
+            .ok_or(OutOfBounds)?;
+        let bit = 0x01 << (y % 8);
+        if color.is_on() {
+            *byte |= bit;
+        } else {
+            *byte &= !bit;
+        }
+        Ok(())
+    }
+
+    // top to bottom, with their index
+    pub fn pages(&self) -> impl Iterator<Item = (u8, &[u8; WIDTH])> {
+        (0..).zip(self.pages.iter())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_set_pixel() {
+        let mut buffer = PageBuffer::<4, 2>::new();
+        assert_eq!(buffer.size(), Size::new(4, 16));
+        buffer.set_pixel(Point::new(1, 0), BinaryColor::On).unwrap();
+        buffer.set_pixel(Point::new(1, 7), BinaryColor::On).unwrap();
+        buffer.set_pixel(Point::new(3, 9), BinaryColor::On).unwrap();
+        buffer
+            .set_pixel(Point::new(1, 0), BinaryColor::Off)
+            .unwrap();
+        let pages: [(u8, &[u8; 4]); 2] = [(0, &[0, 0x80, 0, 0]), (1, &[0, 0, 0, 0x02])];
+        assert!(buffer.pages().eq(pages));
+
+        assert_eq!(
+            buffer.set_pixel(Point::new(4, 0), BinaryColor::On),
+            Err(OutOfBounds)
+        );
+        assert_eq!(
+            buffer.set_pixel(Point::new(0, 16), BinaryColor::On),
+            Err(OutOfBounds)
+        );
+        assert_eq!(
+            buffer.set_pixel(Point::new(-1, 0), BinaryColor::On),
+            Err(OutOfBounds)
+        );
+    }
+}
@@ -0,0 +1,43 @@
+extern crate std;
+
+use std::vec::Vec;
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use embedded_hal::digital::{ErrorType, OutputPin};
+
+// what went over the interface, as (command?, bytes)
+#[derive(Default)]
+pub struct Interface(pub Vec<(bool, Vec<u8>)>);
+
+impl WriteOnlyDataCommand for Interface {
+    fn send_commands(&mut self, cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+        if let DataFormat::U8(bytes) = cmd {
+            self.0.push((true, bytes.to_vec()));
+        }
+        Ok(())
+    }
+
+    fn send_data(&mut self, buf: DataFormat<'_>) -> Result<(), DisplayError> {
+        if let DataFormat::U8(bytes) = buf {
+            self.0.push((false, bytes.to_vec()));
+        }
+        Ok(())
+    }
+}
+
+// an output nobody is listening to
+pub struct Pin;
+
+impl ErrorType for Pin {
+    type Error = core::convert::Infallible;
+}
+
+impl OutputPin for Pin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
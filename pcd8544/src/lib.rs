@@ -1,7 +1,7 @@
 #![no_std]
 
-// TODO: documentation
-// TODO: test
+// driver for the PCD8544, the controller of the Nokia 5110's 84x48 panel. drawing only changes
+// the buffer; nothing reaches the panel until `flush`
 
 use core::cmp;
 
@@ -9,11 +9,12 @@ use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
 use embedded_graphics_core::{
     Pixel,
     draw_target::DrawTarget,
-    geometry::{Dimensions, Point, Size},
+    geometry::{Dimensions, Point},
     pixelcolor::BinaryColor,
     primitives::rectangle::Rectangle,
 };
 use embedded_hal::{delay::DelayNs, digital::OutputPin};
+use page_buffer::PageBuffer;
 
 const WIDTH: usize = 84;
 const HEIGHT: usize = 48;
@@ -26,6 +27,8 @@ const SET_X_ADDR: u8 = 0x80;
 const SET_BIAS: u8 = 0x10;
 const SET_VOP: u8 = 0x80;
 const DISPLAY_INVERTED: u8 = 0x5;
+const DISPLAY_BLANK: u8 = 0x0;
+const POWER_DOWN: u8 = 0x04;
 
 #[derive(Debug)]
 pub enum Error<PinE> {
@@ -40,7 +43,8 @@ where
 {
     display_interface: DI,
     reset: RST,
-    buffer: [u8; (WIDTH * HEIGHT) >> 3],
+    buffer: PageBuffer<WIDTH, { HEIGHT >> 3 }>,
+    inverted: bool,
 }
 
 impl<DI, RST, PinE> Driver<DI, RST, PinE>
@@ -52,7 +56,8 @@ where
         Self {
             display_interface,
             reset,
-            buffer: [0x00; (WIDTH * HEIGHT) >> 3],
+            buffer: PageBuffer::new(),
+            inverted: false,
         }
     }
 
@@ -77,6 +82,7 @@ where
     }
 
     pub fn invert_display(&mut self, i: bool) -> Result<(), DisplayError> {
+        self.inverted = i;
         self.display_interface
             .send_commands(DataFormat::U8(&[FUNCTION_SET]))?;
         if i {
@@ -89,7 +95,7 @@ where
         Ok(())
     }
 
-    pub fn init(&mut self, delay_source: &mut impl DelayNs) -> Result<(), DisplayError> {
+    pub fn init<D: DelayNs>(&mut self, delay_source: &mut D) -> Result<(), DisplayError> {
         let _ = self.reset.set_low();
         delay_source.delay_us(1);
         let _ = self.reset.set_high();
@@ -106,16 +112,30 @@ where
         Ok(())
     }
 
+    // blanks the panel and stops its oscillator and voltage generator. the buffer is kept so that
+    // power_up can put back what was shown
+    pub fn power_down(&mut self) -> Result<(), DisplayError> {
+        self.display_interface
+            .send_commands(DataFormat::U8(&[FUNCTION_SET]))?;
+        self.display_interface
+            .send_commands(DataFormat::U8(&[DISPLAY_CONTROL | DISPLAY_BLANK]))?;
+        self.display_interface
+            .send_commands(DataFormat::U8(&[FUNCTION_SET | POWER_DOWN]))
+    }
+
+    pub fn power_up(&mut self) -> Result<(), DisplayError> {
+        self.invert_display(self.inverted)?;
+        self.flush()
+    }
+
     pub fn flush(&mut self) -> Result<(), DisplayError> {
-        for page in 0..(HEIGHT >> 3) {
+        for (page, data) in self.buffer.pages() {
             self.display_interface
-                .send_commands(DataFormat::U8(&[SET_Y_ADDR | (page as u8)]))?;
+                .send_commands(DataFormat::U8(&[SET_Y_ADDR | page]))?;
             self.display_interface
                 .send_commands(DataFormat::U8(&[SET_X_ADDR]))?;
 
-            self.display_interface.send_data(DataFormat::U8(
-                &self.buffer[(WIDTH * page)..(WIDTH * (page + 1))],
-            ))?;
+            self.display_interface.send_data(DataFormat::U8(data))?;
         }
         self.display_interface
             .send_commands(DataFormat::U8(&[SET_Y_ADDR]))?;
@@ -136,29 +156,13 @@ where
     where
         I: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        for Pixel(coord, color) in pixels.into_iter() {
-            if !(0..WIDTH
-                .try_into()
-                .map_err(|_| Error::DisplayError(DisplayError::OutOfBoundsError))?)
-                .contains(&coord.x)
-                || !(0..HEIGHT
-                    .try_into()
-                    .map_err(|_| Error::DisplayError(DisplayError::OutOfBoundsError))?)
-                    .contains(&coord.y)
-            {
-                return Err(Error::DisplayError(DisplayError::OutOfBoundsError));
-            }
-
-            let mask = !(0x01 << (coord.y % 8));
-            self.buffer[coord.x as usize + ((coord.y as usize) >> 3) * WIDTH] &= mask;
-
-            if color.is_on() {
-                self.buffer[coord.x as usize + ((coord.y as usize) >> 3) * WIDTH] |=
-                    0x01 << ((coord.y as usize) % 8);
-            }
+        for Pixel(point, color) in pixels {
+            self.buffer
+                .set_pixel(point, color)
+                .map_err(|page_buffer::OutOfBounds| {
+                    Error::DisplayError(DisplayError::OutOfBoundsError)
+                })?;
         }
-        let _ = self.flush();
-
         Ok(())
     }
 }
@@ -169,9 +173,44 @@ where
     RST: OutputPin<Error = PinE>,
 {
     fn bounding_box(&self) -> Rectangle {
-        Rectangle::new(
-            Point::new(0, 0),
-            Size::new(WIDTH.try_into().unwrap(), HEIGHT.try_into().unwrap()),
-        )
+        Rectangle::new(Point::zero(), self.buffer.size())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use embedded_graphics_core::geometry::Size;
+    use page_buffer::mock::{Interface, Pin};
+
+    use super::*;
+
+    #[test]
+    fn test_flush() {
+        let mut driver = Driver::new(Interface::default(), Pin);
+        assert_eq!(driver.bounding_box().size, Size::new(84, 48));
+        driver
+            .draw_iter([Pixel(Point::new(2, 9), BinaryColor::On)])
+            .unwrap();
+        // drawing alone sends nothing
+        assert!(driver.display_interface.0.is_empty());
+        assert!(
+            driver
+                .draw_iter([Pixel(Point::new(84, 0), BinaryColor::On)])
+                .is_err()
+        );
+
+        driver.flush().unwrap();
+        let sent = &driver.display_interface.0;
+        assert_eq!(sent.len(), 6 * 3 + 1);
+        assert_eq!(sent[3], (true, vec![SET_Y_ADDR | 1]));
+        assert_eq!(sent[4], (true, vec![SET_X_ADDR]));
+        let mut page = vec![0; 84];
+        page[2] = 0x02;
+        assert_eq!(sent[5], (false, page));
+        assert_eq!(sent[18], (true, vec![SET_Y_ADDR]));
     }
 }
@@ -3,15 +3,8 @@
 #![feature(ascii_char)]
 #![feature(iter_advance_by)]
 
-use embassy_futures::select::Either;
 use embedded_graphics::{
-    Drawable,
-    draw_target::DrawTargetExt,
-    mono_font::ascii::{FONT_6X9, FONT_6X10},
-    pixelcolor::BinaryColor,
-    prelude::*,
-    primitives::{Primitive, PrimitiveStyle, Rectangle},
-    text::Text,
+    Drawable, draw_target::DrawTargetExt, prelude::*, primitives::Rectangle, text::Text,
 };
 use shared::Application;
 
@@ -53,13 +46,12 @@ impl Application for Ringtones<'_> {
     async fn run(&mut self, device: &mut impl shared::Device) -> Result<(), ()> {
         let mut menu = shared::menu::Menu::new(&mut self.songs, Some("PLAY"), |a, b, c, d, e| {
             shared::menu::row_render(a, b, c, d, e)
-        });
+        })
+        .with_filter();
         loop {
             if let Some(mut song) = menu.process(device).await {
-                let _ = device
-                    .bounding_box()
-                    .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
-                    .draw(device);
+                let theme = shared::theme::current();
+                theme.clear(device);
 
                 let text_style = embedded_graphics::text::TextStyleBuilder::new()
                     .alignment(embedded_graphics::text::Alignment::Center)
@@ -69,10 +61,7 @@ impl Application for Ringtones<'_> {
                 let _ = Text::with_text_style(
                     "Playing",
                     Point::new(42, 0),
-                    embedded_graphics::mono_font::MonoTextStyleBuilder::new()
-                        .text_color(BinaryColor::Off)
-                        .font(&FONT_6X10)
-                        .build(),
+                    theme.text_style(),
                     text_style,
                 )
                 .draw(device);
@@ -80,27 +69,11 @@ impl Application for Ringtones<'_> {
                 let _ = Text::with_text_style(
                     song.title,
                     Point::new(42, 10),
-                    embedded_graphics::mono_font::MonoTextStyleBuilder::new()
-                        .text_color(BinaryColor::Off)
-                        .font(&FONT_6X10)
-                        .build(),
-                    text_style,
-                )
-                .draw(device);
-                let text_style = embedded_graphics::text::TextStyleBuilder::new()
-                    .alignment(embedded_graphics::text::Alignment::Center)
-                    .baseline(embedded_graphics::text::Baseline::Bottom)
-                    .build();
-                let _ = Text::with_text_style(
-                    "STOP",
-                    Point::new(42, 47),
-                    embedded_graphics::mono_font::MonoTextStyleBuilder::new()
-                        .text_color(BinaryColor::Off)
-                        .font(&FONT_6X9)
-                        .build(),
+                    theme.text_style(),
                     text_style,
                 )
                 .draw(device);
+                shared::widget::soft_key::SoftKey::new("STOP").draw(device);
 
                 let mut textbox = shared::textbox::Textbox::new(song.note_source);
                 loop {
@@ -119,13 +92,9 @@ impl Application for Ringtones<'_> {
                         } else {
                             let _ = device.mute_buzzer();
                         }
-                        if let Either::First(shared::KeyEvent::Down(_)) =
-                            embassy_futures::select::select(
-                                device.event(),
-                                embassy_time::Timer::after_millis(note.duration().into()),
-                            )
-                            .await
-                        {
+                        let until = embassy_time::Instant::now()
+                            + embassy_time::Duration::from_millis(note.duration().into());
+                        if let Some(shared::KeyEvent::Down(_)) = device.event_before(until).await {
                             let _ = device.mute_buzzer();
                             break;
                         }
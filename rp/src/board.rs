@@ -0,0 +1,92 @@
+use embassy_rp::{Peri, gpio::AnyPin};
+
+#[cfg(feature = "rev-1")]
+mod rev_1;
+
+#[cfg(not(feature = "rev-1"))]
+compile_error!("a board revision feature (e.g. `rev-1`) must be enabled");
+
+#[cfg(feature = "rev-1")]
+pub(crate) use rev_1::split_board;
+#[cfg(feature = "rev-1")]
+pub use rev_1::{
+    BacklightPin, BacklightSlice, BuzzerPin, BuzzerSlice, DisplayClk, DisplayMiso, DisplayMosi,
+    DisplaySpi, RtcI2c, RtcScl, RtcSda, VibrationMotorPin, VibrationMotorSlice,
+};
+#[cfg(all(feature = "rev-1", feature = "matrix-keypad"))]
+pub use rev_1::{KEYPAD_COLUMNS, KEYPAD_ROWS};
+
+#[cfg(not(feature = "matrix-keypad"))]
+pub struct KeypadPins<'a> {
+    pub cancel: Peri<'a, AnyPin>,
+    pub select: Peri<'a, AnyPin>,
+    pub up: Peri<'a, AnyPin>,
+    pub down: Peri<'a, AnyPin>,
+    pub one: Peri<'a, AnyPin>,
+    pub two: Peri<'a, AnyPin>,
+    pub three: Peri<'a, AnyPin>,
+    pub four: Peri<'a, AnyPin>,
+    pub five: Peri<'a, AnyPin>,
+    pub six: Peri<'a, AnyPin>,
+    pub seven: Peri<'a, AnyPin>,
+    pub eight: Peri<'a, AnyPin>,
+    pub nine: Peri<'a, AnyPin>,
+    pub asterisk: Peri<'a, AnyPin>,
+    pub zero: Peri<'a, AnyPin>,
+    pub hash: Peri<'a, AnyPin>,
+    pub power: Option<Peri<'a, AnyPin>>,
+}
+
+// the key at each crossing of a row and a column, if there is one there
+#[cfg(feature = "matrix-keypad")]
+pub struct KeypadPins<'a> {
+    pub rows: [Peri<'a, AnyPin>; KEYPAD_ROWS],
+    pub columns: [Peri<'a, AnyPin>; KEYPAD_COLUMNS],
+    pub keymap: [[Option<shared::Key>; KEYPAD_COLUMNS]; KEYPAD_ROWS],
+}
+
+pub struct DisplayPins<'a> {
+    pub spi: Peri<'a, DisplaySpi>,
+    pub clk: Peri<'a, DisplayClk>,
+    pub mosi: Peri<'a, DisplayMosi>,
+    pub miso: Peri<'a, DisplayMiso>,
+    pub cs: Peri<'a, AnyPin>,
+    pub dc: Peri<'a, AnyPin>,
+    pub rst: Peri<'a, AnyPin>,
+}
+
+pub struct BuzzerPins<'a> {
+    pub slice: Peri<'a, BuzzerSlice>,
+    pub pin: Peri<'a, BuzzerPin>,
+}
+
+pub struct BacklightPins<'a> {
+    pub slice: Peri<'a, BacklightSlice>,
+    pub pin: Peri<'a, BacklightPin>,
+}
+
+pub struct VibrationMotorPins<'a> {
+    pub slice: Peri<'a, VibrationMotorSlice>,
+    pub pin: Peri<'a, VibrationMotorPin>,
+}
+
+pub struct RtcPins<'a> {
+    pub i2c: Peri<'a, RtcI2c>,
+    pub sda: Peri<'a, RtcSda>,
+    pub scl: Peri<'a, RtcScl>,
+}
+
+// the pins `Device` drives itself
+pub struct DevicePins<'a> {
+    pub keypad: KeypadPins<'a>,
+    pub buzzer: BuzzerPins<'a>,
+    pub backlight: BacklightPins<'a>,
+    pub vibration_motor: VibrationMotorPins<'a>,
+    pub shutdown: Peri<'a, AnyPin>,
+}
+
+pub struct Board<'a> {
+    pub device: DevicePins<'a>,
+    pub display: DisplayPins<'a>,
+    pub rtc: RtcPins<'a>,
+}
@@ -0,0 +1,107 @@
+pub type DisplaySpi = embassy_rp::peripherals::SPI0;
+pub type DisplayClk = embassy_rp::peripherals::PIN_38;
+pub type DisplayMosi = embassy_rp::peripherals::PIN_39;
+pub type DisplayMiso = embassy_rp::peripherals::PIN_32;
+pub type BuzzerSlice = embassy_rp::peripherals::PWM_SLICE2;
+pub type BuzzerPin = embassy_rp::peripherals::PIN_21;
+pub type BacklightSlice = embassy_rp::peripherals::PWM_SLICE7;
+pub type BacklightPin = embassy_rp::peripherals::PIN_15;
+pub type VibrationMotorSlice = embassy_rp::peripherals::PWM_SLICE1;
+pub type VibrationMotorPin = embassy_rp::peripherals::PIN_2;
+pub type RtcI2c = embassy_rp::peripherals::I2C1;
+pub type RtcSda = embassy_rp::peripherals::PIN_46;
+pub type RtcScl = embassy_rp::peripherals::PIN_47;
+
+// rev 1 gives every key its own pin. with `matrix-keypad` it is taken to be rewired as a 4x4
+// matrix on the first eight of those pins: the navigation keys' pins drive the rows and the
+// pins of 1 to 4 read the columns. there is no room left for the power key
+#[cfg(feature = "matrix-keypad")]
+pub const KEYPAD_ROWS: usize = 4;
+#[cfg(feature = "matrix-keypad")]
+pub const KEYPAD_COLUMNS: usize = 4;
+
+// moves only the peripherals the board uses out of `$p`, leaving the rest (watchdog, USB, flash)
+// available to the caller
+macro_rules! split_board {
+    ($p:ident) => {
+        $crate::board::Board {
+            device: $crate::board::DevicePins {
+                #[cfg(not(feature = "matrix-keypad"))]
+                keypad: $crate::board::KeypadPins {
+                    cancel: $p.PIN_16.into(),
+                    select: $p.PIN_12.into(),
+                    up: $p.PIN_9.into(),
+                    down: $p.PIN_8.into(),
+                    one: $p.PIN_17.into(),
+                    two: $p.PIN_13.into(),
+                    three: $p.PIN_7.into(),
+                    four: $p.PIN_18.into(),
+                    five: $p.PIN_14.into(),
+                    six: $p.PIN_6.into(),
+                    seven: $p.PIN_19.into(),
+                    eight: $p.PIN_11.into(),
+                    nine: $p.PIN_5.into(),
+                    asterisk: $p.PIN_20.into(),
+                    zero: $p.PIN_10.into(),
+                    hash: $p.PIN_4.into(),
+                    // the power key goes to the power controller, and is read here through a wire
+                    // from the key to the spare GPIO 22. without the wire it reads as never pressed
+                    power: Some($p.PIN_22.into()),
+                },
+                #[cfg(feature = "matrix-keypad")]
+                keypad: $crate::board::KeypadPins {
+                    rows: [
+                        $p.PIN_16.into(),
+                        $p.PIN_12.into(),
+                        $p.PIN_9.into(),
+                        $p.PIN_8.into(),
+                    ],
+                    columns: [
+                        $p.PIN_17.into(),
+                        $p.PIN_13.into(),
+                        $p.PIN_7.into(),
+                        $p.PIN_18.into(),
+                    ],
+                    keymap: {
+                        use shared::Key::*;
+                        [
+                            [Some(One), Some(Two), Some(Three), Some(Up)],
+                            [Some(Four), Some(Five), Some(Six), Some(Down)],
+                            [Some(Seven), Some(Eight), Some(Nine), Some(Select)],
+                            [Some(Asterisk), Some(Zero), Some(Hash), Some(Cancel)],
+                        ]
+                    },
+                },
+                buzzer: $crate::board::BuzzerPins {
+                    slice: $p.PWM_SLICE2,
+                    pin: $p.PIN_21,
+                },
+                backlight: $crate::board::BacklightPins {
+                    slice: $p.PWM_SLICE7,
+                    pin: $p.PIN_15,
+                },
+                vibration_motor: $crate::board::VibrationMotorPins {
+                    slice: $p.PWM_SLICE1,
+                    pin: $p.PIN_2,
+                },
+                shutdown: $p.PIN_28.into(),
+            },
+            display: $crate::board::DisplayPins {
+                spi: $p.SPI0,
+                clk: $p.PIN_38,
+                mosi: $p.PIN_39,
+                miso: $p.PIN_32,
+                cs: $p.PIN_37.into(),
+                dc: $p.PIN_36.into(),
+                rst: $p.PIN_33.into(),
+            },
+            rtc: $crate::board::RtcPins {
+                i2c: $p.I2C1,
+                sda: $p.PIN_46,
+                scl: $p.PIN_47,
+            },
+        }
+    };
+}
+
+pub(crate) use split_board;
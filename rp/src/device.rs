@@ -1,90 +1,80 @@
-use core::cell::RefCell;
-
-use embassy_rp::{
-    peripherals::{
-        PIN_2, PIN_4, PIN_5, PIN_6, PIN_7, PIN_8, PIN_9, PIN_10, PIN_11, PIN_12, PIN_13, PIN_14,
-        PIN_15, PIN_16, PIN_17, PIN_18, PIN_19, PIN_20, PIN_21, PIN_33, PIN_36, PIN_37, PWM_SLICE2,
-        SPI0,
-    },
-    pwm::PwmError,
-    watchdog::Watchdog,
-};
-use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embassy_rp::{Peri, peripherals::FLASH, pwm::PwmError, watchdog::Watchdog};
 use embedded_graphics_core::{
     Pixel,
     pixelcolor::BinaryColor,
     prelude::{Dimensions, DrawTarget},
     primitives::Rectangle,
 };
+
+use crate::board::DevicePins;
+
 mod backlight;
 mod buzzer;
-mod display;
-mod keypad;
+pub mod display;
+pub mod keypad;
+mod power_button;
 mod vibration_motor;
 
-pub struct Device<'a> {
-    keypad: keypad::ContactKeypad<'a>,
+pub struct Device<'a, C: display::Controller> {
+    keypad: keypad::Keys<'a>,
     backlight: backlight::Light<'a>,
     vibration_motor: vibration_motor::Motor<'a>,
     buzzer: buzzer::Beeper<'a>,
-    display: display::Display<'a>,
+    power_button: power_button::Button<'a>,
+    display: C,
+    storage: crate::flash::Settings<'a>,
     watchdog: Watchdog,
 }
-use embassy_rp::Peri;
 
-unsafe impl Send for Device<'_> {}
+unsafe impl<C: display::Controller> Send for Device<'_, C> {}
 
-impl<'a> Device<'a> {
-    #[allow(clippy::too_many_arguments)]
+impl<'a, C: display::Controller> Device<'a, C> {
     pub fn new(
-        watchdog: embassy_rp::watchdog::Watchdog,
-        pin_2: Peri<'a, PIN_2>,
-        pin_4: Peri<'a, PIN_4>,
-        pin_5: Peri<'a, PIN_5>,
-        pin_6: Peri<'a, PIN_6>,
-        pin_7: Peri<'a, PIN_7>,
-        pin_8: Peri<'a, PIN_8>,
-        pin_9: Peri<'a, PIN_9>,
-        pin_10: Peri<'a, PIN_10>,
-        pin_11: Peri<'a, PIN_11>,
-        pin_12: Peri<'a, PIN_12>,
-        pin_13: Peri<'a, PIN_13>,
-        pin_14: Peri<'a, PIN_14>,
-        pin_15: Peri<'a, PIN_15>,
-        pin_16: Peri<'a, PIN_16>,
-        pin_17: Peri<'a, PIN_17>,
-        pin_18: Peri<'a, PIN_18>,
-        pin_19: Peri<'a, PIN_19>,
-        pin_20: Peri<'a, PIN_20>,
-        pin_21: Peri<'a, PIN_21>,
-        pin_33: Peri<'a, PIN_33>,
-        pin_36: Peri<'a, PIN_36>,
-        pin_37: Peri<'a, PIN_37>,
-        pwm_slice2: Peri<'a, PWM_SLICE2>,
-        spi_bus: &'a embassy_sync::blocking_mutex::Mutex<
-            NoopRawMutex,
-            RefCell<embassy_rp::spi::Spi<'a, SPI0, embassy_rp::spi::Blocking>>,
-        >,
-    ) -> Result<Self, display_interface::DisplayError> {
-        Ok(Self {
-            keypad: keypad::ContactKeypad::new(
-                pin_16, pin_12, pin_9, pin_8, pin_17, pin_13, pin_7, pin_18, pin_14, pin_6, pin_19,
-                pin_11, pin_5, pin_20, pin_10, pin_4,
-            ),
-            backlight: backlight::Light::new(pin_15),
-            vibration_motor: vibration_motor::Motor::new(pin_2),
-            buzzer: buzzer::Beeper::new(pwm_slice2, pin_21),
-            display: display::Display::new(spi_bus, pin_37, pin_36, pin_33)?,
+        watchdog: Watchdog,
+        pins: DevicePins<'a>,
+        debounce: keypad::Debounce,
+        display: C,
+        flash: Peri<'a, FLASH>,
+    ) -> Self {
+        Self {
+            keypad: keypad::new(pins.keypad, debounce),
+            backlight: backlight::Light::new(pins.backlight),
+            vibration_motor: vibration_motor::Motor::new(pins.vibration_motor),
+            buzzer: buzzer::Beeper::new(pins.buzzer),
+            power_button: power_button::Button::new(pins.shutdown),
+            display,
+            storage: crate::flash::Settings::new(flash),
             watchdog,
-        })
+        }
+    }
+}
+
+impl<C: display::Controller> shared::DisplayPower for Device<'_, C> {
+    fn power_down(&mut self) {
+        let _ = self.display.power_down();
+    }
+
+    fn power_up(&mut self) {
+        let _ = self.display.power_up();
     }
 }
 
-impl shared::Device for Device<'_> {
+impl<C: display::Controller> shared::Indicators for Device<'_, C> {}
+
+impl<C: display::Controller> shared::Storage for Device<'_, C> {
+    fn load(&mut self, key: &str, buffer: &mut [u8]) -> Option<usize> {
+        self.storage.load(key, buffer)
+    }
+
+    fn store(&mut self, key: &str, data: &[u8]) {
+        self.storage.store(key, data);
+    }
 }
-use shared::{Backlight, Buzzer, Keypad, Rtc, VibrationMotor};
 
-impl Backlight for Device<'_> {
+impl<C: display::Controller> shared::Device for Device<'_, C> {}
+use shared::{Backlight, Buzzer, Keypad, PowerButton, Rtc, Storage, VibrationMotor};
+
+impl<C: display::Controller> Backlight for Device<'_, C> {
     fn on(&mut self) {
         self.backlight.on();
     }
@@ -92,9 +82,13 @@ impl Backlight for Device<'_> {
     fn off(&mut self) {
         self.backlight.off();
     }
+
+    fn set_brightness(&mut self, level: u8) {
+        self.backlight.set_brightness(level);
+    }
 }
 
-impl Buzzer for Device<'_> {
+impl<C: display::Controller> Buzzer for Device<'_, C> {
     type Error = PwmError;
 
     fn mute_buzzer(&mut self) -> Result<(), Self::Error> {
@@ -114,7 +108,7 @@ impl Buzzer for Device<'_> {
     }
 }
 
-impl VibrationMotor for Device<'_> {
+impl<C: display::Controller> VibrationMotor for Device<'_, C> {
     fn start_vibrating(&mut self) {
         self.vibration_motor.start_vibrating();
     }
@@ -122,18 +116,31 @@ impl VibrationMotor for Device<'_> {
     fn stop_vibrating(&mut self) {
         self.vibration_motor.stop_vibrating();
     }
+
+    fn set_intensity(&mut self, level: u8) {
+        self.vibration_motor.set_intensity(level);
+    }
 }
 
-impl Keypad for Device<'_> {
+impl<C: display::Controller> Keypad for Device<'_, C> {
     async fn event(&mut self) -> shared::KeyEvent {
         self.keypad.event().await
     }
+
+    async fn timed_event(&mut self) -> shared::TimedKeyEvent {
+        self.keypad.timed_event().await
+    }
+
+    fn bounce_count(&mut self, key: &shared::Key) -> Option<u32> {
+        self.keypad.bounce_count(key)
+    }
+
     fn last_pressed(&mut self) -> Option<embassy_time::Duration> {
-        todo!()
+        self.keypad.last_pressed()
     }
 }
 
-impl DrawTarget for Device<'_> {
+impl<C: display::Controller> DrawTarget for Device<'_, C> {
     type Color = BinaryColor;
 
     type Error = ();
@@ -142,25 +149,32 @@ impl DrawTarget for Device<'_> {
         &mut self,
         i: I,
     ) -> Result<(), <Self as DrawTarget>::Error> {
+        // the only place the buffer is sent to the panel
         let _ = self.display.draw_iter(i);
+        let _ = self.display.flush();
         Ok(())
     }
 }
 
-impl Dimensions for Device<'_> {
+impl<C: display::Controller> Dimensions for Device<'_, C> {
     fn bounding_box(&self) -> Rectangle {
         self.display.bounding_box()
     }
 }
 
-impl Rtc for Device<'_> {
+impl<C: display::Controller> Rtc for Device<'_, C> {
     type Error = ();
 
     fn timestamp(&mut self) -> Result<i64, ()> {
         Ok(0)
     }
 
-    fn set_timestamp(&mut self, _: i64) {
-        todo!()
+    // there is no clock to set yet, which should not stop the clock app saving
+    fn set_timestamp(&mut self, _: i64) {}
+}
+
+impl<C: display::Controller> PowerButton for Device<'_, C> {
+    fn clear(&mut self) {
+        self.power_button.clear();
     }
 }
@@ -1,24 +1,26 @@
-use embassy_rp::{
-    Peri,
-    gpio::{Level, Output},
-    peripherals::PIN_15,
-};
+use embassy_rp::pwm::{Config, Pwm, SetDutyCycle};
 use shared::Backlight;
 
-pub struct Light<'a>(Output<'a>);
+use crate::board::BacklightPins;
+
+pub struct Light<'a>(Pwm<'a>);
 
 impl<'a> Light<'a> {
-    pub fn new(pin: Peri<'a, PIN_15>) -> Self {
-        Self(Output::new(pin, Level::Low))
+    pub fn new(pins: BacklightPins<'a>) -> Self {
+        Self(Pwm::new_output_b(pins.slice, pins.pin, Config::default()))
     }
 }
 
 impl Backlight for Light<'_> {
     fn on(&mut self) {
-        self.0.set_high();
+        let _ = self.0.set_duty_cycle_fully_on();
     }
 
     fn off(&mut self) {
-        self.0.set_low();
+        let _ = self.0.set_duty_cycle_fully_off();
+    }
+
+    fn set_brightness(&mut self, level: u8) {
+        let _ = self.0.set_duty_cycle_percent(level.min(100));
     }
 }
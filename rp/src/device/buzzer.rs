@@ -1,15 +1,16 @@
-use embassy_rp::{
-    Peri,
-    peripherals::{PIN_21, PWM_SLICE2},
-    pwm::{Config, Pwm, PwmError, SetDutyCycle},
-};
+use embassy_rp::pwm::{Config, Pwm, PwmError, SetDutyCycle};
 use shared::Buzzer;
 
+use crate::board::BuzzerPins;
+
 pub struct Beeper<'a>(Pwm<'a>, u16);
 
 impl<'a> Beeper<'a> {
-    pub fn new(slice: Peri<'a, PWM_SLICE2>, pin: Peri<'a, PIN_21>) -> Self {
-        Self(Pwm::new_output_b(slice, pin, Config::default()), 0)
+    pub fn new(pins: BuzzerPins<'a>) -> Self {
+        Self(
+            Pwm::new_output_b(pins.slice, pins.pin, Config::default()),
+            0,
+        )
     }
 
     fn update(&mut self) -> Result<(), PwmError> {
@@ -1,94 +1,78 @@
 use core::cell::RefCell;
 
+use display_interface::DisplayError;
 use display_interface_spi::SPIInterface;
 use embassy_rp::{
     Peri,
-    gpio::{Level, Output},
-    peripherals::{PIN_33, PIN_36, PIN_37, SPI0},
+    gpio::{AnyPin, Level, Output},
     spi,
 };
 use embassy_sync::blocking_mutex::raw::NoopRawMutex;
 use embassy_time::Delay;
-use embedded_graphics_core::{
-    Pixel,
-    pixelcolor::BinaryColor,
-    prelude::{Dimensions, DrawTarget},
-    primitives::Rectangle,
-};
+use embedded_graphics_core::{pixelcolor::BinaryColor, prelude::DrawTarget};
 
-type SpiDeviceWithConfig<'a> = embassy_embedded_hal::shared_bus::blocking::spi::SpiDeviceWithConfig<
-    'a,
-    NoopRawMutex,
-    embassy_rp::spi::Spi<'a, SPI0, embassy_rp::spi::Blocking>,
-    Output<'a>,
->;
+use crate::board::DisplaySpi;
 
-pub struct Display<'a>(
-    pcd8544::Driver<
-        SPIInterface<SpiDeviceWithConfig<'a>, Output<'a>>,
-        Output<'a>,
-        core::convert::Infallible,
-    >,
-);
-
-impl<'a> Display<'a> {
-    pub fn new(
-        spi_bus: &'a embassy_sync::blocking_mutex::Mutex<
-            NoopRawMutex,
-            RefCell<embassy_rp::spi::Spi<'a, SPI0, embassy_rp::spi::Blocking>>,
-        >,
-        thirty_seven: Peri<'a, PIN_37>,
-        thirty_six: Peri<'a, PIN_36>,
-        thirty_three: Peri<'a, PIN_33>,
-    ) -> Result<Self, display_interface::DisplayError> {
-        let mut display_config = spi::Config::default();
-        display_config.frequency = 4_000_000;
+#[cfg(not(feature = "st7567"))]
+mod pcd8544;
+#[cfg(feature = "st7567")]
+mod st7567;
 
-        let display_spi = SpiDeviceWithConfig::new(
-            spi_bus,
-            Output::new(thirty_seven, Level::High),
-            display_config,
-        );
+#[cfg(not(feature = "st7567"))]
+pub use pcd8544::{CONTRAST, INVERTED, Panel};
+#[cfg(feature = "st7567")]
+pub use st7567::{CONTRAST, INVERTED, Panel};
 
-        let mut pcd8544: pcd8544::Driver<
-            SPIInterface<SpiDeviceWithConfig<'a>, Output<'a>>,
-            Output<'a>,
-            core::convert::Infallible,
-        > = pcd8544::Driver::new(
-            SPIInterface::new(display_spi, Output::new(thirty_six, Level::High)),
-            Output::new(thirty_three, Level::High),
-        );
+pub trait Controller: DrawTarget<Color = BinaryColor> {
+    fn flush(&mut self) -> Result<(), DisplayError>;
+    fn set_contrast(&mut self, contrast: u8) -> Result<(), DisplayError>;
+    fn invert_display(&mut self, inverted: bool) -> Result<(), DisplayError>;
 
-        pcd8544.init(&mut Delay)?;
-        pcd8544.set_contrast(64)?;
-        pcd8544.invert_display(true)?;
-        pcd8544.clear(BinaryColor::Off).unwrap();
+    // panels without a low power mode stay on
+    fn power_down(&mut self) -> Result<(), DisplayError> {
+        Ok(())
+    }
 
-        Ok(Self(pcd8544))
+    fn power_up(&mut self) -> Result<(), DisplayError> {
+        Ok(())
     }
 }
 
-impl<'a> DrawTarget for Display<'a> {
-    type Color = BinaryColor;
+type SpiDeviceWithConfig<'a> = embassy_embedded_hal::shared_bus::blocking::spi::SpiDeviceWithConfig<
+    'a,
+    NoopRawMutex,
+    embassy_rp::spi::Spi<'a, DisplaySpi, embassy_rp::spi::Blocking>,
+    Output<'a>,
+>;
 
-    type Error = <pcd8544::Driver<
-        SPIInterface<SpiDeviceWithConfig<'a>, Output<'a>>,
-        Output<'a>,
-        core::convert::Infallible,
-    > as DrawTarget>::Error;
+pub type Interface<'a> = SPIInterface<SpiDeviceWithConfig<'a>, Output<'a>>;
 
-    fn draw_iter<I: IntoIterator<Item = Pixel<<Self as DrawTarget>::Color>>>(
-        &mut self,
-        i: I,
-    ) -> Result<(), <Self as DrawTarget>::Error> {
-        let a = self.0.draw_iter(i);
-        let _ = self.0.flush();
-        a
-    }
-}
+pub fn new<'a>(
+    spi_bus: &'a embassy_sync::blocking_mutex::Mutex<
+        NoopRawMutex,
+        RefCell<embassy_rp::spi::Spi<'a, DisplaySpi, embassy_rp::spi::Blocking>>,
+    >,
+    cs: Peri<'a, AnyPin>,
+    dc: Peri<'a, AnyPin>,
+    rst: Peri<'a, AnyPin>,
+) -> Result<Panel<'a>, DisplayError> {
+    let mut display_config = spi::Config::default();
+    display_config.frequency = 4_000_000;
 
-impl Dimensions for Display<'_> {
-    fn bounding_box(&self) -> Rectangle {
-        self.0.bounding_box()
-    }
+    let display_spi =
+        SpiDeviceWithConfig::new(spi_bus, Output::new(cs, Level::High), display_config);
+
+    let mut panel = Panel::new(
+        SPIInterface::new(display_spi, Output::new(dc, Level::High)),
+        Output::new(rst, Level::High),
+    );
+
+    panel.init(&mut Delay)?;
+    panel.set_contrast(CONTRAST)?;
+    panel.invert_display(INVERTED)?;
+    let _ = panel.clear(BinaryColor::Off);
+    // the drivers only draw into their buffer
+    panel.flush()?;
+
+    Ok(panel)
 }
@@ -0,0 +1,36 @@
+use display_interface::{DisplayError, WriteOnlyDataCommand};
+use embassy_rp::gpio::Output;
+use embedded_hal::digital::OutputPin;
+
+use super::{Controller, Interface};
+
+pub type Panel<'a> = ::pcd8544::Driver<Interface<'a>, Output<'a>, core::convert::Infallible>;
+
+pub const CONTRAST: u8 = 64;
+pub const INVERTED: bool = true;
+
+impl<DI, RST, PinE> Controller for ::pcd8544::Driver<DI, RST, PinE>
+where
+    DI: WriteOnlyDataCommand,
+    RST: OutputPin<Error = PinE>,
+{
+    fn flush(&mut self) -> Result<(), DisplayError> {
+        self.flush()
+    }
+
+    fn set_contrast(&mut self, contrast: u8) -> Result<(), DisplayError> {
+        self.set_contrast(contrast)
+    }
+
+    fn invert_display(&mut self, inverted: bool) -> Result<(), DisplayError> {
+        self.invert_display(inverted)
+    }
+
+    fn power_down(&mut self) -> Result<(), DisplayError> {
+        self.power_down()
+    }
+
+    fn power_up(&mut self) -> Result<(), DisplayError> {
+        self.power_up()
+    }
+}
@@ -0,0 +1,214 @@
+use embassy_futures::select::{Either, select};
+use embassy_rp::{
+    Peri,
+    gpio::{Input, Pin, Pull},
+};
+use embassy_time::{Instant, Timer};
+use shared::{Key, Keypad, TimedKeyEvent};
+
+use super::Debounce;
+use crate::board::KeypadPins;
+
+struct Button<'a>(Input<'a>, bool, u32);
+
+enum ButtonEvent {
+    Up,
+    Down,
+}
+
+impl<'a> Button<'a> {
+    fn new(pin: Peri<'a, impl Pin>) -> Self {
+        Self(Input::new(pin, Pull::Up), false, 0)
+    }
+
+    async fn event(&mut self, debounce: Debounce) -> (ButtonEvent, Instant) {
+        loop {
+            if self.1 {
+                self.0.wait_for_high().await;
+            } else {
+                self.0.wait_for_low().await;
+            }
+            let at = Instant::now();
+
+            let mut bounces = 0;
+            while let Either::Second(()) =
+                select(Timer::after(debounce.settle), self.0.wait_for_any_edge()).await
+            {
+                bounces += 1;
+            }
+            self.2 = bounces;
+
+            // a glitch that settled back where it started is not a key event
+            if self.0.is_low() != self.1 {
+                self.1 = !self.1;
+                return if self.1 {
+                    (ButtonEvent::Down, at)
+                } else {
+                    (ButtonEvent::Up, at)
+                };
+            }
+        }
+    }
+}
+
+pub struct ContactKeypad<'a> {
+    cancel: Button<'a>,
+    select: Button<'a>,
+    up: Button<'a>,
+    down: Button<'a>,
+    one: Button<'a>,
+    two: Button<'a>,
+    three: Button<'a>,
+    four: Button<'a>,
+    five: Button<'a>,
+    six: Button<'a>,
+    seven: Button<'a>,
+    eight: Button<'a>,
+    nine: Button<'a>,
+    asterisk: Button<'a>,
+    zero: Button<'a>,
+    hash: Button<'a>,
+    power: Option<Button<'a>>,
+    debounce: Debounce,
+    last_pressed: Option<Instant>,
+}
+
+impl<'a> ContactKeypad<'a> {
+    pub fn new(pins: KeypadPins<'a>, debounce: Debounce) -> Self {
+        Self {
+            cancel: Button::new(pins.cancel),
+            select: Button::new(pins.select),
+            up: Button::new(pins.up),
+            down: Button::new(pins.down),
+            one: Button::new(pins.one),
+            two: Button::new(pins.two),
+            three: Button::new(pins.three),
+            four: Button::new(pins.four),
+            five: Button::new(pins.five),
+            six: Button::new(pins.six),
+            seven: Button::new(pins.seven),
+            eight: Button::new(pins.eight),
+            nine: Button::new(pins.nine),
+            asterisk: Button::new(pins.asterisk),
+            zero: Button::new(pins.zero),
+            hash: Button::new(pins.hash),
+            power: pins.power.map(Button::new),
+            debounce,
+            last_pressed: None,
+        }
+    }
+
+    fn button(&mut self, key: &Key) -> Option<&mut Button<'a>> {
+        Some(match key {
+            Key::Cancel => &mut self.cancel,
+            Key::Select => &mut self.select,
+            Key::Up => &mut self.up,
+            Key::Down => &mut self.down,
+            Key::One => &mut self.one,
+            Key::Two => &mut self.two,
+            Key::Three => &mut self.three,
+            Key::Four => &mut self.four,
+            Key::Five => &mut self.five,
+            Key::Six => &mut self.six,
+            Key::Seven => &mut self.seven,
+            Key::Eight => &mut self.eight,
+            Key::Nine => &mut self.nine,
+            Key::Asterisk => &mut self.asterisk,
+            Key::Zero => &mut self.zero,
+            Key::Hash => &mut self.hash,
+            Key::Power => return self.power.as_mut(),
+        })
+    }
+}
+
+unsafe impl Send for ContactKeypad<'_> {}
+
+impl Keypad for ContactKeypad<'_> {
+    fn last_pressed(&mut self) -> Option<embassy_time::Duration> {
+        self.last_pressed
+            .map(|last_pressed| Instant::now() - last_pressed)
+    }
+
+    fn bounce_count(&mut self, key: &Key) -> Option<u32> {
+        self.button(key).map(|button| button.2)
+    }
+
+    async fn event(&mut self) -> shared::KeyEvent {
+        self.timed_event().await.event
+    }
+
+    async fn timed_event(&mut self) -> TimedKeyEvent {
+        let debounce = self.debounce;
+        let keys = embassy_futures::select::select_array([
+            self.cancel.event(debounce),
+            self.select.event(debounce),
+            self.up.event(debounce),
+            self.down.event(debounce),
+            self.one.event(debounce),
+            self.two.event(debounce),
+            self.three.event(debounce),
+            self.four.event(debounce),
+            self.five.event(debounce),
+            self.six.event(debounce),
+            self.seven.event(debounce),
+            self.eight.event(debounce),
+            self.nine.event(debounce),
+            self.asterisk.event(debounce),
+            self.zero.event(debounce),
+            self.hash.event(debounce),
+        ]);
+        let power = async {
+            match self.power {
+                Some(ref mut power) => power.event(debounce).await,
+                None => core::future::pending().await,
+            }
+        };
+        let ((button_event, at), index) = match select(keys, power).await {
+            Either::First(result) => result,
+            Either::Second(result) => (result, 16),
+        };
+        let event = match (button_event, index) {
+            (ButtonEvent::Up, 0) => shared::KeyEvent::Up(shared::Key::Cancel),
+            (ButtonEvent::Up, 1) => shared::KeyEvent::Up(shared::Key::Select),
+            (ButtonEvent::Up, 2) => shared::KeyEvent::Up(shared::Key::Up),
+            (ButtonEvent::Up, 3) => shared::KeyEvent::Up(shared::Key::Down),
+            (ButtonEvent::Up, 4) => shared::KeyEvent::Up(shared::Key::One),
+            (ButtonEvent::Up, 5) => shared::KeyEvent::Up(shared::Key::Two),
+            (ButtonEvent::Up, 6) => shared::KeyEvent::Up(shared::Key::Three),
+            (ButtonEvent::Up, 7) => shared::KeyEvent::Up(shared::Key::Four),
+            (ButtonEvent::Up, 8) => shared::KeyEvent::Up(shared::Key::Five),
+            (ButtonEvent::Up, 9) => shared::KeyEvent::Up(shared::Key::Six),
+            (ButtonEvent::Up, 10) => shared::KeyEvent::Up(shared::Key::Seven),
+            (ButtonEvent::Up, 11) => shared::KeyEvent::Up(shared::Key::Eight),
+            (ButtonEvent::Up, 12) => shared::KeyEvent::Up(shared::Key::Nine),
+            (ButtonEvent::Up, 13) => shared::KeyEvent::Up(shared::Key::Asterisk),
+            (ButtonEvent::Up, 14) => shared::KeyEvent::Up(shared::Key::Zero),
+            (ButtonEvent::Up, 15) => shared::KeyEvent::Up(shared::Key::Hash),
+            (ButtonEvent::Up, 16) => shared::KeyEvent::Up(shared::Key::Power),
+            (ButtonEvent::Down, 0) => shared::KeyEvent::Down(shared::Key::Cancel),
+            (ButtonEvent::Down, 1) => shared::KeyEvent::Down(shared::Key::Select),
+            (ButtonEvent::Down, 2) => shared::KeyEvent::Down(shared::Key::Up),
+            (ButtonEvent::Down, 3) => shared::KeyEvent::Down(shared::Key::Down),
+            (ButtonEvent::Down, 4) => shared::KeyEvent::Down(shared::Key::One),
+            (ButtonEvent::Down, 5) => shared::KeyEvent::Down(shared::Key::Two),
+            (ButtonEvent::Down, 6) => shared::KeyEvent::Down(shared::Key::Three),
+            (ButtonEvent::Down, 7) => shared::KeyEvent::Down(shared::Key::Four),
+            (ButtonEvent::Down, 8) => shared::KeyEvent::Down(shared::Key::Five),
+            (ButtonEvent::Down, 9) => shared::KeyEvent::Down(shared::Key::Six),
+            (ButtonEvent::Down, 10) => shared::KeyEvent::Down(shared::Key::Seven),
+            (ButtonEvent::Down, 11) => shared::KeyEvent::Down(shared::Key::Eight),
+            (ButtonEvent::Down, 12) => shared::KeyEvent::Down(shared::Key::Nine),
+            (ButtonEvent::Down, 13) => shared::KeyEvent::Down(shared::Key::Asterisk),
+            (ButtonEvent::Down, 14) => shared::KeyEvent::Down(shared::Key::Zero),
+            (ButtonEvent::Down, 15) => shared::KeyEvent::Down(shared::Key::Hash),
+            (ButtonEvent::Down, 16) => shared::KeyEvent::Down(shared::Key::Power),
+            _ => {
+                unimplemented!()
+            }
+        };
+        if let shared::KeyEvent::Down(_) = event {
+            self.last_pressed.replace(at);
+        }
+        TimedKeyEvent { event, at }
+    }
+}
@@ -0,0 +1,159 @@
+use embassy_rp::{
+    Peri,
+    gpio::{AnyPin, Input, Level, Output, Pull},
+};
+use embassy_time::{Duration, Instant, Timer};
+use shared::{Key, KeyEvent, Keypad, TimedKeyEvent};
+
+use super::Debounce;
+
+// rows are driven low one at a time and columns are read back with pull-ups. while nothing is
+// held every row is left low so a press on any key pulls its column low and wakes us through the
+// GPIO interrupt instead of polling.
+pub struct MatrixKeypad<'a, const ROWS: usize, const COLUMNS: usize> {
+    rows: [Output<'a>; ROWS],
+    columns: [Input<'a>; COLUMNS],
+    keymap: [[Option<Key>; COLUMNS]; ROWS],
+    debounce: Debounce,
+    state: [[bool; COLUMNS]; ROWS],
+    bounces: [[u32; COLUMNS]; ROWS],
+    pending: heapless::Deque<TimedKeyEvent, 8>,
+    last_pressed: Option<Instant>,
+}
+
+impl<'a, const ROWS: usize, const COLUMNS: usize> MatrixKeypad<'a, ROWS, COLUMNS> {
+    pub fn new(
+        rows: [Peri<'a, AnyPin>; ROWS],
+        columns: [Peri<'a, AnyPin>; COLUMNS],
+        keymap: [[Option<Key>; COLUMNS]; ROWS],
+        debounce: Debounce,
+    ) -> Self {
+        Self {
+            rows: rows.map(|pin| Output::new(pin, Level::Low)),
+            columns: columns.map(|pin| Input::new(pin, Pull::Up)),
+            keymap,
+            debounce,
+            state: [[false; COLUMNS]; ROWS],
+            bounces: [[0; COLUMNS]; ROWS],
+            pending: heapless::Deque::new(),
+            last_pressed: None,
+        }
+    }
+
+    fn scan(&mut self) -> [[bool; COLUMNS]; ROWS] {
+        let mut result = [[false; COLUMNS]; ROWS];
+        for row in self.rows.iter_mut() {
+            row.set_high();
+        }
+        for (r, result_row) in result.iter_mut().enumerate() {
+            self.rows[r].set_low();
+            embassy_time::block_for(Duration::from_micros(2));
+            for (c, pressed) in result_row.iter_mut().enumerate() {
+                *pressed = self.columns[c].is_low();
+            }
+            self.rows[r].set_high();
+        }
+        for row in self.rows.iter_mut() {
+            row.set_low();
+        }
+        result
+    }
+
+    async fn settle(&mut self) -> ([[bool; COLUMNS]; ROWS], Instant) {
+        let at = Instant::now();
+        let mut stable = self.scan();
+        let mut bounces = [[0; COLUMNS]; ROWS];
+        loop {
+            Timer::after(self.debounce.settle).await;
+            let next = self.scan();
+            if next == stable {
+                break;
+            }
+            for r in 0..ROWS {
+                for c in 0..COLUMNS {
+                    if next[r][c] != stable[r][c] {
+                        bounces[r][c] += 1;
+                    }
+                }
+            }
+            stable = next;
+        }
+        for r in 0..ROWS {
+            for c in 0..COLUMNS {
+                if stable[r][c] != self.state[r][c] {
+                    self.bounces[r][c] = bounces[r][c];
+                }
+            }
+        }
+        (stable, at)
+    }
+
+    fn position(&self, key: &Key) -> Option<(usize, usize)> {
+        for (r, keymap_row) in self.keymap.iter().enumerate() {
+            for (c, k) in keymap_row.iter().enumerate() {
+                if k.as_ref() == Some(key) {
+                    return Some((r, c));
+                }
+            }
+        }
+        None
+    }
+}
+
+unsafe impl<const ROWS: usize, const COLUMNS: usize> Send for MatrixKeypad<'_, ROWS, COLUMNS> {}
+
+impl<const ROWS: usize, const COLUMNS: usize> Keypad for MatrixKeypad<'_, ROWS, COLUMNS> {
+    fn last_pressed(&mut self) -> Option<embassy_time::Duration> {
+        self.last_pressed
+            .map(|last_pressed| Instant::now() - last_pressed)
+    }
+
+    fn bounce_count(&mut self, key: &Key) -> Option<u32> {
+        self.position(key).map(|(r, c)| self.bounces[r][c])
+    }
+
+    async fn event(&mut self) -> KeyEvent {
+        self.timed_event().await.event
+    }
+
+    async fn timed_event(&mut self) -> TimedKeyEvent {
+        loop {
+            if let Some(timed_event) = self.pending.pop_front() {
+                if let KeyEvent::Down(_) = timed_event.event {
+                    self.last_pressed.replace(timed_event.at);
+                }
+                return timed_event;
+            }
+
+            if self.state.iter().flatten().any(|pressed| *pressed) {
+                Timer::after(self.debounce.settle).await;
+            } else {
+                embassy_futures::select::select_array(
+                    self.columns.each_mut().map(|column| column.wait_for_low()),
+                )
+                .await;
+            }
+
+            let (stable, at) = self.settle().await;
+            for r in 0..ROWS {
+                for c in 0..COLUMNS {
+                    if stable[r][c] == self.state[r][c] {
+                        continue;
+                    }
+                    if let Some(ref key) = self.keymap[r][c] {
+                        let event = if stable[r][c] {
+                            KeyEvent::Down(key.clone())
+                        } else {
+                            KeyEvent::Up(key.clone())
+                        };
+                        // leave the cell unchanged so it is picked up again on the next scan
+                        if self.pending.push_back(TimedKeyEvent { event, at }).is_err() {
+                            continue;
+                        }
+                    }
+                    self.state[r][c] = stable[r][c];
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,14 @@
 use embassy_rp::{
-    gpio::{Level, Output},
-    peripherals::PIN_28,
+    Peri,
+    gpio::{AnyPin, Level, Output},
 };
 use shared::PowerButton;
 
+// drives the power controller's clear input, which cuts our own supply
 pub struct Button<'a>(Output<'a>);
 
-impl Button<'_> {
-    pub fn new(pin: PIN_28) -> Self {
+impl<'a> Button<'a> {
+    pub fn new(pin: Peri<'a, AnyPin>) -> Self {
         Self(Output::new(pin, Level::Low))
     }
 }
@@ -1,25 +1,36 @@
-use embassy_rp::{
-    gpio::{Level, Output},
-    peripherals::PIN_2,
-};
+use embassy_rp::pwm::{Config, Pwm, SetDutyCycle};
 use shared::VibrationMotor;
 
-pub struct Motor<'a>(Output<'a>);
+use crate::board::VibrationMotorPins;
 
-use embassy_rp::Peri;
+// the motor, how hard it runs as a percentage of full power, and whether it is running
+pub struct Motor<'a>(Pwm<'a>, u8, bool);
 
 impl<'a> Motor<'a> {
-    pub fn new(pin: Peri<'a, PIN_2>) -> Self {
-        Self(Output::new(pin, Level::Low))
+    pub fn new(pins: VibrationMotorPins<'a>) -> Self {
+        Self(
+            Pwm::new_output_a(pins.slice, pins.pin, Config::default()),
+            100,
+            false,
+        )
     }
 }
 
 impl VibrationMotor for Motor<'_> {
     fn start_vibrating(&mut self) {
-        self.0.set_high();
+        self.2 = true;
+        let _ = self.0.set_duty_cycle_percent(self.1);
     }
 
     fn stop_vibrating(&mut self) {
-        self.0.set_low();
+        self.2 = false;
+        let _ = self.0.set_duty_cycle_fully_off();
+    }
+
+    fn set_intensity(&mut self, level: u8) {
+        self.1 = level.min(100);
+        if self.2 {
+            let _ = self.0.set_duty_cycle_percent(self.1);
+        }
     }
 }
@@ -1,111 +1,112 @@
-use defmt_rtt as _;
-use embassy_executor::Spawner;
 use embassy_rp::{
-    flash::{Async, ERASE_SIZE},
+    Peri,
+    flash::{Blocking, ERASE_SIZE, Flash},
     peripherals::FLASH,
 };
-use embassy_time::Timer;
-use panic_probe as _;
 
-// 2MB
-const ADDR_OFFSET: u32 = 0b0001_0000_0000_0000_0000_0000;
+// 4MB, as split up by the partition table in main.rs
+const FLASH_SIZE: usize = 4 * 1024 * 1024;
 
-const FLASH_SIZE: usize = 2 * 1024 * 1024;
+// the last sector, which the partition table leaves out of both partitions
+const OFFSET: u32 = (FLASH_SIZE - ERASE_SIZE) as u32;
 
-use crate::Flashs;
+// an erased byte where the next key length would be marks the end of the entries
+const END: u8 = 0xFF;
 
-#[embassy_executor::task]
-pub async fn flash_task(_spawner: Spawner, flash: Flashs) {
-    Timer::after_millis(10).await;
-    let mut flash = embassy_rp::flash::Flash::<_, Async, FLASH_SIZE>::new(flash.flash, flash.dma_0);
-
-    erase_write_sector(&mut flash, 0x00);
-    multiwrite_bytes(&mut flash, ERASE_SIZE as u32);
-    background_read(&mut flash, (ERASE_SIZE * 2) as u32).await;
-
-    loop {
-        Timer::after_millis(10).await;
-    }
+// settings are kept in one sector as entries one after another: a key length, a data length, the
+// key and then the data. storing anything rewrites the whole sector
+pub struct Settings<'a> {
+    flash: Flash<'a, FLASH, Blocking, FLASH_SIZE>,
 }
 
-fn multiwrite_bytes(
-    flash: &mut embassy_rp::flash::Flash<'_, FLASH, Async, FLASH_SIZE>,
-    offset: u32,
-) {
-    let mut read_buf = [0u8; ERASE_SIZE];
-    defmt::unwrap!(flash.blocking_read(ADDR_OFFSET + offset, &mut read_buf));
-    defmt::unwrap!(flash.blocking_erase(
-        ADDR_OFFSET + offset,
-        ADDR_OFFSET + offset + ERASE_SIZE as u32
-    ));
-
-    defmt::unwrap!(flash.blocking_read(ADDR_OFFSET + offset, &mut read_buf));
-    if read_buf.iter().any(|x| *x != 0xFF) {
-        defmt::panic!("unexpected");
+impl<'a> Settings<'a> {
+    pub fn new(flash: Peri<'a, FLASH>) -> Self {
+        Self {
+            flash: Flash::new_blocking(flash),
+        }
     }
 
-    defmt::unwrap!(flash.blocking_write(ADDR_OFFSET + offset, &[0x01]));
-    defmt::unwrap!(flash.blocking_write(ADDR_OFFSET + offset + 1, &[0x02]));
-    defmt::unwrap!(flash.blocking_write(ADDR_OFFSET + offset + 2, &[0x03]));
-    defmt::unwrap!(flash.blocking_write(ADDR_OFFSET + offset + 3, &[0x04]));
-
-    defmt::unwrap!(flash.blocking_read(ADDR_OFFSET + offset, &mut read_buf));
-    if read_buf[0..4] != [0x01, 0x02, 0x03, 0x04] {
-        defmt::panic!("unexpected");
+    // as if erased if it can't be read
+    fn read(&mut self) -> [u8; ERASE_SIZE] {
+        let mut sector = [END; ERASE_SIZE];
+        if self.flash.blocking_read(OFFSET, &mut sector).is_err() {
+            sector.fill(END);
+        }
+        sector
     }
-}
 
-fn erase_write_sector(
-    flash: &mut embassy_rp::flash::Flash<'_, FLASH, Async, FLASH_SIZE>,
-    offset: u32,
-) {
-    let mut buf = [0u8; ERASE_SIZE];
-    defmt::unwrap!(flash.blocking_read(ADDR_OFFSET + offset, &mut buf));
-    defmt::unwrap!(flash.blocking_erase(
-        ADDR_OFFSET + offset,
-        ADDR_OFFSET + offset + ERASE_SIZE as u32
-    ));
-    defmt::unwrap!(flash.blocking_read(ADDR_OFFSET + offset, &mut buf));
-    if buf.iter().any(|x| *x != 0xFF) {
-        defmt::panic!("unexpected");
-    }
-
-    for b in buf.iter_mut() {
-        *b = 0xDA;
-    }
-
-    defmt::unwrap!(flash.blocking_write(ADDR_OFFSET + offset, &buf));
-    defmt::unwrap!(flash.blocking_read(ADDR_OFFSET + offset, &mut buf));
-    if buf.iter().any(|x| *x != 0xDA) {
-        defmt::panic!("unexpected");
+    fn write(&mut self, sector: &[u8; ERASE_SIZE]) {
+        if let Err(e) = self
+            .flash
+            .blocking_erase(OFFSET, OFFSET + ERASE_SIZE as u32)
+            .and_then(|()| self.flash.blocking_write(OFFSET, sector))
+        {
+            defmt::error!("storing settings failed: {}", e);
+        }
     }
 }
 
-async fn background_read(
-    flash: &mut embassy_rp::flash::Flash<'_, FLASH, Async, FLASH_SIZE>,
-    offset: u32,
-) {
-    let mut buf = [0u32; 8];
-    defmt::unwrap!(flash.background_read(ADDR_OFFSET + offset, &mut buf)).await;
-    defmt::unwrap!(flash.blocking_erase(
-        ADDR_OFFSET + offset,
-        ADDR_OFFSET + offset + ERASE_SIZE as u32
-    ));
-    defmt::unwrap!(flash.background_read(ADDR_OFFSET + offset, &mut buf)).await;
+// the key and data of each entry
+fn entries(sector: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    let mut start = 0;
+    core::iter::from_fn(move || {
+        let key_length = *sector.get(start)?;
+        if key_length == END {
+            return None;
+        }
+        let data_length = *sector.get(start + 1)?;
+        let key = start + 2..start + 2 + key_length as usize;
+        let data = key.end..key.end + data_length as usize;
+        start = data.end;
+        Some((sector.get(key)?, sector.get(data)?))
+    })
+}
 
-    if buf.iter().any(|x| *x != 0xFFFFFFFF) {
-        defmt::panic!("unexpected");
-    }
+// gives back where the next entry goes, or `None` if this one doesn't fit
+fn append(sector: &mut [u8], start: usize, key: &[u8], data: &[u8]) -> Option<usize> {
+    let key_length = u8::try_from(key.len())
+        .ok()
+        .filter(|length| *length != END)?;
+    let data_length = u8::try_from(data.len()).ok()?;
+    let end = start + 2 + key.len() + data.len();
+    let entry = sector.get_mut(start..end)?;
+    entry[0] = key_length;
+    entry[1] = data_length;
+    entry[2..2 + key.len()].copy_from_slice(key);
+    entry[2 + key.len()..].copy_from_slice(data);
+    Some(end)
+}
 
-    for b in buf.iter_mut() {
-        *b = 0xDABA1234;
+impl shared::Storage for Settings<'_> {
+    fn load(&mut self, key: &str, buffer: &mut [u8]) -> Option<usize> {
+        let sector = self.read();
+        let (_, data) = entries(&sector).find(|(k, _)| *k == key.as_bytes())?;
+        let length = data.len().min(buffer.len());
+        buffer[..length].copy_from_slice(&data[..length]);
+        Some(length)
     }
 
-    defmt::unwrap!(flash.blocking_write(ADDR_OFFSET + offset, unsafe {
-        core::slice::from_raw_parts(buf.as_ptr() as *const u8, buf.len() * 4)
-    }));
-    defmt::unwrap!(flash.background_read(ADDR_OFFSET + offset, &mut buf)).await;
-    if buf.iter().any(|x| *x != 0xDABA1234) {
-        defmt::panic!("unexpected");
+    fn store(&mut self, key: &str, data: &[u8]) {
+        let sector = self.read();
+        let mut updated = [END; ERASE_SIZE];
+        let mut start = 0;
+        for (k, d) in entries(&sector) {
+            if k == key.as_bytes() {
+                // saves wearing the sector out on settings that haven't changed
+                if d == data {
+                    return;
+                }
+                continue;
+            }
+            let Some(end) = append(&mut updated, start, k, d) else {
+                break;
+            };
+            start = end;
+        }
+        if append(&mut updated, start, key.as_bytes(), data).is_none() {
+            defmt::error!("no room to store {}", key);
+            return;
+        }
+        self.write(&updated);
     }
 }
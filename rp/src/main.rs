@@ -43,7 +43,8 @@ pub static PARTITION_TABLE: PartitionTableBlock = PartitionTableBlock::new()
                 .with_permission(Permission::BootRead)
                 .with_permission(Permission::BootWrite)
                 .with_name("A"),
-            Partition::new(513, 1023)
+            // stops a sector short, which flash.rs keeps settings in
+            Partition::new(513, 1022)
                 .with_id(1)
                 .with_flag(PartitionFlag::AcceptsDefaultFamilyRp2350ArmS)
                 .with_flag(PartitionFlag::AcceptsDefaultFamilyRp2350Riscv)
@@ -82,18 +83,15 @@ pub static PICOTOOL_ENTRIES: [embassy_rp::binary_info::EntryAddr; 6] = [
 ];
 
 // mod background_core;
+mod board;
 mod device;
-// mod flash;
+mod flash;
 mod rtc;
 
 // assign_resources! {
 //     usbs: Usbs{
 //         usb: USB,
 //     },
-//     // flashs: Flashs {
-//     //     flash: FLASH,
-//     //     dma_0: DMA_CH0
-//     // }
 // }
 
 bind_interrupts!(struct Irqs {
@@ -115,7 +113,6 @@ async fn main(_spawner: Spawner) {
     // watchdog.set_scratch(0, WATCHDOG_MARKER);
     embassy_time::Timer::after_millis(10).await;
 
-    // spawner.spawn(flash::flash_task(spawner, r.flashs)).unwrap();
     // spawn_core1(
     //     p.CORE1,
     //     unsafe { &mut *core::ptr::addr_of_mut!(CORE1_STACK) },
@@ -127,47 +124,40 @@ async fn main(_spawner: Spawner) {
     //     },
     // );
     // FIX
-    let _clock = rtc::Clock::new(p.I2C1, p.PIN_46, p.PIN_47);
+    let board = board::split_board!(p);
+    let _clock = rtc::Clock::new(board.rtc);
 
     let mut display_config = spi::Config::default();
     display_config.frequency = 4_000_000;
 
     let display = Mutex::new(RefCell::new(Spi::new_blocking(
-        p.SPI0,
-        p.PIN_38,
-        p.PIN_39,
-        p.PIN_32,
+        board.display.spi,
+        board.display.clk,
+        board.display.mosi,
+        board.display.miso,
         display_config,
     )));
-    let _device = device::Device::new(
-        watchdog,
-        p.PIN_2,
-        p.PIN_4,
-        p.PIN_5,
-        p.PIN_6,
-        p.PIN_7,
-        p.PIN_8,
-        p.PIN_9,
-        p.PIN_10,
-        p.PIN_11,
-        p.PIN_12,
-        p.PIN_13,
-        p.PIN_14,
-        p.PIN_15,
-        p.PIN_16,
-        p.PIN_17,
-        p.PIN_18,
-        p.PIN_19,
-        p.PIN_20,
-        p.PIN_21,
-        p.PIN_33,
-        p.PIN_36,
-        p.PIN_37,
-        p.PWM_SLICE2,
+    let panel = device::display::new(
         &display,
+        board.display.cs,
+        board.display.dc,
+        board.display.rst,
     )
     .unwrap();
-    loop {
-        log::info!("looping");
-    }
+    let device = device::Device::new(watchdog, board.device, Default::default(), panel, p.FLASH);
+    let mut system = shared::system::System::new(device);
+
+    shared::home::home(
+        &mut system,
+        &mut (
+            (
+                "Keyboard",
+                keyboard::Keyboard::new(heapless::String::<240>::new()),
+            ),
+            ("Ringtones", ringtones::Ringtones::new()),
+            ("Clock", clock::Clock::new()),
+            ("Snake", snake::Snake::new(0)),
+        ),
+    )
+    .await
 }
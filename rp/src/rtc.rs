@@ -1,20 +1,21 @@
-use embassy_rp::peripherals::{I2C1, PIN_46, PIN_47};
 use shared::Rtc;
 use unofficial_piicodev::Driver;
 
+use crate::board::{RtcI2c, RtcPins};
+
 pub struct Clock<'a>(
-    unofficial_piicodev::p19::P19<embassy_rp::i2c::I2c<'a, I2C1, embassy_rp::i2c::Blocking>>,
+    unofficial_piicodev::p19::P19<embassy_rp::i2c::I2c<'a, RtcI2c, embassy_rp::i2c::Blocking>>,
 );
-use embassy_rp::Peri;
 
 impl<'a> Clock<'a> {
-    pub fn new(
-        i2c: Peri<'a, I2C1>,
-        sda: Peri<'a, PIN_46>,
-        scl: Peri<'a, PIN_47>,
-    ) -> Result<Self, unofficial_piicodev::OutOfRange> {
+    pub fn new(pins: RtcPins<'a>) -> Result<Self, unofficial_piicodev::OutOfRange> {
         Ok(Self(unofficial_piicodev::p19::P19::new(
-            embassy_rp::i2c::I2c::new_blocking(i2c, scl, sda, embassy_rp::i2c::Config::default()),
+            embassy_rp::i2c::I2c::new_blocking(
+                pins.i2c,
+                pins.scl,
+                pins.sda,
+                embassy_rp::i2c::Config::default(),
+            ),
             0x52,
         )?))
     }
@@ -0,0 +1,65 @@
+use std::{env, fmt::Write, fs, path::PathBuf};
+
+// the same layout as the keypad, 2 is abc through to 9 which is wxyz
+const KEYS: [&str; 8] = ["abc", "def", "ghi", "jkl", "mno", "pqrs", "tuv", "wxyz"];
+
+#[derive(Default)]
+struct Node {
+    children: [usize; 8],
+    words: Vec<String>,
+}
+
+fn key(c: char) -> Option<usize> {
+    KEYS.iter().position(|letters| letters.contains(c))
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=t9/words.txt");
+
+    let words = fs::read_to_string("t9/words.txt").unwrap();
+    let mut nodes = vec![Node::default()];
+    for word in words.lines().map(str::trim) {
+        if word.is_empty() || word.starts_with('#') {
+            continue;
+        }
+        let mut index = 0;
+        for c in word.chars() {
+            let k = key(c).unwrap_or_else(|| panic!("{word} can not be typed on the keypad"));
+            if nodes[index].children[k] == 0 {
+                nodes.push(Node::default());
+                nodes[index].children[k] = nodes.len() - 1;
+            }
+            index = nodes[index].children[k];
+        }
+        if !nodes[index].words.iter().any(|w| w == word) {
+            nodes[index].words.push(word.to_string());
+        }
+    }
+
+    let mut out = String::new();
+    let mut flattened = Vec::new();
+    writeln!(out, "static NODES: [Node; {}] = [", nodes.len()).unwrap();
+    for node in &nodes {
+        let start = flattened.len();
+        flattened.extend(node.words.iter());
+        writeln!(
+            out,
+            "    Node {{ children: {:?}, words: {}..{} }},",
+            node.children,
+            start,
+            flattened.len()
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    writeln!(
+        out,
+        "static WORDS: [&str; {}] = {:?};",
+        flattened.len(),
+        flattened
+    )
+    .unwrap();
+
+    let path = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("t9.rs");
+    fs::write(path, out).unwrap();
+}
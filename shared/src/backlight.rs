@@ -0,0 +1,143 @@
+use embassy_time::Duration;
+
+use crate::{Backlight, time::remaining};
+
+pub const MAX_BRIGHTNESS: u8 = 100;
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+
+// how far a fade moves the light each step, and how often it steps
+const FADE_STEP: u8 = 10;
+pub const FADE_INTERVAL: Duration = Duration::from_millis(20);
+
+// keeps the light at the chosen brightness while keys are being pressed and fades it out once they
+// have been left alone for the timeout
+#[derive(Debug)]
+pub struct Light {
+    brightness: u8,
+    timeout: Option<Duration>,
+    // what the light is at, and what it is fading towards
+    level: u8,
+    target: u8,
+}
+
+impl Default for Light {
+    fn default() -> Self {
+        Self::new(MAX_BRIGHTNESS, Some(DEFAULT_TIMEOUT))
+    }
+}
+
+impl Light {
+    // starts out dark, fading up to `brightness`
+    pub fn new(brightness: u8, timeout: Option<Duration>) -> Self {
+        let brightness = brightness.min(MAX_BRIGHTNESS);
+        Self {
+            brightness,
+            timeout,
+            level: 0,
+            target: brightness,
+        }
+    }
+
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness.min(MAX_BRIGHTNESS);
+        if self.target > 0 {
+            self.target = self.brightness;
+        }
+    }
+
+    // `None` keeps the light on for good
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    // fades back up, as any key press does
+    pub fn wake(&mut self) {
+        self.target = self.brightness;
+    }
+
+    // how much longer before the next step of a fade, or before the light times out and starts one.
+    // a light that is out, or never times out, has nothing to wait for
+    pub fn next_update(&self, idle: Duration) -> Option<Duration> {
+        if self.level != self.target {
+            Some(FADE_INTERVAL)
+        } else if self.target > 0 {
+            self.timeout.map(|timeout| remaining(timeout, idle))
+        } else {
+            None
+        }
+    }
+
+    // takes one step of any fade, starting one out if keys have been left alone long enough
+    pub fn update(&mut self, device: &mut impl Backlight, idle: Duration) {
+        if self.target > 0 && self.timeout.is_some_and(|timeout| idle >= timeout) {
+            self.target = 0;
+        }
+        if self.level == self.target {
+            return;
+        }
+        self.level = if self.level < self.target {
+            self.level.saturating_add(FADE_STEP).min(self.target)
+        } else {
+            self.level.saturating_sub(FADE_STEP).max(self.target)
+        };
+        device.set_brightness(self.level);
+    }
+
+    // puts the light back where it was after something else (a ringing notification, say) has
+    // been flashing it
+    pub fn restore(&self, device: &mut impl Backlight) {
+        device.set_brightness(self.level);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fade() {
+        let mut device = crate::test::Device::new(1, 1, &[]);
+        let mut light = Light::new(35, Some(DEFAULT_TIMEOUT));
+        let idle = Duration::from_secs(1);
+        for level in [10, 20, 30, 35] {
+            assert_eq!(light.next_update(idle), Some(FADE_INTERVAL));
+            light.update(&mut device, idle);
+            assert_eq!(device.brightness, level);
+        }
+        assert_eq!(light.next_update(idle), Some(Duration::from_secs(14)));
+
+        light.update(&mut device, DEFAULT_TIMEOUT);
+        assert_eq!(device.brightness, 25);
+        for _ in 0..3 {
+            light.update(&mut device, DEFAULT_TIMEOUT);
+        }
+        assert!(!device.backlight);
+        assert_eq!(light.next_update(DEFAULT_TIMEOUT), None);
+
+        light.wake();
+        light.update(&mut device, Duration::from_secs(0));
+        assert_eq!(light.level(), 10);
+    }
+
+    #[test]
+    fn test_no_timeout() {
+        let mut device = crate::test::Device::new(1, 1, &[]);
+        let mut light = Light::new(MAX_BRIGHTNESS, None);
+        for _ in 0..10 {
+            light.update(&mut device, Duration::from_secs(3600));
+        }
+        assert_eq!(device.brightness, MAX_BRIGHTNESS);
+        assert_eq!(light.next_update(Duration::from_secs(3600)), None);
+
+        light.set_brightness(150);
+        assert_eq!(light.brightness(), MAX_BRIGHTNESS);
+    }
+}
@@ -1,91 +1,332 @@
+use embassy_time::{Duration, Instant};
 use embedded_graphics::{
     Drawable,
-    mono_font::{MonoTextStyle, ascii::FONT_6X10},
+    draw_target::{DrawTarget, DrawTargetExt},
+    mono_font::MonoTextStyle,
     pixelcolor::BinaryColor,
-    prelude::{Point, Primitive, Size},
-    primitives::{PrimitiveStyle, Rectangle},
-    text::{Alignment, Text},
-};
-use embedded_text::{
-    TextBox,
-    alignment::HorizontalAlignment,
-    style::{HeightMode, TextBoxStyleBuilder},
+    prelude::{Point, Size},
+    primitives::Rectangle,
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
 };
 
-use crate::KeyEvent;
+use crate::{Key, KeyEvent, Keypad, textbox::Textbox};
+
+const BUTTON_HEIGHT: u32 = 12;
 
+// a message with a row of buttons under it. the message can be anything that gives a `str`, so
+// a `heapless::String` filled with `core::fmt::write` works as well as a literal
 #[derive(Clone, PartialEq)]
-pub struct Confirmation(&'static str, &'static str, &'static str, bool, Option<bool>);
+pub struct Dialog<M, const N: usize> {
+    message: M,
+    choices: [&'static str; N],
+    selected: usize,
+    // how many pixels of the message have scrolled off the top
+    scroll: u32,
+    // how long to wait for an answer before taking the default
+    timeout: Option<(Duration, usize)>,
+    deadline: Option<Instant>,
+    result: Option<usize>,
+}
 
-impl Confirmation {
-    pub fn new(
-        message: &'static str,
-        r#true: &'static str,
-        r#false: &'static str,
-        selected: bool,
-    ) -> Self {
-        Self(message, r#true, r#false, selected, None)
+impl<M: AsRef<str>, const N: usize> Dialog<M, N> {
+    pub fn new(message: M, choices: [&'static str; N], selected: usize) -> Self {
+        assert!(selected < N);
+        Self {
+            message,
+            choices,
+            selected,
+            scroll: 0,
+            timeout: None,
+            deadline: None,
+            result: None,
+        }
     }
 
-    pub async fn run(&mut self, device: &mut impl crate::Device) -> Option<bool> {
-        if let Some(result) = self.4 {
-            return Some(result);
+    pub fn with_timeout(self, timeout: Duration, default: usize) -> Self {
+        assert!(default < N);
+        Self {
+            timeout: Some((timeout, default)),
+            ..self
         }
-        let _ = device.clear(BinaryColor::On);
-
-        let fill = PrimitiveStyle::with_fill(BinaryColor::Off);
-        if self.3 {
-            Rectangle::new(Point::new(0, 36), Size::new(42, 12))
-                .into_styled(fill)
-                .draw(device)
-                .unwrap();
-        } else {
-            Rectangle::new(Point::new(42, 36), Size::new(42, 12))
-                .into_styled(fill)
-                .draw(device)
-                .unwrap();
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    fn style() -> MonoTextStyle<'static, BinaryColor> {
+        crate::theme::current().text_style()
+    }
+
+    fn body(area: Rectangle) -> Rectangle {
+        Rectangle::new(
+            area.top_left,
+            Size::new(
+                area.size.width,
+                area.size.height.saturating_sub(BUTTON_HEIGHT),
+            ),
+        )
+    }
+
+    // how far the message can scroll before its last line is at the bottom of the body
+    fn max_scroll(&self, body: Rectangle) -> u32 {
+        Textbox::with_style(self.message.as_ref(), Self::style())
+            .height(body.size.width)
+            .saturating_sub(body.size.height)
+    }
+
+    pub fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>) {
+        let theme = crate::theme::current();
+        theme.clear(target);
+        let area = target.bounding_box();
+        let width = area.size.width / N as u32;
+        let top = area.top_left.y + area.size.height as i32 - BUTTON_HEIGHT as i32;
+        for (i, choice) in self.choices.iter().enumerate() {
+            let left = area.top_left.x + (i as u32 * width) as i32;
+            let foreground = theme.draw_selection(
+                target,
+                Rectangle::new(Point::new(left, top), Size::new(width, BUTTON_HEIGHT)),
+                i == self.selected,
+            );
+            let _ = Text::with_text_style(
+                choice,
+                Point::new(left + width as i32 / 2, top + BUTTON_HEIGHT as i32 / 2),
+                MonoTextStyle::new(theme.font, foreground),
+                TextStyleBuilder::new()
+                    .alignment(Alignment::Center)
+                    .baseline(Baseline::Middle)
+                    .build(),
+            )
+            .draw(target);
         }
 
-        let true_style = if self.3 {
-            MonoTextStyle::new(&FONT_6X10, BinaryColor::On)
+        let body = Self::body(area);
+        let mut clipped = target.clipped(&body);
+        let mut translated = clipped.translated(Point::new(0, -(self.scroll as i32)));
+        Textbox::with_style(self.message.as_ref(), Self::style()).draw(
+            &mut translated,
+            None,
+            false,
+        );
+    }
+
+    // down reads on through the message before it moves through the buttons, up goes back up the
+    // message before it does
+    fn down(&mut self, body: Rectangle) {
+        let line_height = Self::style().font.character_size.height;
+        let max_scroll = self.max_scroll(body);
+        if self.scroll < max_scroll {
+            self.scroll = (self.scroll + line_height).min(max_scroll);
         } else {
-            MonoTextStyle::new(&FONT_6X10, BinaryColor::Off)
-        };
-        Text::with_alignment(self.1, Point::new(21, 44), true_style, Alignment::Center)
-            .draw(device)
-            .unwrap();
-        let false_style = if self.3 {
-            MonoTextStyle::new(&FONT_6X10, BinaryColor::Off)
+            self.selected = (self.selected + 1) % N;
+        }
+    }
+
+    fn up(&mut self) {
+        let line_height = Self::style().font.character_size.height;
+        if self.scroll > 0 {
+            self.scroll = self.scroll.saturating_sub(line_height);
         } else {
-            MonoTextStyle::new(&FONT_6X10, BinaryColor::On)
-        };
-        Text::with_alignment(self.2, Point::new(63, 44), false_style, Alignment::Center)
-            .draw(device)
-            .unwrap();
-
-        TextBox::with_textbox_style(
-            self.0,
-            Rectangle::new(Point::zero(), Size::new(84, 48)),
-            MonoTextStyle::new(&FONT_6X10, BinaryColor::Off),
-            TextBoxStyleBuilder::new()
-                .height_mode(HeightMode::FitToText)
-                .alignment(HorizontalAlignment::Left)
-                .paragraph_spacing(6)
-                .build(),
-        )
-        .draw(device)
-        .unwrap();
+            self.selected = (self.selected + N - 1) % N;
+        }
+    }
+
+    // handles one key at a time, giving back the button chosen once there is one. pressing keys
+    // restarts the timeout
+    pub async fn run(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> Option<usize> {
+        if let Some(result) = self.result {
+            return Some(result);
+        }
+        self.draw(device);
 
-        match device.event().await {
-            KeyEvent::Down(crate::Key::Up | crate::Key::Down) => {
-                self.3 = !self.3;
-                None
+        let event = match self.timeout {
+            Some((timeout, default)) => {
+                let deadline = *self
+                    .deadline
+                    .get_or_insert_with(|| Instant::now() + timeout);
+                match device.event_before(deadline).await {
+                    Some(event) => {
+                        self.deadline = None;
+                        event
+                    }
+                    None => {
+                        self.result = Some(default);
+                        return self.result;
+                    }
+                }
             }
-            KeyEvent::Down(crate::Key::Select) => {
-                self.4 = Some(self.3);
-                self.4
+            None => device.event().await,
+        };
+
+        match event {
+            KeyEvent::Down(Key::Down) => self.down(Self::body(device.bounding_box())),
+            KeyEvent::Down(Key::Up) => self.up(),
+            KeyEvent::Down(Key::Select) => self.result = Some(self.selected),
+            _ => {}
+        }
+        self.result
+    }
+
+    // runs until a button is chosen
+    pub async fn process(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> usize {
+        loop {
+            if let Some(result) = self.run(device).await {
+                return result;
             }
-            _ => None,
         }
     }
 }
+
+// a yes or no question
+#[derive(Clone, PartialEq)]
+pub struct Confirmation<M = &'static str>(Dialog<M, 2>);
+
+impl<M: AsRef<str>> Confirmation<M> {
+    pub fn new(message: M, r#true: &'static str, r#false: &'static str, selected: bool) -> Self {
+        Self(Dialog::new(
+            message,
+            [r#true, r#false],
+            if selected { 0 } else { 1 },
+        ))
+    }
+
+    pub fn with_timeout(self, timeout: Duration, default: bool) -> Self {
+        Self(self.0.with_timeout(timeout, if default { 0 } else { 1 }))
+    }
+
+    pub async fn run(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> Option<bool> {
+        self.0.run(device).await.map(|result| result == 0)
+    }
+
+    pub async fn process(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> bool {
+        self.0.process(device).await == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn test_confirmation() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                60,
+                36,
+                &[
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Down(Key::Select)),
+                ],
+            );
+            let mut confirmation = Confirmation::new("Delete?", "Yes", "No", false);
+            assert!(confirmation.process(&mut device).await);
+            assert_eq!(confirmation.run(&mut device).await, Some(true));
+        });
+    }
+
+    #[test]
+    fn test_timeout() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                60,
+                36,
+                &[
+                    (400, KeyEvent::Down(Key::Down)),
+                    (400, KeyEvent::Up(Key::Down)),
+                ],
+            );
+            let mut dialog = Dialog::new("Alarm", ["Stop", "Snooze", "Off"], 0)
+                .with_timeout(Duration::from_millis(500), 2);
+            assert_eq!(dialog.process(&mut device).await, 2);
+            assert_eq!(dialog.selected(), 1);
+        });
+    }
+
+    #[test]
+    fn test_timeout_restarted() {
+        block_on(async {
+            // select comes 800ms in, which is only in time because down put the deadline back
+            let mut device = crate::test::Device::new(
+                60,
+                36,
+                &[
+                    (400, KeyEvent::Down(Key::Down)),
+                    (400, KeyEvent::Down(Key::Select)),
+                ],
+            );
+            let mut dialog = Dialog::new("Alarm", ["Stop", "Snooze", "Off"], 0)
+                .with_timeout(Duration::from_millis(500), 2);
+            assert_eq!(dialog.process(&mut device).await, 1);
+        });
+    }
+
+    #[test]
+    fn test_scroll() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                48,
+                32,
+                &[
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Down(Key::Up)),
+                ],
+            );
+            let mut message: heapless::String<32> = heapless::String::new();
+            let _ = core::fmt::write(&mut message, format_args!("Call from {}?", 5550123));
+            let mut dialog = Dialog::new(message, ["Yes", "No"], 0);
+            for _ in 0..2 {
+                assert_eq!(dialog.run(&mut device).await, None);
+            }
+            assert_eq!((dialog.scroll, dialog.selected()), (10, 1));
+            assert_eq!(dialog.run(&mut device).await, None);
+            assert_eq!((dialog.scroll, dialog.selected()), (0, 1));
+        });
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut device = crate::test::Device::new(36, 24, &[]);
+        Dialog::new("Ok?", ["Y", "N"], 0).draw(&mut device);
+        device.display.assert_pattern(&[
+            "####################################",
+            "#...##.######...####################",
+            ".###.#.#####.###.###################",
+            ".###.#.###.####.####################",
+            ".###.#.##.####.#####################",
+            ".###.#...#####.#####################",
+            ".###.#.##.##########################",
+            "#...##.###.###.#####################",
+            "####################################",
+            "####################################",
+            "####################################",
+            "####################################",
+            "..................##################",
+            "..................##################",
+            "..................##################",
+            ".......#...#......#######.###.######",
+            ".......#...#......#######.###.######",
+            "........#.#.......#######..##.######",
+            ".........#........#######.#.#.######",
+            ".........#........#######.##..######",
+            ".........#........#######.###.######",
+            ".........#........#######.###.######",
+            "..................##################",
+            "..................##################",
+        ]);
+    }
+}
@@ -1,30 +1,38 @@
-use core::fmt::Debug;
+use core::fmt::{Debug, Write};
 
+use embassy_time::Instant;
 use embedded_graphics::{
     draw_target::DrawTarget,
-    mono_font::{MonoTextStyle, ascii::FONT_6X10},
+    mono_font::MonoTextStyle,
     pixelcolor::BinaryColor,
     prelude::*,
+    primitives::Rectangle,
+    text::{Baseline, Text, renderer::TextRenderer},
 };
 use embedded_text::{
     TextBox,
     alignment::HorizontalAlignment,
     style::{HeightMode, TextBoxStyle, TextBoxStyleBuilder},
 };
+use heapless::Deque;
+
+use crate::{
+    Key, Keypad,
+    held_key::{Event, HeldKey},
+};
 
 #[derive(Clone, PartialEq)]
-pub struct Console<'a>(MonoTextStyle<'a, BinaryColor>, TextBoxStyle);
+pub struct Console(TextBoxStyle);
 
-impl Default for Console<'_> {
+impl Default for Console {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<'a> Console<'a> {
+impl Console {
     pub fn new() -> Self {
         Self(
-            MonoTextStyle::new(&FONT_6X10, BinaryColor::Off),
             TextBoxStyleBuilder::new()
                 .height_mode(HeightMode::FitToText)
                 .alignment(HorizontalAlignment::Left)
@@ -33,13 +41,268 @@ impl<'a> Console<'a> {
         )
     }
 
-    pub fn draw<D: DrawTarget<Color = BinaryColor>>(&self, draw_target: &mut D, text: &'a str)
+    pub fn draw<D: DrawTarget<Color = BinaryColor>>(&self, draw_target: &mut D, text: &str)
     where
         <D as DrawTarget>::Error: Debug,
     {
-        draw_target.clear(BinaryColor::On).unwrap();
-        TextBox::with_textbox_style(text, draw_target.bounding_box(), self.0, self.1)
+        let theme = crate::theme::current();
+        // the theme is read on every draw so that a change shows up on the next frame
+        let style = theme.text_style();
+        draw_target.clear(theme.background).unwrap();
+        TextBox::with_textbox_style(text, draw_target.bounding_box(), style, self.0)
             .draw(draw_target)
             .unwrap();
     }
 }
+
+// the most recent `N` lines of up to `W` bytes each, newest at the bottom. lines too wide for the
+// screen are cut off rather than wrapped so that each one takes a single row
+pub struct Log<const N: usize, const W: usize> {
+    lines: Deque<(Instant, heapless::String<W>), N>,
+    // index of the line at the top of the screen
+    top: usize,
+    // keeps the newest line in view as more arrive
+    follow: bool,
+    timestamps: bool,
+    held_key: HeldKey,
+}
+
+impl<const N: usize, const W: usize> Default for Log<N, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, const W: usize> Log<N, W> {
+    pub fn new() -> Self {
+        Self {
+            lines: Deque::new(),
+            top: 0,
+            follow: true,
+            timestamps: true,
+            held_key: HeldKey::new(500, 100),
+        }
+    }
+
+    fn style() -> MonoTextStyle<'static, BinaryColor> {
+        crate::theme::current().small_text_style()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn is_following(&self) -> bool {
+        self.follow
+    }
+
+    pub fn top(&self) -> usize {
+        self.top
+    }
+
+    pub fn set_timestamps(&mut self, timestamps: bool) {
+        self.timestamps = timestamps;
+    }
+
+    // each line of `text` becomes a line of its own, the oldest lines making room once it is full
+    pub fn push(&mut self, at: Instant, text: &str) {
+        for text in text.lines() {
+            let mut line = heapless::String::new();
+            for c in text.chars() {
+                if line.push(c).is_err() {
+                    break;
+                }
+            }
+            if self.lines.is_full() {
+                self.lines.pop_front();
+                self.top = self.top.saturating_sub(1);
+            }
+            let _ = self.lines.push_back((at, line));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.top = 0;
+        self.follow = true;
+    }
+
+    fn rows(area: Rectangle) -> usize {
+        (area.size.height / Self::style().line_height()).max(1) as usize
+    }
+
+    fn last_top(&self, rows: usize) -> usize {
+        self.lines.len().saturating_sub(rows)
+    }
+
+    // scrolling back to the end starts following again, scrolling away stops it
+    pub fn scroll(&mut self, area: Rectangle, by: isize) {
+        let rows = Self::rows(area);
+        let top = if self.follow {
+            self.last_top(rows)
+        } else {
+            self.top
+        };
+        self.top = top.saturating_add_signed(by).min(self.last_top(rows));
+        self.follow = self.top == self.last_top(rows);
+    }
+
+    pub fn end(&mut self) {
+        self.follow = true;
+    }
+
+    pub fn draw(&mut self, target: &mut impl DrawTarget<Color = BinaryColor>) {
+        crate::theme::current().clear(target);
+        let area = target.bounding_box();
+        let rows = Self::rows(area);
+        if self.follow {
+            self.top = self.last_top(rows);
+        }
+        let style = Self::style();
+        let mut point = area.top_left;
+        for (at, line) in self.lines.iter().skip(self.top).take(rows) {
+            let mut x = point;
+            if self.timestamps {
+                let mut stamp: heapless::String<8> = heapless::String::new();
+                let seconds = at.as_secs();
+                let _ = write!(stamp, "{:02}:{:02} ", seconds / 60 % 100, seconds % 60);
+                x = Text::with_baseline(&stamp, x, style, Baseline::Top)
+                    .draw(target)
+                    .unwrap_or(x);
+            }
+            let _ = Text::with_baseline(line, x, style, Baseline::Top).draw(target);
+            point.y += style.line_height() as i32;
+        }
+        if self.lines.len() > rows {
+            crate::menu::scroll_bar(
+                target,
+                area,
+                self.top,
+                self.top + rows - 1,
+                self.lines.len(),
+            );
+        }
+    }
+
+    // handles one key, false once the log is closed with Cancel. Up and Down move a line, 2 and 8 a
+    // screen, 0 goes to the end and # shows or hides the times
+    pub async fn run(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> bool {
+        self.draw(device);
+        let area = device.bounding_box();
+        let page = Self::rows(area) as isize;
+        match self.held_key.event(device).await {
+            Some(Event::Down(Key::Up) | Event::Delay(Key::Up) | Event::Repeat(Key::Up)) => {
+                self.scroll(area, -1)
+            }
+            Some(Event::Down(Key::Down) | Event::Delay(Key::Down) | Event::Repeat(Key::Down)) => {
+                self.scroll(area, 1)
+            }
+            Some(Event::Down(Key::Two)) => self.scroll(area, -page),
+            Some(Event::Down(Key::Eight)) => self.scroll(area, page),
+            Some(Event::Down(Key::Zero)) => self.end(),
+            Some(Event::Down(Key::Hash)) => self.timestamps = !self.timestamps,
+            Some(Event::Down(Key::Cancel)) => return false,
+            _ => {}
+        }
+        true
+    }
+
+    pub async fn process(&mut self, device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad)) {
+        while self.run(device).await {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+    use crate::KeyEvent;
+
+    fn log() -> Log<4, 8> {
+        let mut log = Log::new();
+        log.push(Instant::from_secs(61), "one\ntwo");
+        log.push(Instant::from_secs(62), "three");
+        log.push(Instant::from_secs(63), "a line too long");
+        log.push(Instant::from_secs(64), "five");
+        log
+    }
+
+    #[test]
+    fn test_ring_buffer() {
+        let log = log();
+        assert_eq!(log.len(), 4);
+        let lines: heapless::Vec<&str, 4> =
+            log.lines.iter().map(|(_, line)| line.as_str()).collect();
+        assert_eq!(lines, ["two", "three", "a line t", "five"]);
+    }
+
+    #[test]
+    fn test_scroll() {
+        let mut log = log();
+        let area = Rectangle::new(Point::zero(), Size::new(40, 18));
+        log.scroll(area, -1);
+        assert_eq!((log.top(), log.is_following()), (1, false));
+        log.scroll(area, -4);
+        assert_eq!((log.top(), log.is_following()), (0, false));
+        // new lines leave the view where it is while scrolled back
+        log.push(Instant::from_secs(65), "six");
+        assert_eq!((log.top(), log.is_following()), (0, false));
+        log.scroll(area, 5);
+        assert_eq!((log.top(), log.is_following()), (2, true));
+    }
+
+    #[test]
+    fn test_keys() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                40,
+                18,
+                &[
+                    (0, KeyEvent::Down(Key::Two)),
+                    (0, KeyEvent::Down(Key::Zero)),
+                    (0, KeyEvent::Down(Key::Cancel)),
+                ],
+            );
+            let mut log = log();
+            assert!(log.run(&mut device).await);
+            assert_eq!((log.top(), log.is_following()), (0, false));
+            log.process(&mut device).await;
+            assert!(log.is_following());
+        });
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut device = crate::test::Device::new(60, 18, &[]);
+        let mut log = log();
+        log.draw(&mut device);
+        device.display.assert_pattern(&[
+            "##########################################################.#",
+            "##..####.###########..###....####################..#####.#.#",
+            "#.##.##..#####..###.##.####.######################.#######.#",
+            "#.##.###.#####..###.##.###..##########...#########.####..#.#",
+            "#.##.###.##########.##.#####.########.##.#########.#####.#.#",
+            "#.##.###.#####..###.##.#####.########.##.#########.#####.#.#",
+            "##..###...####..####..###...##########...########...###..#.#",
+            "##########################################################.#",
+            "##########################################################.#",
+            "#########################################################...",
+            "##..####.###########..#####.###########.####.############...",
+            "#.##.##..#####..###.##.###..##########.#.################...",
+            "#.##.###.#####..###.##.##.#.##########.####..####.##.###....",
+            "#.##.###.##########.##.#.##.#########...####.####.##.##.#...",
+            "#.##.###.#####..###.##.#.....#########.#####.#####..###.....",
+            "##..###...####..####..#####.##########.####...####..####....",
+            "#########################################################...",
+            "#########################################################...",
+        ]);
+    }
+}
@@ -1,5 +1,4 @@
-use embassy_time::Timer;
-use futures::{future, future::Either, pin_mut};
+use embassy_time::{Duration, Instant};
 
 use crate::{Key, KeyEvent};
 
@@ -10,67 +9,43 @@ pub enum Event {
     Repeat(Key),
 }
 
+#[derive(Debug)]
 pub struct HeldKey {
     down: Option<Key>,
-    timer: Option<Timer>,
+    // when the held key next delays or repeats
+    deadline: Option<Instant>,
     delay_duration: u64,
     repeat_period: u64,
     repeating: bool,
 }
 
-use core::fmt;
-
-impl fmt::Debug for HeldKey {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        #[allow(dead_code)]
-        #[derive(Debug)]
-        struct MySettings {
-            down: Option<Key>,
-            delay_duration: u64,
-            repeat_period: u64,
-            repeating: bool,
-        }
-
-        let Self {
-            down,
-            delay_duration,
-            repeat_period,
-            repeating,
-            ..
-        } = self;
-
-        fmt::Debug::fmt(
-            &MySettings {
-                down: down.clone(),
-                delay_duration: *delay_duration,
-                repeat_period: *repeat_period,
-                repeating: *repeating,
-            },
-            f,
-        )
-    }
-}
-
 impl HeldKey {
     pub fn new(delay_duration: u64, repeat_period: u64) -> Self {
         Self {
             down: None,
-            timer: None,
+            deadline: None,
             delay_duration,
             repeat_period,
             repeating: false,
         }
     }
 
+    // forgets the held key, for when its release may have gone to somebody else
+    pub fn reset(&mut self) {
+        self.deadline = None;
+        self.down = None;
+        self.repeating = false;
+    }
+
     fn timeout_event(&mut self) -> Option<Event> {
         let result = self.down.clone();
 
         if self.repeating {
-            self.timer = Some(embassy_time::Timer::after_millis(self.repeat_period));
+            self.deadline = Some(Instant::now() + Duration::from_millis(self.repeat_period));
             result.map(Event::Repeat)
         } else {
             self.repeating = true;
-            self.timer = Some(embassy_time::Timer::after_millis(self.repeat_period));
+            self.deadline = Some(Instant::now() + Duration::from_millis(self.repeat_period));
             result.map(Event::Delay)
         }
     }
@@ -78,14 +53,12 @@ impl HeldKey {
     fn key_event(&mut self, key_event: KeyEvent) -> Option<Event> {
         match key_event {
             KeyEvent::Down(key) => {
-                self.timer = Some(embassy_time::Timer::after_millis(self.delay_duration));
+                self.deadline = Some(Instant::now() + Duration::from_millis(self.delay_duration));
                 self.down = Some(key.clone());
                 Some(Event::Down(key))
             }
             KeyEvent::Up(_key) => {
-                self.timer = None;
-                self.down = None;
-                self.repeating = false;
+                self.reset();
                 None
             }
         }
@@ -95,16 +68,35 @@ impl HeldKey {
     where
         KEYPAD: crate::Keypad,
     {
-        let event_future = keypad.event();
-        pin_mut!(event_future);
+        match self.deadline {
+            Some(deadline) => match keypad.event_before(deadline).await {
+                Some(event) => self.key_event(event),
+                None => self.timeout_event(),
+            },
+            None => self.key_event(keypad.event().await),
+        }
+    }
 
-        if let Some(timer) = &mut self.timer {
-            match future::select(timer, event_future).await {
-                Either::Left((..)) => self.timeout_event(),
-                Either::Right((e, _)) => self.key_event(e),
+    // what `event` would give, or `None` once `until` passes first. a key being held carries on
+    // being held across calls
+    pub async fn event_before<KEYPAD>(
+        &mut self,
+        keypad: &mut KEYPAD,
+        until: Instant,
+    ) -> Option<Option<Event>>
+    where
+        KEYPAD: crate::Keypad,
+    {
+        let deadline = self.deadline.map_or(until, |deadline| deadline.min(until));
+        match keypad.event_before(deadline).await {
+            Some(event) => Some(self.key_event(event)),
+            None if self
+                .deadline
+                .is_some_and(|deadline| deadline <= Instant::now()) =>
+            {
+                Some(self.timeout_event())
             }
-        } else {
-            self.key_event(event_future.await)
+            None => None,
         }
     }
 }
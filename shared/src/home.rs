@@ -0,0 +1,626 @@
+use core::{
+    future::poll_fn,
+    sync::atomic::{AtomicBool, Ordering},
+    task::Poll,
+};
+
+use chrono::Datelike;
+use embassy_futures::select::{Either, select};
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{
+    prelude::*,
+    primitives::Rectangle,
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use heapless::String;
+
+use crate::{
+    Application, Backlight, Buzzer, DisplayPower, Indicators, Key, KeyEvent, Keypad, Rtc, Storage,
+    VibrationMotor, menu::Menu, profile::Profile, status_bar::StatusBar,
+};
+
+// how long without a key press before an app gives way to the home screen
+pub const HOME_TIMEOUT: Duration = Duration::from_secs(30);
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+// the idle screen: a status bar, the time and date, and a launcher behind Select
+pub struct Home<'a, A> {
+    apps: &'a mut [A],
+}
+
+impl<'a, A> Home<'a, A>
+where
+    A: AsRef<str> + Clone,
+{
+    pub fn new(apps: &'a mut [A]) -> Self {
+        Self { apps }
+    }
+
+    pub fn draw(
+        &self,
+        target: &mut impl DrawTarget<Color = BinaryColor>,
+        status: &StatusBar,
+        timestamp: i64,
+    ) {
+        let theme = crate::theme::current();
+        theme.clear(target);
+        let top = status.draw(target).top_left.y;
+        let area = target.bounding_box();
+        let right = area.top_left.x + area.size.width as i32 - 1;
+        let small = theme.small_text_style();
+        let bottom = area.top_left.y + area.size.height as i32 - 1;
+        // like the 3210's two soft keys, Menu over Select and Names over Cancel
+        for (label, x, alignment) in [
+            ("Menu", area.top_left.x, Alignment::Left),
+            ("Names", right, Alignment::Right),
+        ] {
+            let _ = Text::with_text_style(
+                label,
+                Point::new(x, bottom),
+                small,
+                TextStyleBuilder::new()
+                    .alignment(alignment)
+                    .baseline(Baseline::Bottom)
+                    .build(),
+            )
+            .draw(target);
+        }
+        let bottom = bottom - small.font.character_size.height as i32;
+
+        let centre = area.top_left.x + area.size.width as i32 / 2;
+        let top_centre = TextStyleBuilder::new()
+            .alignment(Alignment::Center)
+            .baseline(Baseline::Top)
+            .build();
+        let mut body = target.clipped(&Rectangle::with_corners(
+            Point::new(area.top_left.x, top),
+            Point::new(right, bottom),
+        ));
+        let large = theme.large_text_style();
+        let _ = Text::with_text_style(
+            &crate::time::format_time(timestamp, false),
+            Point::new(centre, top + 1),
+            large,
+            top_centre,
+        )
+        .draw(&mut body);
+        let _ = Text::with_text_style(
+            &date(timestamp),
+            Point::new(centre, top + 2 + large.font.character_size.height as i32),
+            theme.small_text_style(),
+            top_centre,
+        )
+        .draw(&mut body);
+    }
+
+    // shows the home screen until Select picks an app from the launcher. the launcher gives up and
+    // comes back here if it is left alone
+    pub async fn process<D>(&mut self, device: &mut D) -> A
+    where
+        D: DrawTarget<Color = BinaryColor> + Keypad + Rtc + Indicators + Send,
+    {
+        loop {
+            let timestamp = device.timestamp().unwrap_or_default();
+            let status = StatusBar::read(device);
+            self.draw(device, &status, timestamp);
+
+            let next_minute = Duration::from_secs(60 - timestamp.rem_euclid(60) as u64);
+            if let Some(KeyEvent::Down(Key::Select)) =
+                device.event_before(Instant::now() + next_minute).await
+            {
+                let apps = &mut *self.apps;
+                let chosen = until_idle(device, HOME_TIMEOUT, async |device| {
+                    Menu::new(apps, Some("Open"), |a, b, c, d, e| {
+                        crate::menu::row_render(a, b, c, d, e)
+                    })
+                    .with_shortcuts()
+                    .process(device)
+                    .await
+                })
+                .await;
+                if let Some(Some(app)) = chosen {
+                    return app;
+                }
+            }
+        }
+    }
+}
+
+// the apps the launcher offers, as a tuple of `(name, app)` pairs in the order they are listed
+pub trait Apps {
+    fn name(&self, index: usize) -> Option<&'static str>;
+
+    fn run(
+        &mut self,
+        index: usize,
+        device: &mut impl crate::Device,
+    ) -> impl Future<Output = Result<(), ()>>;
+}
+
+macro_rules! apps {
+    ($($index:tt $app:ident),+) => {
+        impl<$($app: Application),+> Apps for ($((&'static str, $app),)+) {
+            fn name(&self, index: usize) -> Option<&'static str> {
+                match index {
+                    $($index => Some(self.$index.0),)+
+                    _ => None,
+                }
+            }
+
+            async fn run(
+                &mut self,
+                index: usize,
+                device: &mut impl crate::Device,
+            ) -> Result<(), ()> {
+                match index {
+                    $($index => self.$index.1.run(device).await,)+
+                    _ => Err(()),
+                }
+            }
+        }
+    };
+}
+
+apps!(0 A);
+apps!(0 A, 1 B);
+apps!(0 A, 1 B, 2 C);
+apps!(0 A, 1 B, 2 C, 3 D);
+apps!(0 A, 1 B, 2 C, 3 D, 4 E);
+apps!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F);
+
+// an app as listed in the launcher, found again by where it is in `Apps`
+#[derive(Clone, Copy)]
+struct Entry {
+    index: usize,
+    name: &'static str,
+}
+
+impl AsRef<str> for Entry {
+    fn as_ref(&self) -> &str {
+        self.name
+    }
+}
+
+struct Launched<'a, T> {
+    apps: &'a mut T,
+    index: usize,
+}
+
+impl<T: Apps> Application for Launched<'_, T> {
+    fn run(&mut self, device: &mut impl crate::Device) -> impl Future<Output = Result<(), ()>> {
+        self.apps.run(self.index, device)
+    }
+}
+
+// the home screen for good, running whichever of `apps` the launcher opens until it gives up or is
+// left alone
+pub async fn home<D>(device: &mut D, apps: &mut impl Apps) -> !
+where
+    D: crate::Device + Send,
+{
+    let mut entries: heapless::Vec<Entry, 6> = (0..)
+        .map_while(|index| {
+            Some(Entry {
+                index,
+                name: apps.name(index)?,
+            })
+        })
+        .collect();
+    let mut home = Home::new(&mut entries);
+    loop {
+        let index = home.process(device).await.index;
+        run_until_idle(&mut Launched { apps, index }, device, HOME_TIMEOUT).await;
+    }
+}
+
+// e.g. "Sat 1 Feb"
+fn date(timestamp: i64) -> String<16> {
+    let mut text = String::new();
+    if let Some(now) = chrono::DateTime::from_timestamp(timestamp, 0) {
+        let _ = core::fmt::write(
+            &mut text,
+            format_args!(
+                "{} {} {}",
+                now.weekday(),
+                now.day(),
+                MONTHS[now.month0() as usize]
+            ),
+        );
+    }
+    text
+}
+
+// runs `f` until it finishes or no key has been pressed for `timeout`, whichever comes first.
+// `None` means it was left alone for too long
+pub async fn until_idle<D, R>(
+    device: &mut D,
+    timeout: Duration,
+    f: impl AsyncFnOnce(&mut Inactivity<'_, D>) -> R,
+) -> Option<R>
+where
+    D: Keypad + Send,
+{
+    let expired = AtomicBool::new(false);
+    let mut watched = Inactivity {
+        device,
+        timeout,
+        last_activity: Instant::now(),
+        expired: &expired,
+    };
+    match select(
+        f(&mut watched),
+        poll_fn(|_| {
+            if expired.load(Ordering::Relaxed) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        }),
+    )
+    .await
+    {
+        Either::First(result) => Some(result),
+        Either::Second(()) => None,
+    }
+}
+
+// runs an app until it gives up or is left alone long enough to go back to the home screen. an
+// app dropped part way through a tune or a buzz doesn't get to stop it itself
+pub async fn run_until_idle<D>(app: &mut impl Application, device: &mut D, timeout: Duration)
+where
+    D: crate::Device + Send,
+{
+    let finished = until_idle(
+        device,
+        timeout,
+        async |device| {
+            while app.run(device).await.is_ok() {}
+        },
+    )
+    .await;
+    if finished.is_none() {
+        let _ = device.mute_buzzer();
+        device.stop_vibrating();
+    }
+}
+
+// passes everything through to the device, noting when keys were last pressed. once none have
+// been for `timeout` it flags that and stops handing out keys, so that `until_idle` can drop
+// whatever was waiting on them
+pub struct Inactivity<'a, D> {
+    device: &'a mut D,
+    timeout: Duration,
+    last_activity: Instant,
+    expired: &'a AtomicBool,
+}
+
+impl<D> Keypad for Inactivity<'_, D>
+where
+    D: Keypad + Send,
+{
+    async fn event(&mut self) -> KeyEvent {
+        loop {
+            if let Some(event) = self.event_before(Instant::MAX).await {
+                return event;
+            }
+        }
+    }
+
+    async fn event_before(&mut self, deadline: Instant) -> Option<KeyEvent> {
+        let idle = self.last_activity + self.timeout;
+        match self.device.event_before(deadline.min(idle)).await {
+            Some(event) => {
+                self.last_activity = Instant::now();
+                Some(event)
+            }
+            None if Instant::now() >= idle => {
+                self.expired.store(true, Ordering::Relaxed);
+                core::future::pending().await
+            }
+            None => None,
+        }
+    }
+
+    fn bounce_count(&mut self, key: &Key) -> Option<u32> {
+        self.device.bounce_count(key)
+    }
+
+    fn last_pressed(&mut self) -> Option<Duration> {
+        self.device.last_pressed()
+    }
+}
+
+impl<D> DrawTarget for Inactivity<'_, D>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    type Color = BinaryColor;
+
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.device.draw_iter(pixels)
+    }
+}
+
+impl<D> Dimensions for Inactivity<'_, D>
+where
+    D: Dimensions,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.device.bounding_box()
+    }
+}
+
+impl<D> Backlight for Inactivity<'_, D>
+where
+    D: Backlight,
+{
+    fn on(&mut self) {
+        self.device.on();
+    }
+
+    fn off(&mut self) {
+        self.device.off();
+    }
+
+    fn set_brightness(&mut self, level: u8) {
+        self.device.set_brightness(level);
+    }
+}
+
+impl<D> VibrationMotor for Inactivity<'_, D>
+where
+    D: VibrationMotor,
+{
+    fn start_vibrating(&mut self) {
+        self.device.start_vibrating();
+    }
+
+    fn stop_vibrating(&mut self) {
+        self.device.stop_vibrating();
+    }
+
+    fn set_intensity(&mut self, level: u8) {
+        self.device.set_intensity(level);
+    }
+}
+
+impl<D> Buzzer for Inactivity<'_, D>
+where
+    D: Buzzer,
+{
+    type Error = D::Error;
+
+    fn set_frequency(&mut self, frequency: u16) -> Result<(), Self::Error> {
+        self.device.set_frequency(frequency)
+    }
+
+    fn set_volume(&mut self, volume: u8) {
+        self.device.set_volume(volume);
+    }
+
+    fn mute_buzzer(&mut self) -> Result<(), Self::Error> {
+        self.device.mute_buzzer()
+    }
+
+    fn unmute_buzzer(&mut self) -> Result<(), Self::Error> {
+        self.device.unmute_buzzer()
+    }
+}
+
+impl<D> Rtc for Inactivity<'_, D>
+where
+    D: Rtc,
+{
+    type Error = D::Error;
+
+    fn timestamp(&mut self) -> Result<i64, Self::Error> {
+        self.device.timestamp()
+    }
+
+    fn set_timestamp(&mut self, time: i64) {
+        self.device.set_timestamp(time);
+    }
+}
+
+impl<D> DisplayPower for Inactivity<'_, D>
+where
+    D: DisplayPower,
+{
+    fn power_down(&mut self) {
+        self.device.power_down();
+    }
+
+    fn power_up(&mut self) {
+        self.device.power_up();
+    }
+}
+
+impl<D> Indicators for Inactivity<'_, D>
+where
+    D: Indicators,
+{
+    fn battery_level(&mut self) -> Option<u8> {
+        self.device.battery_level()
+    }
+
+    fn usb_connected(&mut self) -> bool {
+        self.device.usb_connected()
+    }
+
+    fn profile(&self) -> Profile {
+        self.device.profile()
+    }
+
+    fn keys_locked(&self) -> bool {
+        self.device.keys_locked()
+    }
+}
+
+impl<D> Storage for Inactivity<'_, D>
+where
+    D: Storage,
+{
+    fn load(&mut self, key: &str, buffer: &mut [u8]) -> Option<usize> {
+        self.device.load(key, buffer)
+    }
+
+    fn store(&mut self, key: &str, data: &[u8]) {
+        self.device.store(key, data);
+    }
+}
+
+impl<D> crate::Device for Inactivity<'_, D> where D: crate::Device + Send {}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn test_launch() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                60,
+                48,
+                &[
+                    (0, KeyEvent::Down(Key::Select)),
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Down(Key::Select)),
+                ],
+            );
+            let mut apps = ["Snake", "Clock"];
+            let mut home = Home::new(&mut apps);
+            assert_eq!(home.process(&mut device).await, "Clock");
+        });
+    }
+
+    #[test]
+    fn test_until_idle() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                60,
+                48,
+                &[
+                    (20_000, KeyEvent::Down(Key::Two)),
+                    (20_000, KeyEvent::Down(Key::Select)),
+                    (0, KeyEvent::Down(Key::Two)),
+                ],
+            );
+            let select = async |device: &mut Inactivity<'_, crate::test::Device<'_>>| {
+                while device.event().await != KeyEvent::Down(Key::Select) {}
+            };
+            assert_eq!(
+                until_idle(&mut device, HOME_TIMEOUT, select).await,
+                Some(())
+            );
+            assert_eq!(until_idle(&mut device, HOME_TIMEOUT, select).await, None);
+        });
+    }
+
+    struct Tune;
+
+    impl Application for Tune {
+        async fn run(&mut self, device: &mut impl crate::Device) -> Result<(), ()> {
+            let _ = device.unmute_buzzer();
+            let _ = device.set_frequency(440);
+            device.start_vibrating();
+            // one long note, waited out the way ringtones does
+            loop {
+                let _ = device.event_before(Instant::now() + HOME_TIMEOUT * 2).await;
+            }
+        }
+    }
+
+    #[test]
+    fn test_apps() {
+        let apps = (("Tune", Tune), ("Another", Tune));
+        assert_eq!(apps.name(1), Some("Another"));
+        assert_eq!(apps.name(2), None);
+    }
+
+    #[test]
+    fn test_silenced_when_idle() {
+        block_on(async {
+            let mut device = crate::test::Device::new(1, 1, &[(60_000, KeyEvent::Down(Key::Two))]);
+            run_until_idle(&mut Tune, &mut device, HOME_TIMEOUT).await;
+            assert_eq!(device.frequency, None);
+            assert!(!device.vibrating);
+        });
+    }
+
+    #[test]
+    fn test_date() {
+        assert_eq!(date(0), "Thu 1 Jan");
+        assert_eq!(date(1_738_368_000), "Sat 1 Feb");
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut device = crate::test::Device::new(60, 48, &[]);
+        device.timestamp = 13 * 3600 + 5 * 60;
+        let status = StatusBar::read(&mut device);
+        let timestamp = device.timestamp;
+        let mut apps = ["Snake"];
+        Home::new(&mut apps).draw(&mut device, &status, timestamp);
+        device.display.assert_pattern(&[
+            "#.##...######.##...#########################################",
+            "..####.##.##.#.#.###########################################",
+            "#.###.######...#..##########################################",
+            "#.####.#####.#.###.#########################################",
+            "...#..###.###.##..##########################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "##########..#######....#################..#####........#####",
+            "#########...######..##..###############....####..###########",
+            "########....#####..####..#############..##..###..###########",
+            "#######..#..#####..####..#############..##..###..###########",
+            "##########..###########..############..####..##..###########",
+            "##########..##########..######...####..####..##..#...#######",
+            "##########..########...#######...####..####..##...##..######",
+            "##########..##########..#############..####..########..#####",
+            "##########..###########..############..####..########..#####",
+            "##########..#####..####..#############..##..#########..#####",
+            "##########..#####..####..#############..##..###..####..#####",
+            "##########..######..##..######...######....#####..##..######",
+            "#######........####....#######...#######..#######....#######",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "####.....##.##################.###########...###############",
+            "######.####.#################..############.################",
+            "######.####...###.##.#########.############.####...##...####",
+            "######.####.##.##.##.#########.############.###.##.##.##.###",
+            "######.####.##.##.##.#########.#########.##.###.##.##.##.###",
+            "######.####.##.###...########...#########..#####...##.##.###",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            ".###.##########################.##.#########################",
+            "..#..##########################..#.#########################",
+            ".#.#.###..###...###.##.########.#..###...#..#.####..####...#",
+            ".#.#.##.#..##.##.##.##.########.##.##.##.#.#.#.##.#..##..###",
+            ".###.##..####.##.##.##.########.##.##.##.#.#.#.##..######..#",
+            ".###.###...##.##.###...########.##.###...#.###.###...##...##",
+            "############################################################",
+            "############################################################",
+        ]);
+    }
+}
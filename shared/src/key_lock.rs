@@ -0,0 +1,182 @@
+use embassy_time::{Duration, Instant};
+
+use crate::{Key, KeyEvent};
+
+// how long after Select the Asterisk still counts as part of the lock/unlock sequence
+pub const SEQUENCE_WINDOW: Duration = Duration::from_millis(1500);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Drop,
+    Lock,
+    Unlock,
+}
+
+#[derive(Debug)]
+pub struct KeyLock {
+    locked: bool,
+    select_at: Option<Instant>,
+    release: Option<Key>,
+    idle_timeout: Option<Duration>,
+}
+
+impl KeyLock {
+    pub fn new(idle_timeout: Option<Duration>) -> Self {
+        Self {
+            locked: false,
+            select_at: None,
+            release: None,
+            idle_timeout,
+        }
+    }
+
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn lock(&mut self) {
+        self.locked = true;
+        self.select_at = None;
+    }
+
+    pub fn set_idle_timeout(&mut self, idle_timeout: Option<Duration>) {
+        self.idle_timeout = idle_timeout;
+    }
+
+    pub fn idle_deadline(&self, last_activity: Instant) -> Option<Instant> {
+        if self.locked {
+            None
+        } else {
+            self.idle_timeout.map(|timeout| last_activity + timeout)
+        }
+    }
+
+    pub fn process(&mut self, event: &KeyEvent, at: Instant) -> Verdict {
+        match event {
+            KeyEvent::Down(Key::Asterisk)
+                if self
+                    .select_at
+                    .take()
+                    .is_some_and(|select_at| at - select_at <= SEQUENCE_WINDOW) =>
+            {
+                self.release = Some(Key::Asterisk);
+                self.locked = !self.locked;
+                if self.locked {
+                    Verdict::Lock
+                } else {
+                    Verdict::Unlock
+                }
+            }
+            KeyEvent::Down(key) => {
+                self.select_at = if *key == Key::Select { Some(at) } else { None };
+                if self.locked {
+                    Verdict::Drop
+                } else {
+                    Verdict::Pass
+                }
+            }
+            KeyEvent::Up(key) => {
+                if self.release.as_ref() == Some(key) {
+                    self.release = None;
+                    Verdict::Drop
+                } else if self.locked {
+                    Verdict::Drop
+                } else {
+                    Verdict::Pass
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn at(millis: u64) -> Instant {
+        Instant::from_millis(millis)
+    }
+
+    #[test]
+    fn test_lock_and_unlock() {
+        let mut key_lock = KeyLock::new(None);
+
+        assert_eq!(
+            key_lock.process(&KeyEvent::Down(Key::Select), at(0)),
+            Verdict::Pass
+        );
+        assert_eq!(
+            key_lock.process(&KeyEvent::Up(Key::Select), at(100)),
+            Verdict::Pass
+        );
+        assert_eq!(
+            key_lock.process(&KeyEvent::Down(Key::Asterisk), at(500)),
+            Verdict::Lock
+        );
+        assert_eq!(
+            key_lock.process(&KeyEvent::Up(Key::Asterisk), at(600)),
+            Verdict::Drop
+        );
+        assert!(key_lock.locked());
+
+        assert_eq!(
+            key_lock.process(&KeyEvent::Down(Key::Five), at(1000)),
+            Verdict::Drop
+        );
+        assert_eq!(
+            key_lock.process(&KeyEvent::Down(Key::Asterisk), at(1100)),
+            Verdict::Drop
+        );
+        assert_eq!(
+            key_lock.process(&KeyEvent::Down(Key::Select), at(2000)),
+            Verdict::Drop
+        );
+        assert_eq!(
+            key_lock.process(&KeyEvent::Down(Key::Asterisk), at(2100)),
+            Verdict::Unlock
+        );
+        assert!(!key_lock.locked());
+        assert_eq!(
+            key_lock.process(&KeyEvent::Up(Key::Asterisk), at(2200)),
+            Verdict::Drop
+        );
+        assert_eq!(
+            key_lock.process(&KeyEvent::Down(Key::Five), at(3000)),
+            Verdict::Pass
+        );
+    }
+
+    #[test]
+    fn test_slow_sequence() {
+        let mut key_lock = KeyLock::new(None);
+
+        key_lock.process(&KeyEvent::Down(Key::Select), at(0));
+        assert_eq!(
+            key_lock.process(&KeyEvent::Down(Key::Asterisk), at(2000)),
+            Verdict::Pass
+        );
+        assert!(!key_lock.locked());
+    }
+
+    #[test]
+    fn test_interrupted_sequence() {
+        let mut key_lock = KeyLock::new(None);
+
+        key_lock.process(&KeyEvent::Down(Key::Select), at(0));
+        key_lock.process(&KeyEvent::Down(Key::Two), at(100));
+        assert_eq!(
+            key_lock.process(&KeyEvent::Down(Key::Asterisk), at(200)),
+            Verdict::Pass
+        );
+    }
+
+    #[test]
+    fn test_idle_deadline() {
+        let mut key_lock = KeyLock::new(Some(Duration::from_secs(30)));
+
+        assert_eq!(key_lock.idle_deadline(at(1000)), Some(at(31000)));
+        key_lock.lock();
+        assert_eq!(key_lock.idle_deadline(at(1000)), None);
+    }
+}
@@ -3,20 +3,34 @@
 #![feature(trivial_bounds)]
 #![no_std]
 
+pub mod backlight;
 pub mod character_select;
 pub mod confirmation;
 pub mod console;
 pub mod grid;
 pub mod held_key;
+pub mod home;
+pub mod key_lock;
 pub mod menu;
 pub mod multitap;
+pub mod notification;
+pub mod power;
+pub mod profile;
+pub mod screensaver;
+pub mod sequencer;
+pub mod status_bar;
+pub mod system;
+pub mod t9;
 pub mod test;
 pub mod textbox;
+pub mod theme;
 pub mod time;
+pub mod tones;
+pub mod vibration;
+pub mod widget;
 
 use core::{ascii::Char, future::Future};
 
-use embassy_time::Duration;
 use embedded_graphics_core::{draw_target::DrawTarget, pixelcolor::BinaryColor};
 use enum_iterator::Sequence;
 use strum_macros::IntoStaticStr;
@@ -25,11 +39,19 @@ use usbd_hid::descriptor::KeyboardUsage;
 pub trait Backlight {
     fn on(&mut self);
     fn off(&mut self);
+
+    // percent. lights that can only be on or off should leave this be
+    fn set_brightness(&mut self, level: u8) {
+        if level > 0 { self.on() } else { self.off() }
+    }
 }
 
 pub trait VibrationMotor {
     fn start_vibrating(&mut self);
     fn stop_vibrating(&mut self);
+
+    // percent. motors that are only ever fully on can ignore this
+    fn set_intensity(&mut self, _level: u8) {}
 }
 
 pub trait Buzzer {
@@ -41,6 +63,12 @@ pub trait Buzzer {
     fn unmute_buzzer(&mut self) -> Result<(), Self::Error>;
 }
 
+// displays that can be put to sleep while nothing is shown. the defaults suit one that can't
+pub trait DisplayPower {
+    fn power_down(&mut self) {}
+    fn power_up(&mut self) {}
+}
+
 pub enum ButtonEvent {
     Up,
     Down,
@@ -75,6 +103,7 @@ pub enum Key {
     Asterisk,
     Zero,
     Hash,
+    Power,
 }
 
 impl From<Key> for Char {
@@ -96,6 +125,7 @@ impl From<Key> for Char {
             Key::Asterisk => Char::Asterisk,
             Key::Zero => Char::Space,
             Key::Hash => Char::NumberSign,
+            Key::Power => Char::Null,
         }
     }
 }
@@ -106,18 +136,100 @@ pub enum KeyEvent {
     Down(Key),
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimedKeyEvent {
+    pub event: KeyEvent,
+    pub at: embassy_time::Instant,
+}
+
 pub trait Keypad {
     fn event(&mut self) -> impl core::future::Future<Output = KeyEvent> + core::marker::Send;
 
+    // keypads that know when an edge actually happened (before debouncing) should override this
+    fn timed_event(&mut self) -> impl core::future::Future<Output = TimedKeyEvent> + Send
+    where
+        Self: Send,
+    {
+        async {
+            let event = self.event().await;
+            TimedKeyEvent {
+                event,
+                at: embassy_time::Instant::now(),
+            }
+        }
+    }
+
+    // the next event, or `None` once `deadline` passes without one. this is how to wait on keys
+    // and a timer together: keypads that do more in `event` than wait for a key (the system
+    // handling its own keys and overlays) can't be dropped part way through it, and override this
+    // to give up only in between
+    fn event_before(
+        &mut self,
+        deadline: embassy_time::Instant,
+    ) -> impl core::future::Future<Output = Option<KeyEvent>> + Send {
+        let event = self.event();
+        async move {
+            // the deadline goes first, so a key that comes in after it doesn't hold it up
+            match embassy_futures::select::select(embassy_time::Timer::at(deadline), event).await {
+                embassy_futures::select::Either::First(()) => None,
+                embassy_futures::select::Either::Second(event) => Some(event),
+            }
+        }
+    }
+
+    // edges that were swallowed by debouncing the last time `key` changed state
+    fn bounce_count(&mut self, _key: &Key) -> Option<u32> {
+        None
+    }
+
     fn last_pressed(&mut self) -> Option<embassy_time::Duration>;
 }
 
+// what the status bar shows besides the time. the defaults suit a device that can't tell
+pub trait Indicators {
+    // percent, if there is a battery to measure
+    fn battery_level(&mut self) -> Option<u8> {
+        None
+    }
+
+    fn usb_connected(&mut self) -> bool {
+        false
+    }
+
+    fn profile(&self) -> profile::Profile {
+        profile::Profile::default()
+    }
+
+    fn keys_locked(&self) -> bool {
+        false
+    }
+}
+
+// somewhere small settings outlive a restart, keyed by name. the defaults suit a device with
+// nowhere to keep them
+pub trait Storage {
+    // how much of `buffer` was filled, if anything is stored under `key`
+    fn load(&mut self, _key: &str, _buffer: &mut [u8]) -> Option<usize> {
+        None
+    }
+
+    fn store(&mut self, _key: &str, _data: &[u8]) {}
+}
+
 pub trait Application {
     fn run(&mut self, device: &mut impl Device) -> impl Future<Output = Result<(), ()>>;
 }
 
 pub trait Device:
-    VibrationMotor + Buzzer + Keypad + Rtc + Backlight + DrawTarget<Color = BinaryColor, Error = ()>
+    VibrationMotor
+    + Buzzer
+    + Keypad
+    + Rtc
+    + Backlight
+    + DisplayPower
+    + Indicators
+    + Storage
+    + DrawTarget<Color = BinaryColor, Error = ()>
 {
 }
 
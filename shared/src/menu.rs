@@ -2,17 +2,146 @@ use core::marker::PhantomData;
 
 use embedded_graphics::{
     draw_target::{Clipped, DrawTargetExt},
-    mono_font::{
-        MonoTextStyle,
-        ascii::{FONT_6X9, FONT_6X10},
-    },
+    mono_font::MonoTextStyle,
     prelude::*,
     primitives::PrimitiveStyle,
     text::{Text, renderer::TextRenderer},
 };
 use embedded_graphics_core::{pixelcolor::BinaryColor, primitives::Rectangle};
 
-use crate::held_key::HeldKey;
+use crate::{
+    Key,
+    held_key::{Event, HeldKey},
+    multitap::{self, MultiTap},
+};
+
+pub mod tree;
+
+// letters typed so far to narrow down the items
+struct Filter {
+    multitap: MultiTap,
+    text: heapless::String<16>,
+    // whether the last letter can still be changed by pressing its key again
+    tentative: bool,
+}
+
+impl Filter {
+    fn new() -> Self {
+        let mut multitap = MultiTap::new(750);
+        while multitap.pending_event().is_some() {}
+        Self {
+            multitap,
+            text: heapless::String::new(),
+            tentative: false,
+        }
+    }
+
+    fn type_letter(&mut self, event: multitap::Event) {
+        let (c, tentative) = match event {
+            multitap::Event::Tentative(c) => (c, true),
+            multitap::Event::Decided(c) => (c, false),
+            _ => return,
+        };
+        if c == multitap::BACKSPACE {
+            self.text.pop();
+        } else {
+            if self.tentative {
+                self.text.pop();
+            }
+            let _ = self.text.push(c);
+        }
+        self.tentative = tentative && c != multitap::BACKSPACE;
+    }
+
+    fn key(&mut self, key: Event) {
+        if let Some(event) = self.multitap.key(Some(key)) {
+            self.type_letter(event);
+        }
+        while let Some(event) = self.multitap.pending_event() {
+            self.type_letter(event);
+        }
+    }
+
+    // once its key is let go of, the last letter is decided if it isn't pressed again in time
+    fn release(&mut self) {
+        self.multitap.key(None);
+    }
+
+    fn timeout(&mut self) {
+        if let Some(event) = self.multitap.timeout() {
+            self.type_letter(event);
+        }
+    }
+}
+
+// whether any word of `item` starts with `filter`, ignoring case
+fn matches(item: &str, filter: &str) -> bool {
+    filter.is_empty()
+        || item.split(' ').any(|word| {
+            let mut word = word.chars().flat_map(char::to_lowercase);
+            filter
+                .chars()
+                .flat_map(char::to_lowercase)
+                .all(|c| word.next() == Some(c))
+        })
+}
+
+// 1 is the first item, up to 9
+fn shortcut(key: &Key) -> Option<usize> {
+    match key {
+        Key::One => Some(0),
+        Key::Two => Some(1),
+        Key::Three => Some(2),
+        Key::Four => Some(3),
+        Key::Five => Some(4),
+        Key::Six => Some(5),
+        Key::Seven => Some(6),
+        Key::Eight => Some(7),
+        Key::Nine => Some(8),
+        _ => None,
+    }
+}
+
+pub const SCROLL_BAR_WIDTH: u32 = 3;
+
+// a line down the right of `area` with a block on it covering the share of `count` items from
+// `first` to `last` that can be seen
+pub fn scroll_bar(
+    target: &mut impl DrawTarget<Color = BinaryColor>,
+    area: Rectangle,
+    first: usize,
+    last: usize,
+    count: usize,
+) {
+    let height = area.size.height as usize;
+    if count == 0 || height == 0 {
+        return;
+    }
+    let theme = crate::theme::current();
+    let x = area.top_left.x + (area.size.width - SCROLL_BAR_WIDTH) as i32;
+    let track = Rectangle::new(
+        Point::new(x, area.top_left.y),
+        Size::new(SCROLL_BAR_WIDTH, area.size.height),
+    );
+    let _ = track
+        .into_styled(PrimitiveStyle::with_fill(theme.background))
+        .draw(target);
+    let _ = Rectangle::new(
+        Point::new(x + 1, area.top_left.y),
+        Size::new(1, area.size.height),
+    )
+    .into_styled(theme.fill())
+    .draw(target);
+
+    let thumb_height = (height * (last + 1 - first) / count).clamp(3.min(height), height);
+    let top = (height * first / count).min(height - thumb_height);
+    let _ = Rectangle::new(
+        Point::new(x, area.top_left.y + top as i32),
+        Size::new(SCROLL_BAR_WIDTH, thumb_height as u32),
+    )
+    .into_styled(theme.fill())
+    .draw(target);
+}
 
 pub struct Menu<'a, T, F, D>
 where
@@ -33,6 +162,8 @@ where
     page_size: usize,
     held_key: HeldKey,
     select_label: Option<&'a str>,
+    filter: Option<Filter>,
+    shortcuts: bool,
     renderer: F,
     display: PhantomData<D>,
 }
@@ -59,14 +190,57 @@ where
             page_size: 0,
             held_key: HeldKey::new(750, 250),
             select_label,
+            filter: None,
+            shortcuts: false,
             renderer,
             display: PhantomData,
         }
     }
 
+    // keys type letters that narrow the items down instead of being shortcuts
+    pub fn with_filter(self) -> Self {
+        Self {
+            filter: Some(Filter::new()),
+            ..self
+        }
+    }
+
+    // 1 to 9 pick the first nine items straight away
+    pub fn with_shortcuts(self) -> Self {
+        Self {
+            shortcuts: true,
+            ..self
+        }
+    }
+
+    fn filter_text(&self) -> &str {
+        self.filter
+            .as_ref()
+            .map_or("", |filter| filter.text.as_str())
+    }
+
+    fn matching(&self) -> impl Iterator<Item = &T> {
+        let filter = self.filter_text();
+        self.items
+            .iter()
+            .filter(move |item| matches(item.as_ref(), filter))
+    }
+
+    fn filter_key(&mut self, key: Event) {
+        if let Some(filter) = &mut self.filter {
+            filter.key(key);
+            self.index = 0;
+            self.start_of_page_index = 0;
+            self.bottom_visible_index = 0;
+            self.page_size = 0;
+        }
+    }
+
     fn down(&mut self) {
-        if self.index < self.items.len() - 1 {
+        if self.index + 1 < self.matching().count() {
             self.index += 1;
+        } else {
+            crate::tones::warn();
         }
         if self.index > self.bottom_visible_index {
             self.page_size = self.index - self.start_of_page_index;
@@ -77,6 +251,8 @@ where
     fn up(&mut self) {
         if self.index > 0 {
             self.index -= 1;
+        } else {
+            crate::tones::warn();
         }
         if self.index < self.start_of_page_index {
             self.start_of_page_index = self.index.saturating_sub(self.page_size - 1);
@@ -84,27 +260,17 @@ where
     }
 
     fn text_style(&self, selected: bool) -> MonoTextStyle<'_, BinaryColor> {
-        embedded_graphics::mono_font::MonoTextStyleBuilder::new()
-            .text_color(if selected {
-                BinaryColor::On
-            } else {
-                BinaryColor::Off
-            })
-            .font(&FONT_6X10)
-            .build()
+        let theme = crate::theme::current();
+        MonoTextStyle::new(theme.font, theme.colors(selected).0)
     }
 
     fn draw(&mut self, target: &mut D) -> Result<(), ()> {
-        let _ = target
-            .bounding_box()
-            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
-            .draw(target);
+        let theme = crate::theme::current();
+        theme.clear(target);
 
         let mut target = if let Some(select_label) = self.select_label {
-            let text_bounding_box = embedded_graphics::mono_font::MonoTextStyleBuilder::new()
-                .text_color(BinaryColor::Off)
-                .font(&FONT_6X9)
-                .build()
+            let text_bounding_box = theme
+                .small_text_style()
                 .measure_string(
                     select_label,
                     Point::new(
@@ -126,10 +292,7 @@ where
                     (target.bounding_box().size.width / 2).try_into().unwrap(),
                     target.bounding_box().size.height as i32 - 1,
                 ),
-                embedded_graphics::mono_font::MonoTextStyleBuilder::new()
-                    .text_color(BinaryColor::Off)
-                    .font(&FONT_6X9)
-                    .build(),
+                theme.small_text_style(),
                 text_style,
             )
             .draw(target);
@@ -145,9 +308,30 @@ where
             target.clipped(&Rectangle::new(Point::zero(), target.bounding_box().size))
         };
 
+        let mut top = 0;
+        if !self.filter_text().is_empty() {
+            let style = theme.small_text_style();
+            let _ = Text::with_baseline(
+                self.filter_text(),
+                Point::zero(),
+                style,
+                embedded_graphics::text::Baseline::Top,
+            )
+            .draw(&mut target);
+            top = style.line_height();
+        }
+
         let mut last_drawn_index = 0;
-        let mut point = Point::zero();
-        for (index, a) in self.items[self.start_of_page_index..].iter().enumerate() {
+        let mut point = Point::new(0, top as i32);
+        let matching = self.matching().count();
+        let start = self.start_of_page_index;
+        let filter = self.filter_text();
+        let page = self
+            .items
+            .iter()
+            .filter(|item| matches(item.as_ref(), filter))
+            .skip(start);
+        for (index, a) in page.enumerate() {
             if let Some(p) = (self.renderer)(
                 &mut target.clipped(&target.bounding_box()),
                 (self.index - self.start_of_page_index) == index,
@@ -160,13 +344,43 @@ where
             }
         }
         self.bottom_visible_index = last_drawn_index + self.start_of_page_index;
+
+        if self.start_of_page_index > 0 || self.bottom_visible_index + 1 < matching {
+            let area = target.bounding_box();
+            scroll_bar(
+                &mut target,
+                Rectangle::new(
+                    area.top_left + Point::new(0, top as i32),
+                    Size::new(area.size.width, area.size.height.saturating_sub(top)),
+                ),
+                self.start_of_page_index,
+                self.bottom_visible_index,
+                matching,
+            );
+        }
         Ok(())
     }
 
     pub async fn process(&mut self, device: &mut D) -> Option<T> {
         loop {
             if let Ok(()) = self.draw(device) {
-                match self.held_key.event(device).await {
+                let decide_at = self
+                    .filter
+                    .as_ref()
+                    .and_then(|filter| filter.multitap.decide_at());
+                let event = match decide_at {
+                    Some(decide_at) => match self.held_key.event_before(device, decide_at).await {
+                        Some(event) => event,
+                        None => {
+                            if let Some(filter) = &mut self.filter {
+                                filter.timeout();
+                            }
+                            continue;
+                        }
+                    },
+                    None => self.held_key.event(device).await,
+                };
+                match event {
                     Some(
                         crate::held_key::Event::Down(crate::Key::Down)
                         | crate::held_key::Event::Delay(crate::Key::Down)
@@ -181,11 +395,26 @@ where
                     ) => {
                         self.up();
                     }
-                    Some(crate::held_key::Event::Down(crate::Key::Cancel)) => {
+                    Some(Event::Down(Key::Cancel)) if self.filter_text().is_empty() => {
                         return None;
                     }
-                    Some(crate::held_key::Event::Down(crate::Key::Select)) => {
-                        return Some(self.items[self.index].clone());
+                    Some(Event::Down(Key::Select)) => {
+                        if let Some(item) = self.matching().nth(self.index) {
+                            return Some(item.clone());
+                        }
+                    }
+                    Some(key @ (Event::Down(_) | Event::Delay(_))) if self.filter.is_some() => {
+                        self.filter_key(key);
+                    }
+                    Some(Event::Down(key)) if self.shortcuts => {
+                        if let Some(item) = shortcut(&key).and_then(|i| self.items.get(i)) {
+                            return Some(item.clone());
+                        }
+                    }
+                    None => {
+                        if let Some(filter) = &mut self.filter {
+                            filter.release();
+                        }
                     }
                     _ => {}
                 }
@@ -209,19 +438,18 @@ pub fn row_render(
         .bounding_box()
         .contains(Point::new(0, text_bounding_box.bottom_right().unwrap().y))
     {
-        let _ = Rectangle::new(
-            point,
-            Size::new(
-                draw_target.bounding_box().size.width,
-                text_bounding_box.size.height,
+        let mut text_style = text_style;
+        text_style.text_color = Some(crate::theme::current().draw_selection(
+            draw_target,
+            Rectangle::new(
+                point,
+                Size::new(
+                    draw_target.bounding_box().size.width,
+                    text_bounding_box.size.height,
+                ),
             ),
-        )
-        .into_styled(PrimitiveStyle::with_fill(if selected {
-            BinaryColor::Off
-        } else {
-            BinaryColor::On
-        }))
-        .draw(draw_target);
+            selected,
+        ));
 
         let _ = Text::with_baseline(
             item,
@@ -255,16 +483,12 @@ pub fn grid_render(
         text_bounding_box.bottom_right().unwrap().x,
         text_bounding_box.bottom_right().unwrap().y,
     )) {
-        let _ = Rectangle::new(
-            point,
-            Size::new(text_bounding_box.size.width, text_bounding_box.size.height),
-        )
-        .into_styled(PrimitiveStyle::with_fill(if selected {
-            BinaryColor::Off
-        } else {
-            BinaryColor::On
-        }))
-        .draw(draw_target);
+        let mut text_style = text_style;
+        text_style.text_color = Some(crate::theme::current().draw_selection(
+            draw_target,
+            Rectangle::new(point, text_bounding_box.size),
+            selected,
+        ));
 
         let _ = Text::with_baseline(
             item,
@@ -282,16 +506,15 @@ pub fn grid_render(
         0,
         text_bounding_box.bottom_right().unwrap().y + text_bounding_box.size.height as i32,
     )) {
-        let _ = Rectangle::new(
-            Point::new(0, text_bounding_box.bottom_right().unwrap().y),
-            Size::new(text_bounding_box.size.width, text_bounding_box.size.height),
-        )
-        .into_styled(PrimitiveStyle::with_fill(if selected {
-            BinaryColor::Off
-        } else {
-            BinaryColor::On
-        }))
-        .draw(draw_target);
+        let mut text_style = text_style;
+        text_style.text_color = Some(crate::theme::current().draw_selection(
+            draw_target,
+            Rectangle::new(
+                Point::new(0, text_bounding_box.bottom_right().unwrap().y),
+                text_bounding_box.size,
+            ),
+            selected,
+        ));
 
         let _ = Text::with_baseline(
             item,
@@ -317,6 +540,7 @@ mod test {
 
     pub struct Device {
         pub display: MockDisplay<BinaryColor>,
+        keypad: Option<crate::test::Keypad<'static>>,
     }
 
     impl Device {
@@ -325,13 +549,26 @@ mod test {
             display.set_allow_out_of_bounds_drawing(false);
             display.set_allow_overdraw(true);
 
-            Self { display }
+            Self {
+                display,
+                keypad: None,
+            }
+        }
+
+        pub fn with_keys(events: &'static [crate::KeyEvent]) -> Self {
+            Self {
+                keypad: Some(crate::test::Keypad::new(events)),
+                ..Self::new()
+            }
         }
     }
 
     impl crate::Keypad for Device {
         async fn event(&mut self) -> crate::KeyEvent {
-            crate::KeyEvent::Down(crate::Key::Down)
+            match &mut self.keypad {
+                Some(keypad) => keypad.event().await,
+                None => crate::KeyEvent::Down(crate::Key::Down),
+            }
         }
 
         fn last_pressed(&mut self) -> Option<embassy_time::Duration> {
@@ -689,4 +926,116 @@ mod test {
             "#############################..######.###..#####################",
         ]);
     }
+
+    #[test]
+    fn test_shortcut() {
+        futures_executor::block_on(async {
+            let mut rows = ["ABC", "XYZ", "Q"];
+            let mut device = Device::with_keys(&[crate::KeyEvent::Down(crate::Key::Two)]);
+            let mut menu = crate::menu::Menu::new(&mut rows, None, |a, b, c, d, e| {
+                crate::menu::row_render(a, b, c, d, e)
+            })
+            .with_shortcuts();
+            assert_eq!(menu.process(&mut device).await, Some("XYZ"));
+        });
+    }
+
+    #[test]
+    fn test_no_shortcut() {
+        futures_executor::block_on(async {
+            let mut rows = ["ABC", "XYZ", "Q"];
+            let mut device = Device::with_keys(&[
+                crate::KeyEvent::Down(crate::Key::Two),
+                crate::KeyEvent::Down(crate::Key::Select),
+            ]);
+            let mut menu = crate::menu::Menu::new(&mut rows, None, |a, b, c, d, e| {
+                crate::menu::row_render(a, b, c, d, e)
+            });
+            assert_eq!(menu.process(&mut device).await, Some("ABC"));
+        });
+    }
+
+    #[test]
+    fn test_filter() {
+        use crate::{Key, KeyEvent};
+
+        futures_executor::block_on(async {
+            let mut rows = ["Alice", "Bob", "Bobby Tables", "Carol"];
+            let mut device = Device::with_keys(&[
+                KeyEvent::Down(Key::Two),
+                KeyEvent::Down(Key::Two),
+                KeyEvent::Down(Key::Down),
+                KeyEvent::Down(Key::Select),
+                KeyEvent::Down(Key::Cancel),
+                KeyEvent::Down(Key::Eight),
+                KeyEvent::Down(Key::Select),
+                KeyEvent::Down(Key::Cancel),
+                KeyEvent::Down(Key::Cancel),
+            ]);
+            let mut menu = crate::menu::Menu::new(&mut rows, None, |a, b, c, d, e| {
+                crate::menu::row_render(a, b, c, d, e)
+            })
+            .with_filter();
+            assert_eq!(menu.process(&mut device).await, Some("Bobby Tables"));
+            assert_eq!(menu.filter_text(), "b");
+            assert_eq!(menu.process(&mut device).await, Some("Bobby Tables"));
+            assert_eq!(menu.process(&mut device).await, None);
+        });
+    }
+
+    #[test]
+    fn test_filter_timeout() {
+        use crate::{Key, KeyEvent};
+
+        futures_executor::block_on(async {
+            let mut rows = ["Abba", "Bob", "Acdc"];
+            // two letters from the same key, let go of in between and told apart by the wait
+            let mut device = crate::test::Device::new(
+                60,
+                48,
+                &[
+                    (0, KeyEvent::Down(Key::Two)),
+                    (0, KeyEvent::Up(Key::Two)),
+                    (1000, KeyEvent::Down(Key::Two)),
+                    (0, KeyEvent::Up(Key::Two)),
+                    (0, KeyEvent::Down(Key::Two)),
+                    (0, KeyEvent::Up(Key::Two)),
+                    (0, KeyEvent::Down(Key::Select)),
+                ],
+            );
+            let mut menu = crate::menu::Menu::new(&mut rows, None, |a, b, c, d, e| {
+                crate::menu::row_render(a, b, c, d, e)
+            })
+            .with_filter();
+            assert_eq!(menu.process(&mut device).await, Some("Abba"));
+            assert_eq!(menu.filter_text(), "ab");
+        });
+    }
+
+    #[test]
+    fn test_scroll_bar() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        crate::menu::scroll_bar(
+            &mut display,
+            embedded_graphics::primitives::Rectangle::new(
+                embedded_graphics::prelude::Point::zero(),
+                embedded_graphics::prelude::Size::new(3, 8),
+            ),
+            2,
+            3,
+            8,
+        );
+        #[rustfmt::skip]
+        display.assert_pattern(&[
+            "#.#",
+            "#.#",
+            "...",
+            "...",
+            "...",
+            "#.#",
+            "#.#",
+            "#.#",
+        ]);
+    }
 }
@@ -0,0 +1,621 @@
+use core::{fmt::Write, marker::PhantomData};
+
+use embedded_graphics::{
+    draw_target::{Clipped, DrawTargetExt},
+    image::{GetPixel, ImageRaw},
+    mono_font::MonoTextStyle,
+    prelude::*,
+    primitives::PrimitiveStyle,
+    text::{Alignment, Baseline, Text, TextStyleBuilder, renderer::TextRenderer},
+};
+use embedded_graphics_core::{pixelcolor::BinaryColor, primitives::Rectangle};
+
+use crate::{
+    Key,
+    held_key::{Event, HeldKey},
+};
+
+// how many submenus can be open inside one another
+pub const DEPTH: usize = 4;
+
+type Line = heapless::String<40>;
+
+pub enum Kind<'a, A> {
+    Submenu(&'a mut [Item<'a, A>]),
+    Action(A),
+    // selecting turns it on or off
+    Toggle(A, bool),
+    // selecting chooses it over the other radio items of the same menu
+    Radio(A, bool),
+    // shows the chosen radio item of the submenu after the label, e.g. "Backlight: 15s", and
+    // selecting opens that submenu
+    Value(&'a mut [Item<'a, A>]),
+}
+
+pub struct Item<'a, A> {
+    pub label: &'a str,
+    pub icon: Option<ImageRaw<'static, BinaryColor>>,
+    pub kind: Kind<'a, A>,
+}
+
+impl<'a, A> Item<'a, A> {
+    pub fn new(label: &'a str, kind: Kind<'a, A>) -> Self {
+        Self {
+            label,
+            icon: None,
+            kind,
+        }
+    }
+
+    pub fn with_icon(self, icon: ImageRaw<'static, BinaryColor>) -> Self {
+        Self {
+            icon: Some(icon),
+            ..self
+        }
+    }
+
+    fn children(&self) -> Option<&[Item<'a, A>]> {
+        match &self.kind {
+            Kind::Submenu(children) | Kind::Value(children) => Some(children),
+            _ => None,
+        }
+    }
+
+    fn line(&self) -> Line {
+        let mut line = Line::new();
+        let _ = match &self.kind {
+            Kind::Toggle(_, true) => write!(line, "[x] {}", self.label),
+            Kind::Toggle(_, false) => write!(line, "[ ] {}", self.label),
+            Kind::Radio(_, true) => write!(line, "(*) {}", self.label),
+            Kind::Radio(_, false) => write!(line, "( ) {}", self.label),
+            Kind::Value(children) => write!(
+                line,
+                "{}: {}",
+                self.label,
+                children
+                    .iter()
+                    .find(|item| matches!(item.kind, Kind::Radio(_, true)))
+                    .map_or("", |item| item.label)
+            ),
+            Kind::Submenu(_) | Kind::Action(_) => write!(line, "{}", self.label),
+        };
+        line
+    }
+}
+
+// the item of `action` among `items` and all of their submenus
+fn find<'b, 'a, A: PartialEq>(items: &'b [Item<'a, A>], action: &A) -> Option<&'b Item<'a, A>> {
+    items.iter().find_map(|item| match &item.kind {
+        Kind::Action(a) | Kind::Toggle(a, _) | Kind::Radio(a, _) if a == action => Some(item),
+        _ => item.children().and_then(|children| find(children, action)),
+    })
+}
+
+fn draw_icon(
+    target: &mut impl DrawTarget<Color = BinaryColor>,
+    icon: &ImageRaw<'static, BinaryColor>,
+    point: Point,
+    height: u32,
+    selected: bool,
+) {
+    // icons are drawn in `Off`, like text
+    let (foreground, background) = crate::theme::current().colors(selected);
+    let size = icon.size();
+    let _ = Rectangle::new(point, Size::new(size.width + 1, height.max(size.height)))
+        .into_styled(PrimitiveStyle::with_fill(background))
+        .draw(target);
+    let pixels = icon.bounding_box().points().filter_map(|p| {
+        icon.pixel(p).map(|color| {
+            Pixel(
+                point + p,
+                if color == BinaryColor::Off {
+                    foreground
+                } else {
+                    background
+                },
+            )
+        })
+    });
+    let _ = target.draw_iter(pixels);
+}
+
+// a menu of menus. the title shows the way to whichever one is open, selecting an item either
+// opens its submenu or hands back its action, and cancel goes back up a level. items are drawn by
+// the same renderers as `Menu`, with icons to the left.
+pub struct Tree<'a, A, F, D>
+where
+    A: Clone,
+    F: Fn(
+        &mut Clipped<'_, Clipped<'_, D>>,
+        bool,
+        &str,
+        Point,
+        MonoTextStyle<'_, BinaryColor>,
+    ) -> Option<Point>,
+    D: DrawTarget<Color = BinaryColor> + crate::Keypad,
+{
+    items: &'a mut [Item<'a, A>],
+    title: &'a str,
+    // which item was opened at each level above the current one
+    path: heapless::Vec<usize, DEPTH>,
+    index: usize,
+    start_of_page_index: usize,
+    bottom_visible_index: usize,
+    page_size: usize,
+    held_key: HeldKey,
+    select_label: Option<&'a str>,
+    renderer: F,
+    display: PhantomData<D>,
+}
+
+impl<'a, A, F, D> Tree<'a, A, F, D>
+where
+    A: Clone,
+    F: Fn(
+        &mut Clipped<'_, Clipped<'_, D>>,
+        bool,
+        &str,
+        Point,
+        MonoTextStyle<'_, BinaryColor>,
+    ) -> Option<Point>,
+    D: DrawTarget<Color = BinaryColor> + crate::Keypad,
+{
+    pub fn new(
+        title: &'a str,
+        items: &'a mut [Item<'a, A>],
+        select_label: Option<&'a str>,
+        renderer: F,
+    ) -> Self {
+        assert!(!items.is_empty());
+        Self {
+            items,
+            title,
+            path: heapless::Vec::new(),
+            index: 0,
+            start_of_page_index: 0,
+            bottom_visible_index: 0,
+            page_size: 0,
+            held_key: HeldKey::new(750, 250),
+            select_label,
+            renderer,
+            display: PhantomData,
+        }
+    }
+
+    // the item whose submenu is open
+    fn opened(&self) -> Option<&Item<'a, A>> {
+        let (&last, path) = self.path.split_last()?;
+        let mut items: &[Item<'a, A>] = self.items;
+        for &i in path {
+            items = items[i].children().unwrap();
+        }
+        Some(&items[last])
+    }
+
+    fn level(&self) -> &[Item<'a, A>] {
+        self.opened()
+            .map_or(self.items, |item| item.children().unwrap())
+    }
+
+    fn level_mut(&mut self) -> &mut [Item<'a, A>] {
+        let mut items: &mut [Item<'a, A>] = self.items;
+        for &i in &self.path {
+            let level = items;
+            items = match &mut level[i].kind {
+                Kind::Submenu(children) | Kind::Value(children) => children,
+                _ => unreachable!(),
+            };
+        }
+        items
+    }
+
+    pub fn toggled(&self, action: &A) -> Option<bool>
+    where
+        A: PartialEq,
+    {
+        match find(self.items, action)?.kind {
+            Kind::Toggle(_, on) | Kind::Radio(_, on) => Some(on),
+            _ => None,
+        }
+    }
+
+    // "Settings>Display", losing the front of it if it does not fit on one line
+    fn breadcrumb(&self, columns: usize) -> Line {
+        let mut line = Line::new();
+        let _ = line.push_str(self.title);
+        let mut items: &[Item<'a, A>] = self.items;
+        for &i in &self.path {
+            if !line.is_empty() {
+                let _ = line.push('>');
+            }
+            let _ = line.push_str(items[i].label);
+            items = items[i].children().unwrap();
+        }
+        let count = line.chars().count();
+        if count > columns {
+            let skip = line
+                .char_indices()
+                .nth(count - columns + 2)
+                .map_or(0, |(i, _)| i);
+            let mut shortened = Line::new();
+            let _ = write!(shortened, "..{}", &line[skip..]);
+            return shortened;
+        }
+        line
+    }
+
+    fn show(&mut self, index: usize) {
+        self.index = index;
+        self.start_of_page_index = index;
+        self.bottom_visible_index = index;
+        self.page_size = 0;
+    }
+
+    fn open(&mut self) {
+        if self.path.push(self.index).is_ok() {
+            let chosen = self
+                .level()
+                .iter()
+                .position(|item| matches!(item.kind, Kind::Radio(_, true)));
+            self.show(chosen.unwrap_or(0));
+        }
+    }
+
+    fn close(&mut self) -> bool {
+        match self.path.pop() {
+            Some(index) => {
+                self.show(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn select(&mut self) -> Option<A> {
+        let index = self.index;
+        match &mut self.level_mut()[index].kind {
+            Kind::Submenu(_) | Kind::Value(_) => {
+                self.open();
+                None
+            }
+            Kind::Action(action) => Some(action.clone()),
+            Kind::Toggle(action, on) => {
+                *on = !*on;
+                Some(action.clone())
+            }
+            Kind::Radio(action, _) => {
+                let action = action.clone();
+                for (i, item) in self.level_mut().iter_mut().enumerate() {
+                    if let Kind::Radio(_, on) = &mut item.kind {
+                        *on = i == index;
+                    }
+                }
+                // the value is chosen, go back to where it is shown
+                if self
+                    .opened()
+                    .is_some_and(|item| matches!(item.kind, Kind::Value(_)))
+                {
+                    self.close();
+                }
+                Some(action)
+            }
+        }
+    }
+
+    fn down(&mut self) {
+        if self.index < self.level().len() - 1 {
+            self.index += 1;
+        }
+        if self.index > self.bottom_visible_index {
+            self.page_size = self.index - self.start_of_page_index;
+            self.start_of_page_index = self.index;
+        }
+    }
+
+    fn up(&mut self) {
+        if self.index > 0 {
+            self.index -= 1;
+        }
+        if self.index < self.start_of_page_index {
+            self.start_of_page_index = self.index.saturating_sub(self.page_size.max(1) - 1);
+        }
+    }
+
+    fn text_style(selected: bool) -> MonoTextStyle<'static, BinaryColor> {
+        let theme = crate::theme::current();
+        MonoTextStyle::new(theme.font, theme.colors(selected).0)
+    }
+
+    fn draw(&mut self, target: &mut D) {
+        let theme = crate::theme::current();
+        theme.clear(target);
+
+        let size = target.bounding_box().size;
+        let small = theme.small_text_style();
+        let line_height = small.line_height();
+
+        let breadcrumb = self.breadcrumb((size.width / small.font.character_size.width) as usize);
+        let _ = Text::with_baseline(&breadcrumb, Point::zero(), small, Baseline::Top).draw(target);
+        let mut bottom = size.height;
+        if let Some(select_label) = self.select_label {
+            let _ = Text::with_text_style(
+                select_label,
+                Point::new((size.width / 2) as i32, size.height as i32 - 1),
+                small,
+                TextStyleBuilder::new()
+                    .alignment(Alignment::Center)
+                    .baseline(Baseline::Bottom)
+                    .build(),
+            )
+            .draw(target);
+            bottom -= line_height;
+        }
+        let mut target = target.clipped(&Rectangle::new(
+            Point::new(0, line_height as i32),
+            Size::new(size.width, bottom.saturating_sub(line_height)),
+        ));
+
+        let mut last_drawn_index = 0;
+        let mut point = Point::new(0, line_height as i32);
+        let start = self.start_of_page_index;
+        for (index, item) in self.level()[start..].iter().enumerate() {
+            let selected = self.index - start == index;
+            let offset = item.icon.map_or(0, |icon| icon.size().width as i32 + 1);
+            let drawn = (self.renderer)(
+                &mut target.clipped(&target.bounding_box()),
+                selected,
+                &item.line(),
+                point + Point::new(offset, 0),
+                Self::text_style(selected),
+            );
+            if let Some(p) = drawn {
+                if let Some(icon) = &item.icon {
+                    let height = (p.y - point.y).max(0) as u32;
+                    draw_icon(&mut target, icon, point, height, selected);
+                }
+                point = p - Point::new(offset, 0);
+                last_drawn_index = index;
+            }
+        }
+        self.bottom_visible_index = last_drawn_index + start;
+
+        let count = self.level().len();
+        if start > 0 || self.bottom_visible_index + 1 < count {
+            let area = target.bounding_box();
+            super::scroll_bar(&mut target, area, start, self.bottom_visible_index, count);
+        }
+    }
+
+    // hands back the action of whatever is selected, or `None` once cancelled at the top level.
+    // call again to carry on from the same place, which suits settings that are changed one after
+    // another.
+    pub async fn process(&mut self, device: &mut D) -> Option<A> {
+        loop {
+            self.draw(device);
+            match self.held_key.event(device).await {
+                Some(
+                    Event::Down(Key::Down) | Event::Delay(Key::Down) | Event::Repeat(Key::Down),
+                ) => self.down(),
+                Some(Event::Down(Key::Up) | Event::Delay(Key::Up) | Event::Repeat(Key::Up)) => {
+                    self.up()
+                }
+                Some(Event::Down(Key::Cancel)) => {
+                    if !self.close() {
+                        return None;
+                    }
+                }
+                Some(Event::Down(Key::Select)) => {
+                    if let Some(action) = self.select() {
+                        return Some(action);
+                    }
+                }
+                // a number picks the item at that position, so 4 then 2 then 1 opens the first
+                // item of the second item of the fourth item
+                Some(Event::Down(key)) => {
+                    let index = super::shortcut(&key).filter(|i| *i < self.level().len());
+                    if let Some(index) = index {
+                        if index < self.start_of_page_index || index > self.bottom_visible_index {
+                            self.show(index);
+                        }
+                        self.index = index;
+                        if let Some(action) = self.select() {
+                            return Some(action);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{Pixel, mock_display::MockDisplay};
+    use futures_executor::block_on;
+
+    use super::*;
+    use crate::KeyEvent;
+
+    struct Device<'a> {
+        display: MockDisplay<BinaryColor>,
+        keypad: crate::test::Keypad<'a>,
+    }
+
+    impl<'a> Device<'a> {
+        fn new(events: &'a [KeyEvent]) -> Self {
+            let mut display = MockDisplay::new();
+            display.set_allow_overdraw(true);
+            Self {
+                display,
+                keypad: crate::test::Keypad::new(events),
+            }
+        }
+    }
+
+    impl crate::Keypad for Device<'_> {
+        async fn event(&mut self) -> KeyEvent {
+            self.keypad.event().await
+        }
+
+        fn last_pressed(&mut self) -> Option<embassy_time::Duration> {
+            None
+        }
+    }
+
+    impl DrawTarget for Device<'_> {
+        type Color = BinaryColor;
+        type Error = core::convert::Infallible;
+
+        fn draw_iter<I: IntoIterator<Item = Pixel<BinaryColor>>>(
+            &mut self,
+            pixels: I,
+        ) -> Result<(), Self::Error> {
+            self.display.draw_iter(pixels)
+        }
+    }
+
+    impl OriginDimensions for Device<'_> {
+        fn size(&self) -> Size {
+            self.display.size()
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Action {
+        Vibrate,
+        Backlight(u8),
+        About,
+    }
+
+    fn press(keys: &[Key]) -> heapless::Vec<KeyEvent, 16> {
+        keys.iter().map(|key| KeyEvent::Down(key.clone())).collect()
+    }
+
+    #[test]
+    fn test_navigate() {
+        block_on(async {
+            let mut backlight = [
+                Item::new("5s", Kind::Radio(Action::Backlight(5), false)),
+                Item::new("15s", Kind::Radio(Action::Backlight(15), true)),
+                Item::new("30s", Kind::Radio(Action::Backlight(30), false)),
+            ];
+            let mut settings = [
+                Item::new("Vibrate", Kind::Toggle(Action::Vibrate, false)),
+                Item::new("Backlight", Kind::Value(&mut backlight)),
+            ];
+            let mut items = [
+                Item::new("Settings", Kind::Submenu(&mut settings)),
+                Item::new("About", Kind::Action(Action::About)),
+            ];
+            let events = press(&[
+                Key::Select,
+                Key::Select,
+                Key::Down,
+                Key::Select,
+                Key::Down,
+                Key::Select,
+                Key::Cancel,
+                Key::Down,
+                Key::Select,
+                Key::Cancel,
+            ]);
+            let mut device = Device::new(&events);
+            let mut tree = Tree::new("Menu", &mut items, None, |a, b, c, d, e| {
+                crate::menu::row_render(a, b, c, d, e)
+            });
+
+            assert_eq!(tree.process(&mut device).await, Some(Action::Vibrate));
+            assert_eq!(tree.toggled(&Action::Vibrate), Some(true));
+            assert_eq!(tree.breadcrumb(14).as_str(), "Menu>Settings");
+            assert_eq!(tree.level()[1].line().as_str(), "Backlight: 15s");
+
+            assert_eq!(tree.process(&mut device).await, Some(Action::Backlight(30)));
+            assert_eq!(tree.toggled(&Action::Backlight(15)), Some(false));
+            assert_eq!(tree.level()[1].line().as_str(), "Backlight: 30s");
+            assert_eq!(tree.breadcrumb(14).as_str(), "Menu>Settings");
+
+            assert_eq!(tree.process(&mut device).await, Some(Action::About));
+            assert_eq!(tree.process(&mut device).await, None);
+        });
+    }
+
+    #[test]
+    fn test_breadcrumb() {
+        let mut display = [Item::new("Contrast", Kind::Action(()))];
+        let mut settings = [Item::new("Display settings", Kind::Submenu(&mut display))];
+        let mut items = [Item::new("Settings", Kind::Submenu(&mut settings))];
+        let mut tree: Tree<_, _, Device> = Tree::new("Menu", &mut items, None, |a, b, c, d, e| {
+            crate::menu::row_render(a, b, c, d, e)
+        });
+        tree.open();
+        tree.open();
+        assert_eq!(tree.breadcrumb(14).as_str(), "..lay settings");
+        assert_eq!(tree.level()[0].line().as_str(), "Contrast");
+    }
+
+    #[test]
+    fn test_icon() {
+        block_on(async {
+            #[rustfmt::skip]
+            const ICON: &[u8] = &[
+                0b0110_0000,
+                0b1001_0000,
+            ];
+            let mut items = [
+                Item::new("A", Kind::Action(())).with_icon(ImageRaw::new(ICON, 4)),
+                Item::new("B", Kind::Action(())),
+            ];
+            let events = press(&[Key::Select]);
+            let mut device = Device::new(&events);
+            let mut tree = Tree::new("", &mut items, None, |a, b, c, d, e| {
+                crate::menu::row_render(a, b, c, d, e)
+            });
+            tree.process(&mut device).await;
+
+            // the first row is selected, so the icon is inverted and the text starts after it
+            let row = |x| device.display.get_pixel(Point::new(x, 9));
+            assert_eq!(
+                [row(0), row(1), row(2), row(3), row(4)],
+                [
+                    Some(BinaryColor::On),
+                    Some(BinaryColor::Off),
+                    Some(BinaryColor::Off),
+                    Some(BinaryColor::On),
+                    Some(BinaryColor::Off),
+                ]
+            );
+            assert_eq!(
+                device.display.get_pixel(Point::new(7, 10)),
+                Some(BinaryColor::On)
+            );
+        });
+    }
+
+    #[test]
+    fn test_shortcuts() {
+        block_on(async {
+            let mut backlight = [
+                Item::new("5s", Kind::Radio(Action::Backlight(5), false)),
+                Item::new("15s", Kind::Radio(Action::Backlight(15), true)),
+                Item::new("30s", Kind::Radio(Action::Backlight(30), false)),
+            ];
+            let mut settings = [
+                Item::new("Vibrate", Kind::Toggle(Action::Vibrate, false)),
+                Item::new("Backlight", Kind::Value(&mut backlight)),
+            ];
+            let mut items = [
+                Item::new("Settings", Kind::Submenu(&mut settings)),
+                Item::new("About", Kind::Action(Action::About)),
+            ];
+            let events = press(&[Key::Nine, Key::One, Key::Two, Key::Three]);
+            let mut device = Device::new(&events);
+            let mut tree = Tree::new("Menu", &mut items, None, |a, b, c, d, e| {
+                crate::menu::row_render(a, b, c, d, e)
+            });
+
+            assert_eq!(tree.process(&mut device).await, Some(Action::Backlight(30)));
+            assert_eq!(tree.breadcrumb(14).as_str(), "Menu>Settings");
+        });
+    }
+}
@@ -1,20 +1,26 @@
-#![expect(dead_code)]
-use core::ascii::Char;
-
 use defmt::Format;
+use embassy_time::{Duration, Instant};
 
 use crate::{Key, held_key::HeldKey};
 mod case;
 pub use case::*;
+mod keymap;
+pub use keymap::*;
 mod pending;
 pub use pending::*;
 
+pub const BACKSPACE: char = '\u{8}';
+
 #[derive(Debug, PartialEq, Format, Copy, Clone)]
 pub enum Event {
-    Tentative(Char),
-    Decided(Char),
+    Tentative(char),
+    Decided(char),
     Case(Case),
     ShowSpecialCharacters,
+    ChooseLocale,
+    TogglePredictive,
+    CursorLeft,
+    CursorRight,
 }
 
 impl Event {
@@ -24,80 +30,6 @@ impl Event {
             _ => None,
         }
     }
-
-    fn next_char(self) -> Option<Self> {
-        match self {
-            Self::Tentative(c) => {
-                let result = match c {
-                    Char::CapitalA => Char::CapitalB,
-                    Char::CapitalB => Char::CapitalC,
-                    Char::CapitalC => Char::CapitalA,
-                    Char::CapitalD => Char::CapitalE,
-                    Char::CapitalE => Char::CapitalF,
-                    Char::CapitalF => Char::CapitalD,
-                    Char::CapitalG => Char::CapitalH,
-                    Char::CapitalH => Char::CapitalI,
-                    Char::CapitalI => Char::CapitalG,
-                    Char::CapitalJ => Char::CapitalK,
-                    Char::CapitalK => Char::CapitalL,
-                    Char::CapitalL => Char::CapitalJ,
-                    Char::CapitalM => Char::CapitalN,
-                    Char::CapitalN => Char::CapitalO,
-                    Char::CapitalO => Char::CapitalM,
-                    Char::CapitalP => Char::CapitalQ,
-                    Char::CapitalQ => Char::CapitalR,
-                    Char::CapitalR => Char::CapitalS,
-                    Char::CapitalS => Char::CapitalP,
-                    Char::CapitalT => Char::CapitalU,
-                    Char::CapitalU => Char::CapitalV,
-                    Char::CapitalV => Char::CapitalT,
-                    Char::CapitalW => Char::CapitalX,
-                    Char::CapitalX => Char::CapitalY,
-                    Char::CapitalY => Char::CapitalZ,
-                    Char::CapitalZ => Char::CapitalW,
-                    Char::SmallA => Char::SmallB,
-                    Char::SmallB => Char::SmallC,
-                    Char::SmallC => Char::SmallA,
-                    Char::SmallD => Char::SmallE,
-                    Char::SmallE => Char::SmallF,
-                    Char::SmallF => Char::SmallD,
-                    Char::SmallG => Char::SmallH,
-                    Char::SmallH => Char::SmallI,
-                    Char::SmallI => Char::SmallG,
-                    Char::SmallJ => Char::SmallK,
-                    Char::SmallK => Char::SmallL,
-                    Char::SmallL => Char::SmallJ,
-                    Char::SmallM => Char::SmallN,
-                    Char::SmallN => Char::SmallO,
-                    Char::SmallO => Char::SmallM,
-                    Char::SmallP => Char::SmallQ,
-                    Char::SmallQ => Char::SmallR,
-                    Char::SmallR => Char::SmallS,
-                    Char::SmallS => Char::SmallP,
-                    Char::SmallT => Char::SmallU,
-                    Char::SmallU => Char::SmallV,
-                    Char::SmallV => Char::SmallT,
-                    Char::SmallW => Char::SmallX,
-                    Char::SmallX => Char::SmallY,
-                    Char::SmallY => Char::SmallZ,
-                    Char::SmallZ => Char::SmallW,
-                    Char::Digit1 => Char::Digit2,
-                    Char::Digit2 => Char::Digit3,
-                    Char::Digit3 => Char::Digit4,
-                    Char::Digit4 => Char::Digit5,
-                    Char::Digit5 => Char::Digit6,
-                    Char::Digit6 => Char::Digit7,
-                    Char::Digit7 => Char::Digit8,
-                    Char::Digit8 => Char::Digit9,
-                    Char::Digit9 => Char::Digit0,
-                    Char::Digit0 => Char::Digit1,
-                    e => e,
-                };
-                Some(Self::Tentative(result))
-            }
-            _ => None,
-        }
-    }
 }
 
 #[derive(Debug)]
@@ -138,10 +70,15 @@ impl Last {
 #[derive(Debug)]
 pub struct MultiTap {
     case_state: CaseState,
+    locale: Locale,
+    // position of the tentative character within the letters of its key
+    index: usize,
     last: Last,
     pending: Pending<Event>,
     held_key: HeldKey,
     duration: u64,
+    // when the tentative character is decided, unless its key is pressed again first
+    decide_at: Option<Instant>,
 }
 
 impl MultiTap {
@@ -152,10 +89,13 @@ impl MultiTap {
 
         Self {
             case_state,
+            locale: Locale::default(),
+            index: 0,
             last: Last::new(),
             pending,
-            held_key: HeldKey::new(15000, 5000),
+            held_key: HeldKey::new(duration, 5000),
             duration,
+            decide_at: None,
         }
     }
 
@@ -163,43 +103,160 @@ impl MultiTap {
         self.case_state.case()
     }
 
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = locale;
+        self.last.clear();
+    }
+
+    // moving away from a tentative character decides it first
+    fn cursor(&mut self, event: Event) -> Option<Event> {
+        match self.last.event.and_then(Event::decide) {
+            Some(decided) => {
+                self.pending.enqueue(event);
+                Some(decided)
+            }
+            None => Some(event),
+        }
+    }
+
+    fn letter(&self, letters: &str) -> char {
+        let c = letters.chars().nth(self.index).unwrap_or(' ');
+        match self.case() {
+            Case::Upper => uppercase(c),
+            _ => c,
+        }
+    }
+
     pub async fn event(&mut self, keypad: &mut impl crate::Keypad) -> Option<Event> {
-        if let Some(pending) = self.pending.dequeue() {
-            self.last.set_event(Some(pending));
-            return Some(pending);
+        loop {
+            if let Some(event) = self.event_before(keypad, Instant::MAX).await {
+                return event;
+            }
         }
+    }
 
-        let key = self.held_key.event(keypad).await;
-        let last_key = self.last.set_held_key_event(key.clone());
+    // what `event` would give, or `None` once `until` passes first
+    pub async fn event_before(
+        &mut self,
+        keypad: &mut impl crate::Keypad,
+        until: Instant,
+    ) -> Option<Option<Event>> {
+        if let Some(pending) = self.pending_event() {
+            return Some(Some(pending));
+        }
 
-        let result = match key {
+        let deadline = self.decide_at.map_or(until, |at| at.min(until));
+        let Some(key) = self.held_key.event_before(keypad, deadline).await else {
+            return self
+                .decide_at
+                .is_some_and(|at| at <= Instant::now())
+                .then(|| self.timeout());
+        };
+        Some(self.key(key))
+    }
+
+    // for callers with their own `HeldKey`, who should call `timeout` once this passes
+    pub fn decide_at(&self) -> Option<Instant> {
+        self.decide_at
+    }
+
+    // decides the tentative character, if there is one, as its key wasn't pressed again in time
+    pub fn timeout(&mut self) -> Option<Event> {
+        self.decide_at = None;
+        let decided = self.last.event.and_then(Event::decide);
+        self.last.clear();
+        decided
+    }
+
+    // one key can make more than one event, the rest wait here until they are asked for
+    pub fn pending_event(&mut self) -> Option<Event> {
+        let pending = self.pending.dequeue()?;
+        self.last.set_event(Some(pending));
+        Some(pending)
+    }
+
+    // for when something else read the keypad meanwhile, such as a menu opened from an event
+    pub fn reset(&mut self) {
+        self.held_key.reset();
+        self.last.clear();
+        self.decide_at = None;
+    }
+
+    // a key was let go of. the tentative character stands if it isn't pressed again soon
+    fn release(&mut self) -> Option<Event> {
+        let result = match self.last.held_key_event {
+            // a press of `*` or `#` only counts once it is let go of before it is held long enough
             Some(crate::held_key::Event::Down(Key::Asterisk)) => Some(Event::ShowSpecialCharacters),
             Some(crate::held_key::Event::Down(Key::Hash)) => {
                 self.case_state.cycle_case();
                 Some(Event::Case(self.case()))
             }
-            Some(crate::held_key::Event::Down(Key::Cancel)) => {
-                Some(Event::Decided(core::ascii::Char::Backspace))
+            _ => {
+                if let Some(Event::Tentative(_)) = self.last.event {
+                    self.decide_at = Some(Instant::now() + Duration::from_millis(self.duration));
+                }
+                return None;
             }
-            Some(crate::held_key::Event::Delay(Key::Hash)) => {
-                self.case_state.enable_numeric_case();
-                Some(Event::Case(self.case()))
+        };
+        self.last.clear();
+        self.last.set_event(result);
+        result
+    }
+
+    // for callers with their own `HeldKey`, which should call `pending_event` after each key
+    pub fn key(&mut self, key: Option<crate::held_key::Event>) -> Option<Event> {
+        let Some(key) = key else {
+            return self.release();
+        };
+        let key = Some(key);
+        self.decide_at = None;
+        let last_key = self.last.set_held_key_event(key.clone());
+
+        let result = match key {
+            // held, these choose the locale and toggle predictive text instead
+            Some(crate::held_key::Event::Down(Key::Asterisk | Key::Hash)) => {
+                self.last.event.and_then(Event::decide)
             }
+            Some(crate::held_key::Event::Down(Key::Cancel)) => Some(Event::Decided(BACKSPACE)),
+            Some(
+                crate::held_key::Event::Down(Key::Up)
+                | crate::held_key::Event::Delay(Key::Up)
+                | crate::held_key::Event::Repeat(Key::Up),
+            ) => self.cursor(Event::CursorLeft),
+            Some(
+                crate::held_key::Event::Down(Key::Down)
+                | crate::held_key::Event::Delay(Key::Down)
+                | crate::held_key::Event::Repeat(Key::Down),
+            ) => self.cursor(Event::CursorRight),
+            Some(crate::held_key::Event::Delay(Key::Asterisk)) => Some(Event::ChooseLocale),
+            Some(crate::held_key::Event::Delay(Key::Hash)) => Some(Event::TogglePredictive),
             Some(crate::held_key::Event::Delay(Key::Cancel)) => None,
             Some(crate::held_key::Event::Delay(d)) => {
                 self.last.clear();
-                Some(Event::Decided(digit(d)))
+                digit(d).map(Event::Decided)
+            }
+            Some(crate::held_key::Event::Down(ref now)) if self.case() == Case::Number => {
+                digit(now.clone()).map(Event::Decided)
             }
             Some(crate::held_key::Event::Down(ref now)) => {
+                let letters = self.locale.keymap().letters(now)?;
                 if key == last_key {
-                    self.last.event.unwrap().next_char()
-                } else if last_key.is_some() {
-                    let result = self.last.event.unwrap().decide();
-                    self.pending
-                        .enqueue(Event::Tentative(lowercase(now.clone().into())));
-                    result
+                    self.index = (self.index + 1) % letters.chars().count();
+                    Some(Event::Tentative(self.letter(letters)))
                 } else {
-                    Some(Event::Tentative(lowercase(now.clone().into())))
+                    self.index = 0;
+                    let tentative = Event::Tentative(self.letter(letters));
+                    match self.last.event.and_then(Event::decide) {
+                        Some(decided) => {
+                            self.pending.enqueue(tentative);
+                            Some(decided)
+                        }
+                        None => Some(tentative),
+                    }
                 }
             }
             None | Some(crate::held_key::Event::Repeat(_)) => None,
@@ -210,51 +267,28 @@ impl MultiTap {
     }
 }
 
-fn digit(k: crate::Key) -> Char {
+fn digit(k: crate::Key) -> Option<char> {
     match k {
-        crate::Key::One => core::ascii::Char::Digit1,
-        crate::Key::Two => core::ascii::Char::Digit2,
-        crate::Key::Three => core::ascii::Char::Digit3,
-        crate::Key::Four => core::ascii::Char::Digit4,
-        crate::Key::Five => core::ascii::Char::Digit5,
-        crate::Key::Six => core::ascii::Char::Digit6,
-        crate::Key::Seven => core::ascii::Char::Digit7,
-        crate::Key::Eight => core::ascii::Char::Digit8,
-        crate::Key::Nine => core::ascii::Char::Digit9,
-        crate::Key::Zero => core::ascii::Char::Digit0,
-        _ => core::ascii::Char::Digit0,
+        crate::Key::One => Some('1'),
+        crate::Key::Two => Some('2'),
+        crate::Key::Three => Some('3'),
+        crate::Key::Four => Some('4'),
+        crate::Key::Five => Some('5'),
+        crate::Key::Six => Some('6'),
+        crate::Key::Seven => Some('7'),
+        crate::Key::Eight => Some('8'),
+        crate::Key::Nine => Some('9'),
+        crate::Key::Zero => Some('0'),
+        _ => None,
     }
 }
 
-fn lowercase(c: Char) -> Char {
-    match c {
-        core::ascii::Char::CapitalA => core::ascii::Char::SmallA,
-        core::ascii::Char::CapitalB => core::ascii::Char::SmallB,
-        core::ascii::Char::CapitalC => core::ascii::Char::SmallC,
-        core::ascii::Char::CapitalD => core::ascii::Char::SmallD,
-        core::ascii::Char::CapitalE => core::ascii::Char::SmallE,
-        core::ascii::Char::CapitalF => core::ascii::Char::SmallF,
-        core::ascii::Char::CapitalG => core::ascii::Char::SmallG,
-        core::ascii::Char::CapitalH => core::ascii::Char::SmallH,
-        core::ascii::Char::CapitalI => core::ascii::Char::SmallI,
-        core::ascii::Char::CapitalJ => core::ascii::Char::SmallJ,
-        core::ascii::Char::CapitalK => core::ascii::Char::SmallK,
-        core::ascii::Char::CapitalL => core::ascii::Char::SmallL,
-        core::ascii::Char::CapitalM => core::ascii::Char::SmallM,
-        core::ascii::Char::CapitalN => core::ascii::Char::SmallN,
-        core::ascii::Char::CapitalO => core::ascii::Char::SmallO,
-        core::ascii::Char::CapitalP => core::ascii::Char::SmallP,
-        core::ascii::Char::CapitalQ => core::ascii::Char::SmallQ,
-        core::ascii::Char::CapitalR => core::ascii::Char::SmallR,
-        core::ascii::Char::CapitalS => core::ascii::Char::SmallS,
-        core::ascii::Char::CapitalT => core::ascii::Char::SmallT,
-        core::ascii::Char::CapitalU => core::ascii::Char::SmallU,
-        core::ascii::Char::CapitalV => core::ascii::Char::SmallV,
-        core::ascii::Char::CapitalW => core::ascii::Char::SmallW,
-        core::ascii::Char::CapitalX => core::ascii::Char::SmallX,
-        core::ascii::Char::CapitalY => core::ascii::Char::SmallY,
-        core::ascii::Char::CapitalZ => core::ascii::Char::SmallZ,
-        t => t,
+// letters without a single uppercase form (such as ß) are left alone
+pub(crate) fn uppercase(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) => u,
+        _ => c,
     }
 }
 
@@ -273,7 +307,7 @@ mod test {
             );
             assert_eq!(
                 multitap.event(&mut keypad).await,
-                Some(super::Event::Tentative(core::ascii::Char::SmallA))
+                Some(super::Event::Tentative('a'))
             );
         });
     }
@@ -292,17 +326,40 @@ mod test {
             );
             assert_eq!(
                 multitap.event(&mut keypad).await,
-                Some(super::Event::Tentative(core::ascii::Char::SmallA))
+                Some(super::Event::Tentative('a'))
             );
             assert_eq!(
                 multitap.event(&mut keypad).await,
-                Some(super::Event::Tentative(core::ascii::Char::SmallB))
+                Some(super::Event::Tentative('b'))
             );
         });
     }
 
     #[test]
-    fn test_decided_by_timeout() {}
+    fn test_decided_by_timeout() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                1,
+                1,
+                &[
+                    (0, crate::KeyEvent::Down(crate::Key::Two)),
+                    (0, crate::KeyEvent::Up(crate::Key::Two)),
+                    (1000, crate::KeyEvent::Down(crate::Key::Two)),
+                ],
+            );
+            let mut multitap = super::MultiTap::new(1000);
+            // letting go of the key gives nothing
+            for event in [
+                Some(super::Event::Case(super::Case::Lower)),
+                Some(super::Event::Tentative('a')),
+                None,
+                Some(super::Event::Decided('a')),
+                Some(super::Event::Tentative('a')),
+            ] {
+                assert_eq!(multitap.event(&mut device).await, event);
+            }
+        });
+    }
 
     #[test]
     fn test_hold_for_number() {}
@@ -321,16 +378,155 @@ mod test {
             );
             assert_eq!(
                 multitap.event(&mut keypad).await,
-                Some(super::Event::Tentative(core::ascii::Char::SmallA))
+                Some(super::Event::Tentative('a'))
             );
             assert_eq!(
                 multitap.event(&mut keypad).await,
-                Some(super::Event::Decided(core::ascii::Char::SmallA))
+                Some(super::Event::Decided('a'))
             );
             assert_eq!(
                 multitap.event(&mut keypad).await,
-                Some(super::Event::Tentative(core::ascii::Char::SmallD))
+                Some(super::Event::Tentative('d'))
             );
         });
     }
+
+    #[test]
+    fn test_locale() {
+        block_on(async {
+            let mut keypad = crate::test::Keypad::new(&[
+                crate::KeyEvent::Down(crate::Key::Hash),
+                crate::KeyEvent::Up(crate::Key::Hash),
+                crate::KeyEvent::Down(crate::Key::Hash),
+                crate::KeyEvent::Up(crate::Key::Hash),
+                crate::KeyEvent::Down(crate::Key::Eight),
+                crate::KeyEvent::Down(crate::Key::Eight),
+                crate::KeyEvent::Down(crate::Key::Eight),
+                crate::KeyEvent::Down(crate::Key::Eight),
+                crate::KeyEvent::Down(crate::Key::Eight),
+            ]);
+            let mut multitap = super::MultiTap::new(1000);
+            multitap.set_locale(super::Locale::German);
+            assert_eq!(
+                multitap.event(&mut keypad).await,
+                Some(super::Event::Case(super::Case::Lower))
+            );
+            for case in [super::Case::Number, super::Case::Upper] {
+                assert_eq!(multitap.event(&mut keypad).await, None);
+                assert_eq!(
+                    multitap.event(&mut keypad).await,
+                    Some(super::Event::Case(case))
+                );
+            }
+            for c in ['T', 'U', 'V', '8', 'Ü'] {
+                assert_eq!(
+                    multitap.event(&mut keypad).await,
+                    Some(super::Event::Tentative(c))
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn test_hold_hash() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                1,
+                1,
+                &[
+                    (0, crate::KeyEvent::Down(crate::Key::Hash)),
+                    (2000, crate::KeyEvent::Up(crate::Key::Hash)),
+                ],
+            );
+            let mut multitap = super::MultiTap::new(1000);
+            // the case stays as it is
+            for event in [
+                Some(super::Event::Case(super::Case::Lower)),
+                None,
+                Some(super::Event::TogglePredictive),
+                None,
+            ] {
+                assert_eq!(multitap.event(&mut device).await, event);
+            }
+        });
+    }
+
+    #[test]
+    fn test_special_character() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                64,
+                48,
+                &[
+                    (0, crate::KeyEvent::Down(crate::Key::Asterisk)),
+                    (100, crate::KeyEvent::Up(crate::Key::Asterisk)),
+                    (100, crate::KeyEvent::Down(crate::Key::Select)),
+                    (100, crate::KeyEvent::Up(crate::Key::Select)),
+                    (2000, crate::KeyEvent::Down(crate::Key::Two)),
+                ],
+            );
+            let mut multitap = super::MultiTap::new(1000);
+            for event in [Some(super::Event::Case(super::Case::Lower)), None] {
+                assert_eq!(multitap.event(&mut device).await, event);
+            }
+            assert_eq!(
+                multitap.event(&mut device).await,
+                Some(super::Event::ShowSpecialCharacters)
+            );
+            assert_eq!(
+                crate::character_select::process(&mut device).await,
+                Some("<")
+            );
+            multitap.reset();
+            // the menu's Select is let go of, then typing carries on without choosing a locale
+            for event in [None, Some(super::Event::Tentative('a'))] {
+                assert_eq!(multitap.event(&mut device).await, event);
+            }
+        });
+    }
+
+    #[test]
+    fn test_hold_asterisk() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                1,
+                1,
+                &[
+                    (0, crate::KeyEvent::Down(crate::Key::Asterisk)),
+                    (2000, crate::KeyEvent::Up(crate::Key::Asterisk)),
+                ],
+            );
+            let mut multitap = super::MultiTap::new(1000);
+            // the special characters don't show first
+            for event in [
+                Some(super::Event::Case(super::Case::Lower)),
+                None,
+                Some(super::Event::ChooseLocale),
+                None,
+            ] {
+                assert_eq!(multitap.event(&mut device).await, event);
+            }
+        });
+    }
+
+    #[test]
+    fn test_cursor_decides() {
+        block_on(async {
+            let mut keypad = crate::test::Keypad::new(&[
+                crate::KeyEvent::Down(crate::Key::Two),
+                crate::KeyEvent::Down(crate::Key::Up),
+                crate::KeyEvent::Down(crate::Key::Down),
+            ]);
+            let mut multitap = super::MultiTap::new(1000);
+            for event in [
+                super::Event::Case(super::Case::Lower),
+                super::Event::Tentative('a'),
+                super::Event::Decided('a'),
+                super::Event::CursorLeft,
+                super::Event::CursorRight,
+            ] {
+                assert_eq!(multitap.event(&mut keypad).await, Some(event));
+            }
+        });
+    }
 }
@@ -8,15 +8,11 @@ pub enum Case {
 #[derive(Debug)]
 pub struct CaseState {
     case: Case,
-    prev_case: Case,
 }
 
 impl CaseState {
     pub fn new(case: Case) -> Self {
-        Self {
-            case,
-            prev_case: case,
-        }
+        Self { case }
     }
 
     pub fn case(&self) -> Case {
@@ -24,19 +20,15 @@ impl CaseState {
     }
 
     pub fn enable_numeric_case(&mut self) {
-        self.prev_case = self.case;
         self.case = Case::Number;
     }
 
+    // a long press on hash is taken by predictive text so numbers are part of the cycle
     pub fn cycle_case(&mut self) {
         self.case = match self.case {
-            Case::Number => match self.prev_case {
-                Case::Upper => Case::Lower,
-                Case::Lower => Case::Upper,
-                Case::Number => Case::Upper,
-            },
             Case::Upper => Case::Lower,
-            Case::Lower => Case::Upper,
+            Case::Lower => Case::Number,
+            Case::Number => Case::Upper,
         }
     }
 }
@@ -0,0 +1,184 @@
+use embedded_graphics::mono_font::{MonoFont, iso_8859_1, iso_8859_5, iso_8859_7};
+use enum_iterator::Sequence;
+use strum_macros::IntoStaticStr;
+
+use crate::Key;
+
+#[derive(Clone, Copy, Debug, Default, IntoStaticStr, Sequence, PartialEq)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+    French,
+    Nordic,
+    Greek,
+    Russian,
+}
+
+impl AsRef<str> for Locale {
+    fn as_ref(&self) -> &str {
+        (*self).into()
+    }
+}
+
+// what each digit key cycles through, lowercase, from one to nine and then zero
+pub struct Keymap {
+    keys: [&'static str; 10],
+    // a font that has a glyph for every letter above
+    pub font: &'static MonoFont<'static>,
+}
+
+impl Keymap {
+    pub fn letters(&self, key: &Key) -> Option<&'static str> {
+        let index = match key {
+            Key::One => 0,
+            Key::Two => 1,
+            Key::Three => 2,
+            Key::Four => 3,
+            Key::Five => 4,
+            Key::Six => 5,
+            Key::Seven => 6,
+            Key::Eight => 7,
+            Key::Nine => 8,
+            Key::Zero => 9,
+            _ => return None,
+        };
+        Some(self.keys[index])
+    }
+}
+
+const PUNCTUATION: &str = ".,?!1'\"-()@/:";
+
+static ENGLISH: Keymap = Keymap {
+    keys: [
+        PUNCTUATION,
+        "abc2",
+        "def3",
+        "ghi4",
+        "jkl5",
+        "mno6",
+        "pqrs7",
+        "tuv8",
+        "wxyz9",
+        " 0",
+    ],
+    font: &iso_8859_1::FONT_6X9,
+};
+
+static GERMAN: Keymap = Keymap {
+    keys: [
+        PUNCTUATION,
+        "abc2ä",
+        "def3",
+        "ghi4",
+        "jkl5",
+        "mno6ö",
+        "pqrs7ß",
+        "tuv8ü",
+        "wxyz9",
+        " 0",
+    ],
+    font: &iso_8859_1::FONT_6X9,
+};
+
+static FRENCH: Keymap = Keymap {
+    keys: [
+        PUNCTUATION,
+        "abc2àâæç",
+        "def3éèêë",
+        "ghi4îï",
+        "jkl5",
+        "mno6ô",
+        "pqrs7",
+        "tuv8ùûü",
+        "wxyz9",
+        " 0",
+    ],
+    font: &iso_8859_1::FONT_6X9,
+};
+
+static NORDIC: Keymap = Keymap {
+    keys: [
+        PUNCTUATION,
+        "abc2äåæ",
+        "def3é",
+        "ghi4",
+        "jkl5",
+        "mno6öø",
+        "pqrs7",
+        "tuv8ü",
+        "wxyz9",
+        " 0",
+    ],
+    font: &iso_8859_1::FONT_6X9,
+};
+
+static GREEK: Keymap = Keymap {
+    keys: [
+        PUNCTUATION,
+        "αβγ2ά",
+        "δεζ3έ",
+        "ηθι4ήίϊ",
+        "κλμ5",
+        "νξο6ό",
+        "πρσς7",
+        "τυφ8ύϋ",
+        "χψω9ώ",
+        " 0",
+    ],
+    font: &iso_8859_7::FONT_6X9,
+};
+
+static RUSSIAN: Keymap = Keymap {
+    keys: [
+        PUNCTUATION,
+        "абвг2",
+        "деёжз3",
+        "ийкл4",
+        "мноп5",
+        "рсту6",
+        "фхцч7",
+        "шщъы8",
+        "ьэюя9",
+        " 0",
+    ],
+    font: &iso_8859_5::FONT_6X9,
+};
+
+impl Locale {
+    pub fn keymap(self) -> &'static Keymap {
+        match self {
+            Locale::English => &ENGLISH,
+            Locale::German => &GERMAN,
+            Locale::French => &FRENCH,
+            Locale::Nordic => &NORDIC,
+            Locale::Greek => &GREEK,
+            Locale::Russian => &RUSSIAN,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // a glyph the font does not have is drawn as a question mark
+    #[test]
+    fn test_fonts_cover_keymaps() {
+        for locale in enum_iterator::all::<Locale>() {
+            let keymap = locale.keymap();
+            let mapping = keymap.font.glyph_mapping;
+            for letters in keymap.keys {
+                for c in letters
+                    .chars()
+                    .chain(letters.chars().map(crate::multitap::uppercase))
+                {
+                    assert!(
+                        c == '?' || mapping.index(c) != mapping.index('?'),
+                        "{c} is missing from the {locale:?} font"
+                    );
+                }
+            }
+        }
+    }
+}
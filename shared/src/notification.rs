@@ -0,0 +1,290 @@
+use embassy_sync::{
+    blocking_mutex::raw::CriticalSectionRawMutex,
+    channel::{Channel, TrySendError},
+};
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{draw_target::DrawTarget, pixelcolor::BinaryColor};
+use heapless::String;
+
+use crate::{
+    Backlight, Buzzer, KeyEvent, Keypad, VibrationMotor,
+    confirmation::Dialog,
+    profile::{Ringtone, Settings},
+};
+
+pub const TITLE_LENGTH: usize = 16;
+pub const BODY_LENGTH: usize = 64;
+
+// how many notifications can wait to be shown before posting more fails
+const QUEUE_LENGTH: usize = 4;
+
+// alarms are heard whatever the profile, at least this loud
+const ALARM_VOLUME: u8 = 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Urgency {
+    // a beep, and only where the profile allows sound
+    Low,
+    Normal,
+    // an alarm, ringing three times over and sounding even in Silent
+    High,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Notification {
+    pub title: String<TITLE_LENGTH>,
+    pub body: String<BODY_LENGTH>,
+    pub urgency: Urgency,
+    // an RTTTL tune to ring with instead of the usual one
+    pub tune: Option<&'static str>,
+}
+
+// as much of `text` as fits
+fn truncated<const N: usize>(text: &str) -> String<N> {
+    let mut result = String::new();
+    for c in text.chars() {
+        if result.push(c).is_err() {
+            break;
+        }
+    }
+    result
+}
+
+impl Notification {
+    pub fn new(title: &str, body: &str, urgency: Urgency) -> Self {
+        Self {
+            title: truncated(title),
+            body: truncated(body),
+            urgency,
+            tune: None,
+        }
+    }
+
+    pub fn with_tune(self, tune: &'static str) -> Self {
+        Self {
+            tune: Some(tune),
+            ..self
+        }
+    }
+
+    // what the dialog says
+    pub fn message(&self) -> String<{ TITLE_LENGTH + 1 + BODY_LENGTH }> {
+        let mut message = String::new();
+        let _ = message.push_str(&self.title);
+        let _ = message.push('\n');
+        let _ = message.push_str(&self.body);
+        message
+    }
+}
+
+// notifications posted from anywhere (an app, or a task keeping alarms) wait here until the
+// system next gets a look in, which is whenever the app waits for a key
+static QUEUE: Channel<CriticalSectionRawMutex, Notification, QUEUE_LENGTH> = Channel::new();
+
+// gives the notification back if too many are already waiting
+pub fn post(notification: Notification) -> Result<(), Notification> {
+    QUEUE
+        .try_send(notification)
+        .map_err(|TrySendError::Full(notification)| notification)
+}
+
+pub async fn next() -> Notification {
+    QUEUE.receive().await
+}
+
+// how the phone gets attention, which the profile decides as much as the notification
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alert {
+    pub tune: &'static str,
+    pub repeats: u8,
+    // nothing is heard at 0
+    pub volume: u8,
+    pub vibrate: bool,
+    pub flash: bool,
+}
+
+impl Alert {
+    pub fn new(settings: &Settings, notification: &Notification) -> Self {
+        let urgency = notification.urgency;
+        let tune = match urgency {
+            Urgency::Low => Ringtone::BeepOnce.tune(),
+            _ => notification.tune.unwrap_or(settings.ringtone.tune()),
+        };
+        let volume = settings.ring_volume_percent();
+        let (repeats, volume) = match urgency {
+            Urgency::High => (3, volume.max(ALARM_VOLUME)),
+            _ => (1, volume),
+        };
+        Self {
+            tune,
+            repeats,
+            volume,
+            vibrate: settings.vibration,
+            flash: true,
+        }
+    }
+
+    // plays the tune, buzzing and flashing the backlight in time with it, until it is over or a
+    // key is pressed. gives back whether a key stopped it
+    pub async fn ring(
+        &self,
+        device: &mut (impl Buzzer + VibrationMotor + Backlight + Keypad),
+    ) -> bool {
+        let ringing = Ringing(device);
+        let device = &mut *ringing.0;
+        let mut song = rtttl::Song::new(self.tune);
+        let mut lit = true;
+        let mut stopped = false;
+        device.set_volume(self.volume);
+        'ringing: for _ in 0..self.repeats {
+            song.reset();
+            while let Some(note) = song.next() {
+                let sounding = match note.frequency() {
+                    Some(Ok(frequency)) => {
+                        if self.volume > 0 {
+                            let _ = device.unmute_buzzer();
+                            let _ = device.set_frequency(frequency as u16);
+                        }
+                        true
+                    }
+                    _ => {
+                        let _ = device.mute_buzzer();
+                        false
+                    }
+                };
+                if self.vibrate {
+                    if sounding {
+                        device.start_vibrating();
+                    } else {
+                        device.stop_vibrating();
+                    }
+                }
+                if self.flash {
+                    lit = !lit;
+                    if lit {
+                        device.on();
+                    } else {
+                        device.off();
+                    }
+                }
+                let until = Instant::now() + Duration::from_millis(note.duration().into());
+                if let Some(KeyEvent::Down(_)) = device.event_before(until).await {
+                    stopped = true;
+                    break 'ringing;
+                }
+            }
+        }
+        stopped
+    }
+}
+
+// quietens everything `ring` set going however it ends, being dropped part way through included
+struct Ringing<'a, D: Buzzer + VibrationMotor + Backlight>(&'a mut D);
+
+impl<D: Buzzer + VibrationMotor + Backlight> Drop for Ringing<'_, D> {
+    fn drop(&mut self) {
+        let _ = self.0.mute_buzzer();
+        self.0.stop_vibrating();
+        self.0.on();
+    }
+}
+
+// shows the notification, rings, and waits for it to be dismissed. the key that stops the
+// ringing doesn't dismiss it as well
+pub async fn show(
+    device: &mut (impl DrawTarget<Color = BinaryColor> + Buzzer + VibrationMotor + Backlight + Keypad),
+    notification: &Notification,
+    settings: &Settings,
+) {
+    let mut dialog = Dialog::new(notification.message(), ["OK"], 0);
+    dialog.draw(device);
+    Alert::new(settings, notification).ring(device).await;
+    dialog.process(device).await;
+}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+    use crate::{Key, profile::Profile};
+
+    #[test]
+    fn test_queue() {
+        let notification = Notification::new("Alarm", "Wake up", Urgency::High);
+        for _ in 0..QUEUE_LENGTH {
+            assert_eq!(post(notification.clone()), Ok(()));
+        }
+        assert_eq!(post(notification.clone()), Err(notification.clone()));
+        for _ in 0..QUEUE_LENGTH {
+            assert_eq!(block_on(next()), notification);
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let notification = Notification::new("A title that is too long", "", Urgency::Low);
+        assert_eq!(notification.title, "A title that is ");
+        assert_eq!(
+            Notification::new("Alarm", "7:00", Urgency::Normal).message(),
+            "Alarm\n7:00"
+        );
+    }
+
+    #[test]
+    fn test_alert() {
+        let high = Notification::new("Alarm", "", Urgency::High);
+        let general = Alert::new(&Profile::General.defaults(), &high);
+        assert_eq!(
+            (general.tune, general.repeats),
+            (Ringtone::NokiaTune.tune(), 3)
+        );
+        assert!(!general.vibrate && general.volume == ALARM_VOLUME);
+        let silent = Alert::new(&Profile::Silent.defaults(), &high);
+        assert!(silent.vibrate && silent.flash);
+        assert_eq!(silent.volume, ALARM_VOLUME);
+
+        let ring = Ringtone::Ring.tune();
+        let low = Notification::new("Note", "", Urgency::Low).with_tune(ring);
+        let outdoor = Alert::new(&Profile::Outdoor.defaults(), &low);
+        assert_eq!(
+            (outdoor.tune, outdoor.volume),
+            (Ringtone::BeepOnce.tune(), 100)
+        );
+        let normal = Notification::new("Note", "", Urgency::Normal).with_tune(ring);
+        assert_eq!(Alert::new(&Profile::General.defaults(), &normal).tune, ring);
+        assert_eq!(Alert::new(&Profile::Silent.defaults(), &normal).volume, 0);
+    }
+
+    #[test]
+    fn test_ring() {
+        block_on(async {
+            let mut device = crate::test::Device::new(60, 36, &[(0, KeyEvent::Down(Key::Two))]);
+            let high = Notification::new("Alarm", "", Urgency::High);
+            assert!(
+                Alert::new(&Profile::Outdoor.defaults(), &high)
+                    .ring(&mut device)
+                    .await
+            );
+            assert_eq!(device.frequency, None);
+            assert!(!device.vibrating && device.backlight);
+        });
+    }
+
+    #[test]
+    fn test_ring_dropped() {
+        block_on(async {
+            let mut device = crate::test::Device::new(60, 36, &[]);
+            let high = Notification::new("Alarm", "", Urgency::High);
+            let alert = Alert::new(&Profile::Outdoor.defaults(), &high);
+            // given up on while the first note sounds
+            let ringing = embassy_futures::select::select(alert.ring(&mut device), async {});
+            assert!(matches!(
+                ringing.await,
+                embassy_futures::select::Either::Second(())
+            ));
+            assert_eq!(device.frequency, None);
+            assert!(!device.vibrating && device.backlight);
+        });
+    }
+}
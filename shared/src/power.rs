@@ -0,0 +1,80 @@
+use embassy_time::{Duration, Instant};
+
+use crate::{Key, KeyEvent, Keypad, profile::Profile};
+
+pub const LONG_PRESS: Duration = Duration::from_millis(1500);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Press {
+    Short,
+    Long,
+}
+
+// to be called straight after `KeyEvent::Down(Key::Power)`. anything else pressed while the power
+// key is held is dropped.
+pub async fn press(keypad: &mut impl Keypad) -> Press {
+    let deadline = Instant::now() + LONG_PRESS;
+    loop {
+        match keypad.event_before(deadline).await {
+            None => return Press::Long,
+            Some(KeyEvent::Up(Key::Power)) => return Press::Short,
+            Some(_) => {}
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Action {
+    Profile(Profile),
+    Personalise,
+    Theme,
+    LockKeys,
+    SwitchOff,
+}
+
+impl AsRef<str> for Action {
+    fn as_ref(&self) -> &str {
+        match self {
+            Action::Profile(profile) => (*profile).into(),
+            Action::Personalise => "Personalise",
+            Action::Theme => "Theme",
+            Action::LockKeys => "Lock keys",
+            Action::SwitchOff => "Switch off",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embassy_futures::join::join;
+    use futures_executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn test_short_press() {
+        block_on(async {
+            let mut keypad = crate::test::Keypad::new(&[
+                KeyEvent::Down(Key::One),
+                KeyEvent::Up(Key::One),
+                KeyEvent::Up(Key::Power),
+            ]);
+
+            assert_eq!(press(&mut keypad).await, Press::Short);
+        });
+    }
+
+    #[test]
+    fn test_long_press() {
+        block_on(async {
+            let mut keypad = crate::test::Keypad::new(&[]);
+            keypad.pending();
+
+            let (result, ()) = join(press(&mut keypad), async {
+                embassy_time::MockDriver::get().advance(LONG_PRESS);
+            })
+            .await;
+            assert_eq!(result, Press::Long);
+        });
+    }
+}
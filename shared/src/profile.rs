@@ -0,0 +1,428 @@
+use embedded_graphics::{draw_target::DrawTarget, pixelcolor::BinaryColor};
+use enum_iterator::Sequence;
+use strum_macros::IntoStaticStr;
+
+use crate::{Keypad, Storage, menu::Menu, theme::Preset, tones::ToneSet, widget::slider::Slider};
+
+pub const MAX_RING_VOLUME: u8 = 5;
+pub const MAX_KEYPAD_TONES: u8 = 3;
+
+// where the profiles are kept in `Storage`, and the layout they are kept in
+const STORAGE_KEY: &str = "profiles";
+const VERSION: u8 = 3;
+const SETTINGS_LENGTH: usize = 6;
+pub const STORED_LENGTH: usize = 3 + SETTINGS_LENGTH * 4;
+
+#[derive(Clone, Copy, Debug, Default, IntoStaticStr, Sequence, PartialEq)]
+pub enum Profile {
+    #[default]
+    General,
+    Silent,
+    Meeting,
+    Outdoor,
+}
+
+#[derive(Clone, Copy, Debug, Default, IntoStaticStr, Sequence, PartialEq)]
+pub enum Ringtone {
+    #[default]
+    #[strum(serialize = "Nokia tune")]
+    NokiaTune,
+    Ring,
+    #[strum(serialize = "Beep once")]
+    BeepOnce,
+}
+
+impl AsRef<str> for Ringtone {
+    fn as_ref(&self) -> &str {
+        (*self).into()
+    }
+}
+
+impl Ringtone {
+    // as RTTTL
+    pub fn tune(self) -> &'static str {
+        match self {
+            Self::NokiaTune => "NokiaTun:d=4,o=5,b=225:8e6,8d6,f#,g#,8c#6,8b,d,e,8b,8a,c#,e,2a",
+            Self::Ring => "Ring:d=16,o=6,b=160:e,g,e,g,e,g,4p,e,g,e,g,e,g",
+            Self::BeepOnce => "Beep:d=8,o=6,b=120:c",
+        }
+    }
+}
+
+// what a profile decides, as in the 3310's Personalise menu
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    pub ringtone: Ringtone,
+    // from 0, which is silent, to `MAX_RING_VOLUME`
+    pub ring_volume: u8,
+    pub vibration: bool,
+    // from 0, which is off, to `MAX_KEYPAD_TONES`
+    pub keypad_tones: u8,
+    pub tone_set: ToneSet,
+    pub warning_tones: bool,
+}
+
+impl Settings {
+    pub fn ring_volume_percent(&self) -> u8 {
+        (u16::from(self.ring_volume) * 100 / u16::from(MAX_RING_VOLUME)) as u8
+    }
+
+    fn to_bytes(self) -> [u8; SETTINGS_LENGTH] {
+        [
+            index(self.ringtone),
+            self.ring_volume,
+            self.vibration.into(),
+            self.keypad_tones,
+            index(self.tone_set),
+            self.warning_tones.into(),
+        ]
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match *bytes {
+            [
+                ringtone,
+                ring_volume,
+                vibration,
+                keypad_tones,
+                tone_set,
+                warning_tones,
+            ] => Some(Self {
+                ringtone: nth(ringtone)?,
+                ring_volume: ring_volume.min(MAX_RING_VOLUME),
+                vibration: vibration != 0,
+                keypad_tones: keypad_tones.min(MAX_KEYPAD_TONES),
+                tone_set: nth(tone_set)?,
+                warning_tones: warning_tones != 0,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Profile {
+    // what each profile starts out as, before it is personalised
+    pub fn defaults(self) -> Settings {
+        match self {
+            Self::General => Settings {
+                ringtone: Ringtone::NokiaTune,
+                ring_volume: 3,
+                vibration: false,
+                keypad_tones: 2,
+                tone_set: ToneSet::Click,
+                warning_tones: true,
+            },
+            Self::Silent => Settings {
+                ringtone: Ringtone::NokiaTune,
+                ring_volume: 0,
+                vibration: true,
+                keypad_tones: 0,
+                tone_set: ToneSet::Click,
+                warning_tones: false,
+            },
+            Self::Meeting => Settings {
+                ringtone: Ringtone::BeepOnce,
+                ring_volume: 1,
+                vibration: true,
+                keypad_tones: 0,
+                tone_set: ToneSet::Click,
+                warning_tones: false,
+            },
+            Self::Outdoor => Settings {
+                ringtone: Ringtone::Ring,
+                ring_volume: MAX_RING_VOLUME,
+                vibration: true,
+                keypad_tones: MAX_KEYPAD_TONES,
+                tone_set: ToneSet::Beep,
+                warning_tones: true,
+            },
+        }
+    }
+}
+
+fn index<T: Sequence + PartialEq>(value: T) -> u8 {
+    enum_iterator::all::<T>()
+        .position(|other| other == value)
+        .unwrap_or_default() as u8
+}
+
+fn nth<T: Sequence>(index: u8) -> Option<T> {
+    enum_iterator::all::<T>().nth(index.into())
+}
+
+// every profile's settings and which of them is in use, along with the theme, which stays the
+// same whichever profile is
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profiles {
+    active: Profile,
+    theme: Preset,
+    settings: [Settings; 4],
+}
+
+impl Default for Profiles {
+    fn default() -> Self {
+        Self {
+            active: Profile::default(),
+            theme: Preset::default(),
+            settings: [
+                Profile::General.defaults(),
+                Profile::Silent.defaults(),
+                Profile::Meeting.defaults(),
+                Profile::Outdoor.defaults(),
+            ],
+        }
+    }
+}
+
+impl Profiles {
+    pub fn active(&self) -> Profile {
+        self.active
+    }
+
+    pub fn set_active(&mut self, profile: Profile) {
+        self.active = profile;
+    }
+
+    pub fn theme(&self) -> Preset {
+        self.theme
+    }
+
+    pub fn set_theme(&mut self, theme: Preset) {
+        self.theme = theme;
+    }
+
+    // those of the active profile
+    pub fn settings(&self) -> &Settings {
+        &self.settings[self.active as usize]
+    }
+
+    pub fn settings_mut(&mut self, profile: Profile) -> &mut Settings {
+        &mut self.settings[profile as usize]
+    }
+
+    pub fn to_bytes(&self) -> [u8; STORED_LENGTH] {
+        let mut bytes = [0; STORED_LENGTH];
+        bytes[0] = VERSION;
+        bytes[1] = index(self.active);
+        bytes[2] = index(self.theme);
+        for (chunk, settings) in bytes[3..].chunks_mut(SETTINGS_LENGTH).zip(self.settings) {
+            chunk.copy_from_slice(&settings.to_bytes());
+        }
+        bytes
+    }
+
+    // `None` for anything not written by `to_bytes` of this version
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != STORED_LENGTH || bytes[0] != VERSION {
+            return None;
+        }
+        let mut profiles = Self {
+            active: nth(bytes[1])?,
+            theme: nth(bytes[2])?,
+            ..Self::default()
+        };
+        for (settings, chunk) in profiles
+            .settings
+            .iter_mut()
+            .zip(bytes[3..].chunks(SETTINGS_LENGTH))
+        {
+            *settings = Settings::from_bytes(chunk)?;
+        }
+        Some(profiles)
+    }
+
+    // the defaults if nothing usable was stored
+    pub fn load(storage: &mut impl Storage) -> Self {
+        let mut bytes = [0; STORED_LENGTH];
+        storage
+            .load(STORAGE_KEY, &mut bytes)
+            .and_then(|length| Self::from_bytes(&bytes[..length]))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, storage: &mut impl Storage) {
+        storage.store(STORAGE_KEY, &self.to_bytes());
+    }
+}
+
+#[derive(Clone, Copy, Debug, IntoStaticStr, PartialEq)]
+enum Setting {
+    Ringtone,
+    #[strum(serialize = "Ring volume")]
+    RingVolume,
+    Vibration,
+    #[strum(serialize = "Keypad tones")]
+    KeypadTones,
+    #[strum(serialize = "Tone set")]
+    ToneSet,
+    #[strum(serialize = "Warning tones")]
+    WarningTones,
+}
+
+impl AsRef<str> for Setting {
+    fn as_ref(&self) -> &str {
+        (*self).into()
+    }
+}
+
+async fn on_off(device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad), value: bool) -> bool {
+    let mut options = if value { ["On", "Off"] } else { ["Off", "On"] };
+    let mut menu = Menu::new(&mut options, Some("Select"), |a, b, c, d, e| {
+        crate::menu::row_render(a, b, c, d, e)
+    });
+    menu.process(device)
+        .await
+        .map_or(value, |option| option == "On")
+}
+
+// changes settings one at a time until Cancel
+pub async fn personalise(
+    device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    settings: &mut Settings,
+) {
+    let mut items = [
+        Setting::Ringtone,
+        Setting::RingVolume,
+        Setting::Vibration,
+        Setting::KeypadTones,
+        Setting::ToneSet,
+        Setting::WarningTones,
+    ];
+    let mut menu = Menu::new(&mut items, Some("Select"), |a, b, c, d, e| {
+        crate::menu::row_render(a, b, c, d, e)
+    });
+    while let Some(setting) = menu.process(device).await {
+        match setting {
+            Setting::Ringtone => {
+                let mut ringtones = [Ringtone::NokiaTune, Ringtone::Ring, Ringtone::BeepOnce];
+                let mut menu = Menu::new(&mut ringtones, Some("Select"), |a, b, c, d, e| {
+                    crate::menu::row_render(a, b, c, d, e)
+                });
+                if let Some(ringtone) = menu.process(device).await {
+                    settings.ringtone = ringtone;
+                }
+            }
+            Setting::RingVolume => {
+                let mut slider = Slider::new(
+                    "Ring volume",
+                    settings.ring_volume.into(),
+                    MAX_RING_VOLUME.into(),
+                );
+                if let Some(level) = slider.process(device).await {
+                    settings.ring_volume = level as u8;
+                }
+            }
+            Setting::Vibration => settings.vibration = on_off(device, settings.vibration).await,
+            Setting::KeypadTones => {
+                let mut slider = Slider::new(
+                    "Keypad tones",
+                    settings.keypad_tones.into(),
+                    MAX_KEYPAD_TONES.into(),
+                );
+                if let Some(level) = slider.process(device).await {
+                    settings.keypad_tones = level as u8;
+                }
+            }
+            Setting::ToneSet => {
+                let mut tone_sets = [ToneSet::Click, ToneSet::Beep, ToneSet::Chirp];
+                let mut menu = Menu::new(&mut tone_sets, Some("Select"), |a, b, c, d, e| {
+                    crate::menu::row_render(a, b, c, d, e)
+                });
+                if let Some(tone_set) = menu.process(device).await {
+                    settings.tone_set = tone_set;
+                }
+            }
+            Setting::WarningTones => {
+                settings.warning_tones = on_off(device, settings.warning_tones).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+    use crate::{Key, KeyEvent};
+
+    // keeps whatever was stored last
+    #[derive(Default)]
+    struct Memory(heapless::Vec<u8, STORED_LENGTH>);
+
+    impl Storage for Memory {
+        fn load(&mut self, _key: &str, buffer: &mut [u8]) -> Option<usize> {
+            let length = self.0.len().min(buffer.len());
+            buffer[..length].copy_from_slice(&self.0[..length]);
+            Some(length)
+        }
+
+        fn store(&mut self, _key: &str, data: &[u8]) {
+            self.0 = heapless::Vec::from_slice(data).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_persistence() {
+        let mut memory = Memory::default();
+        assert_eq!(Profiles::load(&mut memory), Profiles::default());
+
+        let mut profiles = Profiles::default();
+        profiles.set_active(Profile::Meeting);
+        profiles.settings_mut(Profile::Meeting).ringtone = Ringtone::Ring;
+        profiles.set_theme(Preset::LargeText);
+        profiles.save(&mut memory);
+        let loaded = Profiles::load(&mut memory);
+        assert_eq!(loaded, profiles);
+        assert_eq!(loaded.settings().ringtone, Ringtone::Ring);
+        assert_eq!(loaded.theme(), Preset::LargeText);
+
+        // another version's layout is ignored rather than misread
+        memory.0[0] = VERSION + 1;
+        assert_eq!(Profiles::load(&mut memory), Profiles::default());
+    }
+
+    #[test]
+    fn test_defaults() {
+        assert_eq!(Profile::Silent.defaults().ring_volume_percent(), 0);
+        assert_eq!(Profile::Outdoor.defaults().ring_volume_percent(), 100);
+        assert_eq!(Profiles::default().settings(), &Profile::General.defaults());
+    }
+
+    #[test]
+    fn test_personalise() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                60,
+                48,
+                &[
+                    (0, KeyEvent::Down(Key::Select)),
+                    (0, KeyEvent::Up(Key::Select)),
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Up(Key::Down)),
+                    (0, KeyEvent::Down(Key::Select)),
+                    (0, KeyEvent::Up(Key::Select)),
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Up(Key::Down)),
+                    (0, KeyEvent::Down(Key::Select)),
+                    (0, KeyEvent::Up(Key::Select)),
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Up(Key::Down)),
+                    (0, KeyEvent::Down(Key::Select)),
+                    (0, KeyEvent::Up(Key::Select)),
+                    (0, KeyEvent::Down(Key::Cancel)),
+                ],
+            );
+            let mut settings = Profile::General.defaults();
+            personalise(&mut device, &mut settings).await;
+            assert_eq!(
+                settings,
+                Settings {
+                    ringtone: Ringtone::Ring,
+                    ring_volume: 2,
+                    ..Profile::General.defaults()
+                }
+            );
+        });
+    }
+}
@@ -0,0 +1,283 @@
+use embassy_time::Duration;
+use embedded_graphics::{
+    prelude::*,
+    text::{Baseline, Text},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+
+use crate::{DisplayPower, Rtc, time::remaining};
+
+// how long keys can be left alone before each stage of saving power. the backlight keeps its own
+// timeout
+pub const SCREENSAVER_TIMEOUT: Duration = Duration::from_secs(30);
+pub const POWER_DOWN_TIMEOUT: Duration = Duration::from_secs(600);
+
+// how often the screensaver moves, and how far across and down it goes each time
+pub const DRIFT_INTERVAL: Duration = Duration::from_secs(2);
+const DRIFT: Point = Point::new(3, 2);
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Stage {
+    Active,
+    Saving,
+    PoweredDown,
+}
+
+impl Stage {
+    // where things should be once keys have been left alone for `idle`
+    pub fn after(idle: Duration) -> Self {
+        if idle >= POWER_DOWN_TIMEOUT {
+            Self::PoweredDown
+        } else if idle >= SCREENSAVER_TIMEOUT {
+            Self::Saving
+        } else {
+            Self::Active
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Style {
+    #[default]
+    Clock,
+    Logo,
+}
+
+// covers the screen with something that drifts about so that no one
+// part of the panel is left showing the same thing, then puts the panel to sleep
+#[derive(Debug)]
+pub struct Screensaver {
+    style: Style,
+    stage: Stage,
+    position: Point,
+    velocity: Point,
+}
+
+impl Default for Screensaver {
+    fn default() -> Self {
+        Self::new(Style::default())
+    }
+}
+
+impl Screensaver {
+    pub fn new(style: Style) -> Self {
+        Self {
+            style,
+            stage: Stage::Active,
+            position: Point::zero(),
+            velocity: DRIFT,
+        }
+    }
+
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    pub fn stage(&self) -> Stage {
+        self.stage
+    }
+
+    // whether the screensaver is covering whatever was on screen
+    pub fn showing(&self) -> bool {
+        self.stage >= Stage::Saving
+    }
+
+    // how much longer before the screensaver starts, drifts or powers the display down. once it has
+    // powered it down there is nothing left to wait for
+    pub fn next_update(&self, idle: Duration) -> Option<Duration> {
+        match self.stage {
+            Stage::Active => Some(remaining(SCREENSAVER_TIMEOUT, idle)),
+            Stage::Saving => Some(remaining(POWER_DOWN_TIMEOUT, idle).min(DRIFT_INTERVAL)),
+            Stage::PoweredDown => None,
+        }
+    }
+
+    fn size(&self) -> Size {
+        match self.style {
+            Style::Clock => {
+                let font = crate::theme::current().large_font;
+                Size::new(
+                    font.character_size.width * 5 + font.character_spacing * 4,
+                    font.character_size.height,
+                )
+            }
+            Style::Logo => assets::OPERATOR_LOGO.size(),
+        }
+    }
+
+    // bounces off the edges of `area`
+    fn drift(&mut self, area: Size) {
+        let size = self.size();
+        let room = Point::new(
+            area.width.saturating_sub(size.width) as i32,
+            area.height.saturating_sub(size.height) as i32,
+        );
+        let next = self.position + self.velocity;
+        if !(0..=room.x).contains(&next.x) {
+            self.velocity.x = -self.velocity.x;
+        }
+        if !(0..=room.y).contains(&next.y) {
+            self.velocity.y = -self.velocity.y;
+        }
+        self.position = (self.position + self.velocity)
+            .component_max(Point::zero())
+            .component_min(room);
+    }
+
+    pub fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>, timestamp: i64) {
+        let theme = crate::theme::current();
+        theme.clear(target);
+        let top_left = target.bounding_box().top_left + self.position;
+        match self.style {
+            Style::Clock => {
+                let _ = Text::with_baseline(
+                    &crate::time::format_time(timestamp, false),
+                    top_left,
+                    theme.large_text_style(),
+                    Baseline::Top,
+                )
+                .draw(target);
+            }
+            Style::Logo => crate::status_bar::draw_icon(
+                target,
+                &assets::OPERATOR_LOGO,
+                top_left,
+                theme.foreground,
+            ),
+        }
+    }
+
+    // moves on to whatever stage `idle` calls for, drawing the screensaver while it is up
+    pub fn update(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + DisplayPower + Rtc),
+        idle: Duration,
+    ) {
+        let stage = Stage::after(idle);
+        if stage == Stage::Saving {
+            let area = device.bounding_box().size;
+            if self.stage == Stage::Saving {
+                self.drift(area);
+            } else {
+                let size = self.size();
+                self.position = Point::new(
+                    area.width.saturating_sub(size.width) as i32 / 2,
+                    area.height.saturating_sub(size.height) as i32 / 2,
+                );
+            }
+            let timestamp = device.timestamp().unwrap_or_default();
+            self.draw(device, timestamp);
+        }
+        if stage == Stage::PoweredDown && self.stage != Stage::PoweredDown {
+            device.power_down();
+        }
+        self.stage = stage;
+    }
+
+    // powers the panel back up, giving back whether the screen needs putting back as it was
+    pub fn wake(&mut self, device: &mut impl DisplayPower) -> bool {
+        let showing = self.showing();
+        if self.stage == Stage::PoweredDown {
+            device.power_up();
+        }
+        self.stage = Stage::Active;
+        showing
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stages() {
+        assert_eq!(Stage::after(Duration::from_secs(0)), Stage::Active);
+        assert_eq!(Stage::after(Duration::from_secs(15)), Stage::Active);
+        assert_eq!(Stage::after(Duration::from_secs(59)), Stage::Saving);
+        assert_eq!(Stage::after(Duration::from_secs(3600)), Stage::PoweredDown);
+
+        let screensaver = Screensaver::default();
+        assert_eq!(
+            screensaver.next_update(Duration::from_secs(5)),
+            Some(Duration::from_secs(25))
+        );
+        assert_eq!(
+            screensaver.next_update(Duration::from_secs(40)),
+            Some(Duration::MIN)
+        );
+    }
+
+    #[test]
+    fn test_update() {
+        let mut device = crate::test::Device::new(60, 24, &[]);
+        let mut screensaver = Screensaver::new(Style::Clock);
+
+        screensaver.update(&mut device, Duration::from_secs(15));
+        assert_eq!(screensaver.stage(), Stage::Active);
+        assert!(!screensaver.showing());
+
+        screensaver.update(&mut device, SCREENSAVER_TIMEOUT);
+        assert!(screensaver.showing());
+        assert_eq!(screensaver.position, Point::new(5, 2));
+        screensaver.update(&mut device, SCREENSAVER_TIMEOUT + DRIFT_INTERVAL);
+        assert_eq!(screensaver.position, Point::new(8, 4));
+        assert_eq!(
+            screensaver.next_update(POWER_DOWN_TIMEOUT - Duration::from_secs(1)),
+            Some(Duration::from_secs(1))
+        );
+
+        screensaver.update(&mut device, POWER_DOWN_TIMEOUT);
+        assert!(device.powered_down);
+        assert_eq!(screensaver.next_update(POWER_DOWN_TIMEOUT), None);
+
+        assert!(screensaver.wake(&mut device));
+        assert!(!device.powered_down);
+        assert!(!screensaver.wake(&mut device));
+    }
+
+    #[test]
+    fn test_drift() {
+        let mut screensaver = Screensaver::new(Style::Logo);
+        screensaver.position = Point::new(1, 30);
+        // the logo is 72x14, leaving 12 by 34 to move in
+        for _ in 0..4 {
+            screensaver.drift(Size::new(84, 48));
+        }
+        assert_eq!(screensaver.position, Point::new(7, 30));
+        assert_eq!(screensaver.velocity, Point::new(-3, -2));
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut device = crate::test::Device::new(60, 24, &[]);
+        let mut screensaver = Screensaver::new(Style::Clock);
+        screensaver.position = Point::new(6, 2);
+        screensaver.draw(&mut device, 9 * 3600 + 41 * 60);
+        device.display.assert_pattern(&[
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "##########..#######....####################.######..########",
+            "#########....#####..##..##################..#####...########",
+            "########..##..###..####..################...####....########",
+            "########..##..###..####..###############....###..#..########",
+            "#######..####..##..####..##############..#..######..########",
+            "#######..####..##..####..#####...#####..##..######..########",
+            "#######..####..###..##...#####...####..###..######..########",
+            "#######..####..####...#..############..###..######..########",
+            "#######..####..########..############........#####..########",
+            "########..##..#########..#################..######..########",
+            "########..##..####.####..#################..######..########",
+            "#########....#####..##..######...#########..######..########",
+            "##########..#######....#######...#########..###........#####",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+            "############################################################",
+        ]);
+    }
+}
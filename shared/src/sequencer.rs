@@ -0,0 +1,98 @@
+use embassy_time::{Duration, Instant};
+
+// one step of a sequence, lasting this many milliseconds
+pub trait Step {
+    fn millis(&self) -> u16;
+}
+
+// steps through a sequence as its deadlines come up, so that whoever owns the hardware can play
+// one alongside waiting on anything else. what each step does to the hardware is up to them
+#[derive(Debug)]
+pub struct Sequencer<S: 'static> {
+    steps: &'static [S],
+    index: usize,
+    // when the current step is over, while playing
+    until: Option<Instant>,
+}
+
+impl<S> Default for Sequencer<S> {
+    fn default() -> Self {
+        Self {
+            steps: &[],
+            index: 0,
+            until: None,
+        }
+    }
+}
+
+impl<S: Step> Sequencer<S> {
+    pub fn playing(&self) -> bool {
+        self.until.is_some()
+    }
+
+    // cuts short anything already playing. nothing starts until the next `update`
+    pub fn start(&mut self, steps: &'static [S], now: Instant) {
+        self.steps = steps;
+        self.index = 0;
+        self.until = Some(now);
+    }
+
+    pub fn next_update(&self) -> Option<Instant> {
+        self.until
+    }
+
+    // forgets about the sequence, leaving the hardware as the last step left it
+    pub fn cancel(&mut self) {
+        self.until = None;
+    }
+
+    // hands `play` each step that starts by `now`, along with where it is in the sequence. late
+    // updates catch up rather than stretching the sequence out. `true` means the last step just
+    // ended
+    pub fn update(&mut self, now: Instant, mut play: impl FnMut(usize, &S)) -> bool {
+        let Some(mut until) = self.until else {
+            return false;
+        };
+        while until <= now {
+            let Some(step) = self.steps.get(self.index) else {
+                self.until = None;
+                return true;
+            };
+            play(self.index, step);
+            self.index += 1;
+            until += Duration::from_millis(step.millis().into());
+        }
+        self.until = Some(until);
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    impl Step for u8 {
+        fn millis(&self) -> u16 {
+            (*self).into()
+        }
+    }
+
+    #[test]
+    fn test_update() {
+        let start = Instant::from_secs(0);
+        let mut sequencer = Sequencer::default();
+        sequencer.start(&[10u8, 20, 30], start);
+        let mut played = heapless::Vec::<usize, 4>::new();
+        let mut play = |index: usize, _: &u8| played.push(index).unwrap();
+
+        assert!(!sequencer.update(start + Duration::from_millis(35), &mut play));
+        assert_eq!(
+            sequencer.next_update(),
+            Some(start + Duration::from_millis(60))
+        );
+        assert!(sequencer.update(start + Duration::from_millis(60), &mut play));
+        assert!(!sequencer.playing());
+        assert!(!sequencer.update(start + Duration::from_millis(100), &mut play));
+        assert_eq!(played, [0, 1, 2]);
+    }
+}
@@ -0,0 +1,214 @@
+use embedded_graphics::{
+    image::{GetPixel, ImageRaw},
+    mono_font::{MonoTextStyle, ascii::FONT_4X6},
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use heapless::String;
+
+use crate::{Indicators, Rtc, profile::Profile};
+
+pub const HEIGHT: u32 = 7;
+
+// between icons
+const GAP: i32 = 2;
+
+// the battery's body, not counting its terminal
+const BATTERY_WIDTH: u32 = 8;
+const BATTERY_BARS: u32 = 3;
+
+// everything the status bar shows, read from the device in one go so drawing doesn't need it
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusBar {
+    pub time: String<8>,
+    pub battery_level: Option<u8>,
+    pub usb_connected: bool,
+    pub profile: Profile,
+    pub keys_locked: bool,
+}
+
+impl StatusBar {
+    pub fn read(device: &mut (impl Rtc + Indicators)) -> Self {
+        Self {
+            time: crate::time::write_time(device, false),
+            battery_level: device.battery_level(),
+            usb_connected: device.usb_connected(),
+            profile: device.profile(),
+            keys_locked: device.keys_locked(),
+        }
+    }
+
+    fn profile_icon(&self) -> Option<&'static ImageRaw<'static, BinaryColor>> {
+        match self.profile {
+            Profile::General => None,
+            Profile::Silent => Some(&assets::SILENT),
+            Profile::Meeting => Some(&assets::MEETING),
+            Profile::Outdoor => Some(&assets::OUTDOOR),
+        }
+    }
+
+    // along the top of `target`, giving back the area left below it
+    pub fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>) -> Rectangle {
+        let theme = crate::theme::current();
+        let area = target.bounding_box();
+        let bar = Rectangle::new(area.top_left, Size::new(area.size.width, HEIGHT));
+        let _ = bar
+            .into_styled(PrimitiveStyle::with_fill(theme.background))
+            .draw(target);
+        let _ = Text::with_baseline(
+            &self.time,
+            bar.top_left,
+            MonoTextStyle::new(&FONT_4X6, theme.foreground),
+            Baseline::Top,
+        )
+        .draw(target);
+
+        // icons are packed in from the right
+        let mut right = bar.top_left.x + bar.size.width as i32;
+        if let Some(level) = self.battery_level {
+            right = battery(
+                target,
+                Point::new(right, bar.top_left.y),
+                level,
+                theme.foreground,
+            );
+            right -= GAP;
+        }
+        let icons = [
+            self.usb_connected.then_some(&assets::USB),
+            self.keys_locked.then_some(&assets::LOCK),
+            self.profile_icon(),
+        ];
+        for icon in icons.into_iter().flatten() {
+            right -= icon.size().width as i32;
+            draw_icon(
+                target,
+                icon,
+                Point::new(right, bar.top_left.y),
+                theme.foreground,
+            );
+            right -= GAP;
+        }
+
+        Rectangle::new(
+            bar.top_left + Point::new(0, HEIGHT as i32),
+            Size::new(area.size.width, area.size.height.saturating_sub(HEIGHT)),
+        )
+    }
+}
+
+// only the ink of an icon, in `color`, so that icons follow the theme
+pub(crate) fn draw_icon(
+    target: &mut impl DrawTarget<Color = BinaryColor>,
+    icon: &ImageRaw<BinaryColor>,
+    top_left: Point,
+    color: BinaryColor,
+) {
+    let pixels = Rectangle::new(Point::zero(), icon.size())
+        .points()
+        .filter(|point| icon.pixel(*point) == Some(BinaryColor::Off))
+        .map(|point| Pixel(top_left + point, color));
+    let _ = target.draw_iter(pixels);
+}
+
+// a battery ending just left of `top_right`, with a bar for each third of charge. gives back its
+// left edge
+fn battery(
+    target: &mut impl DrawTarget<Color = BinaryColor>,
+    top_right: Point,
+    level: u8,
+    color: BinaryColor,
+) -> i32 {
+    let left = top_right.x - BATTERY_WIDTH as i32 - 1;
+    let body = Rectangle::new(
+        Point::new(left, top_right.y + 1),
+        Size::new(BATTERY_WIDTH, 5),
+    );
+    let _ = body
+        .into_styled(PrimitiveStyle::with_stroke(color, 1))
+        .draw(target);
+    let _ = Rectangle::new(
+        Point::new(left + BATTERY_WIDTH as i32, top_right.y + 2),
+        Size::new(1, 3),
+    )
+    .into_styled(PrimitiveStyle::with_fill(color))
+    .draw(target);
+
+    let bars = (u32::from(level.min(100)) * BATTERY_BARS).div_ceil(100);
+    let bar_width = (BATTERY_WIDTH - 2) / BATTERY_BARS;
+    let _ = Rectangle::new(
+        body.top_left + Point::new(1, 1),
+        Size::new(bars * bar_width, 3),
+    )
+    .into_styled(PrimitiveStyle::with_fill(color))
+    .draw(target);
+    left
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::mock_display::MockDisplay;
+
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let mut device = crate::test::Device::new(60, 7, &[]);
+        device.timestamp = 13 * 3600 + 5 * 60;
+        let status = StatusBar::read(&mut device);
+        assert_eq!(status.time, "13:05");
+        assert_eq!(status.battery_level, None);
+        assert_eq!(status.profile, Profile::General);
+        assert!(!status.keys_locked && !status.usb_connected);
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        let status = StatusBar {
+            time: String::try_from("9:41").unwrap(),
+            battery_level: Some(50),
+            usb_connected: true,
+            profile: Profile::Silent,
+            keys_locked: true,
+        };
+        let mut clipped = display.clipped(&Rectangle::new(Point::zero(), Size::new(48, 8)));
+        let rest = status.draw(&mut clipped);
+        assert_eq!(rest, Rectangle::new(Point::new(0, 7), Size::new(48, 1)));
+        display.assert_pattern(&[
+            "#.######.#.##.##.#######...#####...#############",
+            ".#.##.##.#.#..#..#.#.##.###.####.#.####........#",
+            "#..#####...##....##.###.....###.....###.....##..",
+            "##.#######.##....#.#.##..#..###.....###.....##..",
+            "..###.####.#.....######..#..####...####.....##..",
+            "################.######.....#####.#####........#",
+            "################################################",
+        ]);
+    }
+
+    #[test]
+    fn test_draw_plain() {
+        let status = StatusBar {
+            time: String::try_from("12:00").unwrap(),
+            battery_level: None,
+            usb_connected: false,
+            profile: Profile::General,
+            keys_locked: false,
+        };
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        status.draw(&mut display.clipped(&Rectangle::new(Point::zero(), Size::new(20, 7))));
+        display.assert_pattern(&[
+            "#.###.#######.###.##",
+            "..##.#.##.##.#.#.#.#",
+            "#.####.#####...#...#",
+            "#.###.######.#.#.#.#",
+            "...#...##.###.###.##",
+            "####################",
+            "####################",
+        ]);
+    }
+}
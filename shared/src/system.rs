@@ -0,0 +1,737 @@
+use embassy_futures::select::{Either, Either4, select, select4};
+use embassy_time::{Duration, Instant, Timer};
+use embedded_graphics::{
+    framebuffer::{Framebuffer, buffer_size},
+    image::{Image, ImageRaw},
+    pixelcolor::raw::{LittleEndian, RawU1},
+    prelude::*,
+};
+use embedded_graphics_core::{pixelcolor::BinaryColor, primitives::Rectangle};
+
+use crate::{
+    Backlight, Buzzer, DisplayPower, Indicators, Key, KeyEvent, Keypad, PowerButton, Rtc, Storage,
+    VibrationMotor,
+    backlight::Light,
+    confirmation::Confirmation,
+    console::Console,
+    key_lock::{KeyLock, Verdict},
+    notification::{self, Notification},
+    power::{self, Action, Press},
+    profile::{self, Profile, Profiles},
+    screensaver::{Screensaver, Stage, Style},
+    theme::{self, Preset},
+    tones::{self, Note, Tone},
+    vibration::{self, Player},
+};
+
+pub const IDLE_LOCK: Duration = Duration::from_secs(60);
+
+// big enough for every panel we drive
+const WIDTH: usize = 128;
+const HEIGHT: usize = 64;
+
+type Screen = Framebuffer<
+    BinaryColor,
+    RawU1,
+    LittleEndian,
+    WIDTH,
+    HEIGHT,
+    { buffer_size::<BinaryColor>(WIDTH, HEIGHT) },
+>;
+
+// sits between the foreground app and the hardware. the app draws through it as usual (a copy is
+// kept so the app's screen can be put back after a system overlay) and reads keys through it, with
+// system keys such as power handled before the app ever sees them.
+//
+// there is no task of its own behind it: vibration patterns, key tones, backlight fades and the
+// screensaver only move on while the app is waiting for a key, so an app that is busy for a while
+// should wait with `event_before` rather than on a bare timer.
+pub struct System<D> {
+    device: D,
+    screen: Screen,
+    profiles: Profiles,
+    // what the app last asked for, before the profile scales it
+    volume: u8,
+    key_lock: KeyLock,
+    last_activity: Instant,
+    light: Light,
+    player: Player,
+    // key and warning tones, which only sound while the app hasn't got the buzzer sounding. what
+    // the app last set is kept to put back afterwards
+    tone: Tone,
+    app_sounding: bool,
+    app_frequency: Option<u16>,
+    screensaver: Screensaver,
+    // the key that woke the screen, whose release is swallowed along with it
+    waking: Option<Key>,
+    // taken off the queue but not yet dismissed
+    pending: Option<Notification>,
+}
+
+impl<D> System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    pub fn new(mut device: D) -> Self {
+        let profiles = Profiles::load(&mut device);
+        theme::set(profiles.theme());
+        Self {
+            device,
+            screen: Framebuffer::new(),
+            profiles,
+            volume: 50,
+            key_lock: KeyLock::new(Some(IDLE_LOCK)),
+            last_activity: Instant::now(),
+            light: Light::default(),
+            player: Player::new(),
+            tone: Tone::new(),
+            app_sounding: false,
+            app_frequency: None,
+            screensaver: Screensaver::default(),
+            waking: None,
+            pending: None,
+        }
+    }
+
+    pub fn profile(&self) -> Profile {
+        self.profiles.active()
+    }
+
+    pub fn set_profile(&mut self, profile: Profile) {
+        self.profiles.set_active(profile);
+        self.profiles.save(&mut self.device);
+        self.device.set_volume(self.scaled_volume());
+    }
+
+    // the app's volume, turned down as far as the active profile's ring volume
+    fn scaled_volume(&self) -> u8 {
+        let percent = self.profiles.settings().ring_volume_percent();
+        (u16::from(self.volume) * u16::from(percent) / 100) as u8
+    }
+
+    pub fn locked(&self) -> bool {
+        self.key_lock.locked()
+    }
+
+    pub fn set_idle_lock(&mut self, timeout: Option<Duration>) {
+        self.key_lock.set_idle_timeout(timeout);
+    }
+
+    // percent
+    pub fn set_backlight_brightness(&mut self, brightness: u8) {
+        self.light.set_brightness(brightness);
+    }
+
+    // `None` keeps the backlight on for good
+    pub fn set_backlight_timeout(&mut self, timeout: Option<Duration>) {
+        self.light.set_timeout(timeout);
+    }
+
+    pub fn set_screensaver(&mut self, style: Style) {
+        self.screensaver.set_style(style);
+    }
+
+    // how long since a key was last pressed or anything else happened, whichever was later
+    fn idle(&mut self) -> Duration {
+        let since_activity = self.last_activity.elapsed();
+        self.device
+            .last_pressed()
+            .map_or(since_activity, |since_pressed| {
+                since_pressed.min(since_activity)
+            })
+    }
+
+    // puts back whatever belongs on screen once an overlay is done with it
+    fn restore(&mut self) {
+        if self.screensaver.showing() {
+            return;
+        }
+        if self.key_lock.locked() {
+            Console::new().draw(
+                &mut self.device,
+                "Keys locked\nPress Select then * to unlock",
+            );
+        } else {
+            let image: ImageRaw<BinaryColor, LittleEndian> = self.screen.as_image();
+            let _ = Image::new(&image, Point::zero()).draw(&mut self.device);
+        }
+    }
+
+    fn play_tone(&mut self, notes: &'static [Note], volume: u8) {
+        if self.app_sounding || volume == 0 {
+            return;
+        }
+        self.device.set_volume(volume);
+        self.tone.start(&mut self.device, notes, Instant::now());
+    }
+
+    // puts the buzzer back as the app left it, once a tone is over or the app wants it back
+    fn give_back_buzzer(&mut self) {
+        self.tone.cancel();
+        let _ = self.device.mute_buzzer();
+        self.device.set_volume(self.scaled_volume());
+        if let Some(frequency) = self.app_frequency {
+            let _ = self.device.set_frequency(frequency);
+        }
+    }
+
+    fn lock(&mut self) {
+        self.key_lock.lock();
+        self.restore();
+    }
+
+    async fn power_key(&mut self) {
+        match power::press(&mut self.device).await {
+            Press::Short if self.key_lock.locked() => {}
+            Press::Short => {
+                let mut actions = [
+                    Action::Profile(Profile::General),
+                    Action::Profile(Profile::Silent),
+                    Action::Profile(Profile::Meeting),
+                    Action::Profile(Profile::Outdoor),
+                    Action::Personalise,
+                    Action::Theme,
+                    Action::LockKeys,
+                    Action::SwitchOff,
+                ];
+                let mut menu =
+                    crate::menu::Menu::new(&mut actions, Some("Select"), |a, b, c, d, e| {
+                        crate::menu::row_render(a, b, c, d, e)
+                    })
+                    .with_shortcuts();
+                match menu.process(&mut self.device).await {
+                    Some(Action::Profile(profile)) => {
+                        self.set_profile(profile);
+                    }
+                    Some(Action::Personalise) => {
+                        self.personalise().await;
+                    }
+                    Some(Action::Theme) => {
+                        self.choose_theme().await;
+                    }
+                    Some(Action::LockKeys) => {
+                        self.key_lock.lock();
+                    }
+                    Some(Action::SwitchOff) => {
+                        self.switch_off().await;
+                    }
+                    None => {}
+                }
+            }
+            Press::Long => {
+                self.switch_off().await;
+            }
+        }
+        self.restore();
+    }
+
+    // edits the active profile
+    async fn personalise(&mut self) {
+        let active = self.profiles.active();
+        profile::personalise(&mut self.device, self.profiles.settings_mut(active)).await;
+        self.set_profile(active);
+    }
+
+    async fn choose_theme(&mut self) {
+        let mut presets = [
+            Preset::Standard,
+            Preset::Inverted,
+            Preset::LargeText,
+            Preset::Classic,
+        ];
+        let mut menu = crate::menu::Menu::new(&mut presets, Some("Select"), |a, b, c, d, e| {
+            crate::menu::row_render(a, b, c, d, e)
+        });
+        if let Some(preset) = menu.process(&mut self.device).await {
+            theme::set(preset);
+            self.profiles.set_theme(preset);
+            self.profiles.save(&mut self.device);
+        }
+    }
+
+    // over whatever is on screen, screensaver and lock screen included, until it is dismissed. it
+    // stays pending until then, so it is shown again from the start if this is cut short
+    async fn notify(&mut self) {
+        if self.pending.is_none() {
+            return;
+        }
+        self.screensaver.wake(&mut self.device);
+        self.light.wake();
+        self.player.stop(&mut self.device);
+        if self.tone.playing() {
+            self.give_back_buzzer();
+        }
+        if let Some(notification) = &self.pending {
+            notification::show(&mut self.device, notification, self.profiles.settings()).await;
+        }
+        self.pending = None;
+        self.device.set_volume(self.scaled_volume());
+        self.light.restore(&mut self.device);
+        self.last_activity = Instant::now();
+        self.restore();
+    }
+
+    async fn switch_off(&mut self) {
+        let mut confirmation = Confirmation::new("Switch off?", "Yes", "No", false);
+        loop {
+            if let Some(result) = confirmation.run(&mut self.device).await {
+                if result {
+                    PowerButton::clear(&mut self.device);
+                }
+                return;
+            }
+        }
+    }
+}
+
+impl<D> System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    // as `serve`, but should it be dropped anyway nothing is left buzzing that nobody would stop
+    async fn next(&mut self, until: Option<Instant>) -> Option<KeyEvent> {
+        let mut waiting = Waiting {
+            system: self,
+            finished: false,
+        };
+        let event = waiting.system.serve(until).await;
+        waiting.finished = true;
+        event
+    }
+
+    // the next key for the app, or `None` once `until` passes. everything the system does for
+    // itself happens in here, so this must never be dropped part way through: that is why the app
+    // gives it a deadline rather than racing it against a timer
+    async fn serve(&mut self, until: Option<Instant>) -> Option<KeyEvent> {
+        loop {
+            if self.pending.is_some() {
+                self.notify().await;
+            }
+            let lock = self.key_lock.idle_deadline(self.last_activity);
+            let idle = self.idle();
+            let updates = [
+                self.light.next_update(idle),
+                self.screensaver.next_update(idle),
+            ];
+            let deadline = lock
+                .into_iter()
+                .chain(
+                    updates
+                        .into_iter()
+                        .flatten()
+                        .map(|wait| Instant::now() + wait),
+                )
+                .chain(self.player.next_update())
+                .chain(self.tone.next_update())
+                .chain(until)
+                .min();
+            let timer = async {
+                match deadline {
+                    Some(deadline) => Timer::at(deadline).await,
+                    None => core::future::pending().await,
+                }
+            };
+            let event = match select4(
+                timer,
+                self.device.event(),
+                notification::next(),
+                select(vibration::requested(), tones::warned()),
+            )
+            .await
+            {
+                Either4::First(()) => {
+                    if lock.is_some_and(|lock| lock <= Instant::now()) {
+                        self.lock();
+                    }
+                    self.player.update(&mut self.device, Instant::now());
+                    if self.tone.playing() {
+                        self.tone.update(&mut self.device, Instant::now());
+                        if !self.tone.playing() {
+                            self.give_back_buzzer();
+                        }
+                    }
+                    let idle = self.idle();
+                    self.light.update(&mut self.device, idle);
+                    self.screensaver.update(&mut self.device, idle);
+                    if until.is_some_and(|until| until <= Instant::now()) {
+                        return None;
+                    }
+                    continue;
+                }
+                Either4::Second(event) => event,
+                Either4::Third(notification) => {
+                    self.pending = Some(notification);
+                    self.notify().await;
+                    continue;
+                }
+                Either4::Fourth(Either::First((pattern, intensity))) => {
+                    if self.profiles.settings().vibration {
+                        self.player
+                            .start(&mut self.device, pattern, intensity, Instant::now());
+                    }
+                    continue;
+                }
+                Either4::Fourth(Either::Second(())) => {
+                    if self.profiles.settings().warning_tones {
+                        self.play_tone(tones::WARNING, tones::WARNING_VOLUME);
+                    }
+                    continue;
+                }
+            };
+            self.last_activity = Instant::now();
+            if let KeyEvent::Down(_) = event {
+                self.light.wake();
+                let settings = self.profiles.settings();
+                let volume = tones::key_volume(settings.keypad_tones);
+                self.play_tone(settings.tone_set.notes(), volume);
+            }
+
+            // a key that wakes the screen up from the screensaver does only that
+            if let KeyEvent::Down(key) = &event
+                && self.screensaver.stage() != Stage::Active
+                && self.screensaver.wake(&mut self.device)
+            {
+                self.waking = Some(key.clone());
+                self.restore();
+                continue;
+            }
+            if let KeyEvent::Up(key) = &event
+                && self.waking.as_ref() == Some(key)
+            {
+                self.waking = None;
+                continue;
+            }
+
+            match event {
+                KeyEvent::Down(Key::Power) => {
+                    // nothing would move a tone on while the power menu is up
+                    if self.tone.playing() {
+                        self.give_back_buzzer();
+                    }
+                    self.power_key().await;
+                }
+                KeyEvent::Up(Key::Power) => {}
+                event => match self.key_lock.process(&event, self.last_activity) {
+                    Verdict::Pass => return Some(event),
+                    Verdict::Drop => {}
+                    Verdict::Lock => self.lock(),
+                    Verdict::Unlock => self.restore(),
+                },
+            }
+        }
+    }
+}
+
+struct Waiting<'a, D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    system: &'a mut System<D>,
+    finished: bool,
+}
+
+impl<D> Drop for Waiting<'_, D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    fn drop(&mut self) {
+        if !self.finished {
+            self.system.player.stop(&mut self.system.device);
+            if self.system.tone.playing() {
+                self.system.give_back_buzzer();
+            }
+        }
+    }
+}
+
+impl<D> Keypad for System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    async fn event(&mut self) -> KeyEvent {
+        loop {
+            if let Some(event) = self.next(None).await {
+                return event;
+            }
+        }
+    }
+
+    async fn event_before(&mut self, deadline: Instant) -> Option<KeyEvent> {
+        self.next(Some(deadline)).await
+    }
+
+    fn bounce_count(&mut self, key: &Key) -> Option<u32> {
+        self.device.bounce_count(key)
+    }
+
+    fn last_pressed(&mut self) -> Option<embassy_time::Duration> {
+        self.device.last_pressed()
+    }
+}
+
+impl<D> DrawTarget for System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    type Color = BinaryColor;
+
+    type Error = ();
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        // the lock screen and screensaver stay up, the app only gets to draw into the copy
+        if self.key_lock.locked() || self.screensaver.showing() {
+            let _ = self.screen.draw_iter(pixels);
+            return Ok(());
+        }
+        let pixels = pixels.into_iter().inspect(|Pixel(point, color)| {
+            let _ = self.screen.draw_iter([Pixel(*point, *color)]);
+        });
+        self.device.draw_iter(pixels)
+    }
+}
+
+impl<D> Dimensions for System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.device.bounding_box()
+    }
+}
+
+impl<D> Backlight for System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    fn on(&mut self) {
+        self.device.on();
+    }
+
+    fn off(&mut self) {
+        self.device.off();
+    }
+
+    fn set_brightness(&mut self, level: u8) {
+        self.device.set_brightness(level);
+    }
+}
+
+impl<D> VibrationMotor for System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    fn start_vibrating(&mut self) {
+        if self.profiles.settings().vibration {
+            self.device.start_vibrating();
+        }
+    }
+
+    fn stop_vibrating(&mut self) {
+        self.device.stop_vibrating();
+    }
+
+    fn set_intensity(&mut self, level: u8) {
+        self.device.set_intensity(level);
+    }
+}
+
+impl<D> Buzzer for System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    type Error = <D as Buzzer>::Error;
+
+    fn set_frequency(&mut self, frequency: u16) -> Result<(), Self::Error> {
+        self.app_frequency = Some(frequency);
+        if self.tone.playing() {
+            self.give_back_buzzer();
+        }
+        self.device.set_frequency(frequency)
+    }
+
+    fn set_volume(&mut self, volume: u8) {
+        self.volume = volume;
+        if !self.tone.playing() {
+            self.device.set_volume(self.scaled_volume());
+        }
+    }
+
+    // leaves any tone that is playing be
+    fn mute_buzzer(&mut self) -> Result<(), Self::Error> {
+        self.app_sounding = false;
+        if self.tone.playing() {
+            return Ok(());
+        }
+        self.device.mute_buzzer()
+    }
+
+    // alarms ring through the device itself, so everything heard from here is subject to the
+    // profile and nothing at all is in Silent. the app always wins over a tone
+    fn unmute_buzzer(&mut self) -> Result<(), Self::Error> {
+        if self.profiles.settings().ring_volume == 0 {
+            return Ok(());
+        }
+        self.app_sounding = true;
+        if self.tone.playing() {
+            self.give_back_buzzer();
+        }
+        self.device.unmute_buzzer()
+    }
+}
+
+impl<D> Rtc for System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    type Error = <D as Rtc>::Error;
+
+    fn timestamp(&mut self) -> Result<i64, Self::Error> {
+        self.device.timestamp()
+    }
+
+    fn set_timestamp(&mut self, time: i64) {
+        self.device.set_timestamp(time);
+    }
+}
+
+impl<D> DisplayPower for System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    fn power_down(&mut self) {
+        self.device.power_down();
+    }
+
+    fn power_up(&mut self) {
+        self.device.power_up();
+    }
+}
+
+impl<D> Indicators for System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    fn battery_level(&mut self) -> Option<u8> {
+        self.device.battery_level()
+    }
+
+    fn usb_connected(&mut self) -> bool {
+        self.device.usb_connected()
+    }
+
+    fn profile(&self) -> Profile {
+        self.profiles.active()
+    }
+
+    fn keys_locked(&self) -> bool {
+        self.key_lock.locked()
+    }
+}
+
+impl<D> Storage for System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    fn load(&mut self, key: &str, buffer: &mut [u8]) -> Option<usize> {
+        self.device.load(key, buffer)
+    }
+
+    fn store(&mut self, key: &str, data: &[u8]) {
+        self.device.store(key, data);
+    }
+}
+
+impl<D> PowerButton for System<D>
+where
+    D: crate::Device + PowerButton + Send,
+{
+    fn clear(&mut self) {
+        PowerButton::clear(&mut self.device);
+    }
+}
+
+impl<D> crate::Device for System<D> where D: crate::Device + PowerButton + Send {}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use core::{
+        future::Future,
+        task::{Context, Waker},
+    };
+
+    use futures_executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn test_event_before() {
+        block_on(async {
+            let device = crate::test::Device::new(
+                60,
+                48,
+                &[
+                    (0, KeyEvent::Down(Key::Power)),
+                    (0, KeyEvent::Up(Key::Power)),
+                    // the power menu stays up past the app's deadline, and gets this
+                    (2_000, KeyEvent::Down(Key::Cancel)),
+                    (0, KeyEvent::Down(Key::Two)),
+                ],
+            );
+            let mut system = System::new(device);
+            let deadline = Instant::now() + Duration::from_secs(1);
+            assert_eq!(
+                system.event_before(deadline).await,
+                Some(KeyEvent::Down(Key::Two))
+            );
+            let deadline = Instant::now() + Duration::from_secs(1);
+            assert_eq!(system.event_before(deadline).await, None);
+        });
+    }
+
+    #[test]
+    fn test_power_key_tone() {
+        let mut system = System::new(crate::test::Device::new(
+            60,
+            48,
+            &[
+                (0, KeyEvent::Down(Key::Power)),
+                (0, KeyEvent::Up(Key::Power)),
+            ],
+        ));
+        system.set_profile(Profile::Outdoor);
+        assert!(system.profiles.settings().keypad_tones > 0);
+        // left waiting in the power menu, and forgotten rather than dropped so nothing is tidied up
+        let mut event = std::boxed::Box::pin(system.event());
+        let mut context = Context::from_waker(Waker::noop());
+        assert!(event.as_mut().poll(&mut context).is_pending());
+        core::mem::forget(event);
+        assert_eq!(system.device.frequency, None);
+    }
+
+    #[test]
+    fn test_dropped() {
+        block_on(async {
+            let _clock = crate::test::clock();
+            let mut system = System::new(crate::test::Device::new(60, 48, &[]));
+            system.player.start(
+                &mut system.device,
+                vibration::Pattern::LongBuzz,
+                100,
+                Instant::now(),
+            );
+            system.play_tone(tones::WARNING, tones::WARNING_VOLUME);
+            assert!(system.device.vibrating && system.device.frequency.is_some());
+            // given up on while waiting for a key
+            assert!(matches!(
+                select(system.event(), async {}).await,
+                Either::Second(())
+            ));
+            assert!(!system.device.vibrating && system.device.frequency.is_none());
+        });
+    }
+}
@@ -0,0 +1,426 @@
+use embassy_time::{Duration, Instant};
+
+use crate::{Key, KeyEvent, Keypad};
+
+mod dictionary;
+
+pub const MAX_WORD: usize = 16;
+// holding hash this long switches between predictive text and multi-tap
+pub const LONG_PRESS: Duration = Duration::from_millis(1500);
+// how soon a key has to be pressed again to move on to its next letter while spelling
+pub const SPELL_TIMEOUT: Duration = Duration::from_millis(1500);
+
+const LETTERS: [&str; 8] = ["abc", "def", "ghi", "jkl", "mno", "pqrs", "tuv", "wxyz"];
+
+pub type Word = heapless::String<MAX_WORD>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    // replaces whatever was shown for the word being composed
+    Composing(Word),
+    // nothing in the dictionary fits so the word is spelled out one letter at a time instead. also
+    // replaces whatever was shown for the word being composed.
+    Spelling(Word),
+    // replaces whatever was shown for the word being composed, and is final
+    Decided(Word),
+    Backspace,
+    TogglePredictive,
+    CursorLeft,
+    CursorRight,
+}
+
+fn word(s: &str) -> Word {
+    let mut word = Word::new();
+    let _ = word.push_str(s);
+    word
+}
+
+fn key_index(key: &Key) -> Option<u8> {
+    match key {
+        Key::Two => Some(0),
+        Key::Three => Some(1),
+        Key::Four => Some(2),
+        Key::Five => Some(3),
+        Key::Six => Some(4),
+        Key::Seven => Some(5),
+        Key::Eight => Some(6),
+        Key::Nine => Some(7),
+        _ => None,
+    }
+}
+
+fn keys_of(word: &str) -> Option<heapless::Vec<u8, MAX_WORD>> {
+    word.chars()
+        .map(|c| {
+            LETTERS
+                .iter()
+                .position(|letters| letters.contains(c))
+                .map(|k| k as u8)
+        })
+        .collect()
+}
+
+#[derive(Debug, Default)]
+struct Spelling {
+    word: Word,
+    // key and letter of the last press, which the same key cycles through until the timeout
+    last: Option<(u8, usize, Instant)>,
+}
+
+// `N` is how many words the user has spelled that are remembered, oldest are forgotten first
+#[derive(Debug, Default)]
+pub struct T9<const N: usize> {
+    keys: heapless::Vec<u8, MAX_WORD>,
+    candidate: usize,
+    spelling: Option<Spelling>,
+    learned: heapless::Vec<Word, N>,
+    // when hash, while it is held, switches predictive text off
+    hash_held: Option<Instant>,
+}
+
+impl<const N: usize> T9<N> {
+    pub fn new() -> Self {
+        Self {
+            keys: heapless::Vec::new(),
+            candidate: 0,
+            spelling: None,
+            learned: heapless::Vec::new(),
+            hash_held: None,
+        }
+    }
+
+    // remembered words come before dictionary words, the most recently learned first
+    fn candidate(&self, index: usize) -> Option<Word> {
+        let learned = self
+            .learned
+            .iter()
+            .rev()
+            .filter(|w| keys_of(w).as_deref() == Some(&self.keys[..]))
+            .map(|w| w.as_str());
+        let words = dictionary::words(&self.keys).unwrap_or(&[]);
+        match learned.chain(words.iter().copied()).nth(index) {
+            Some(w) => Some(word(w)),
+            // nothing is typed by exactly these keys (yet), show the start of a longer word
+            None if index == 0 => self
+                .learned
+                .iter()
+                .rev()
+                .map(|w| w.as_str())
+                .find(|w| keys_of(w).is_some_and(|keys| keys.starts_with(&self.keys)))
+                .or_else(|| dictionary::completion(&self.keys))
+                .map(|w| word(&w[..self.keys.len()])),
+            None => None,
+        }
+    }
+
+    pub fn learned(&self) -> &[Word] {
+        &self.learned
+    }
+
+    pub fn learn(&mut self, w: &str) {
+        let Some(keys) = keys_of(w) else {
+            return;
+        };
+        if w.is_empty()
+            || self.learned.iter().any(|learned| learned == w)
+            || dictionary::words(&keys).is_some_and(|words| words.contains(&w))
+        {
+            return;
+        }
+        if self.learned.is_full() {
+            self.learned.remove(0);
+        }
+        let _ = self.learned.push(word(w));
+    }
+
+    fn spell(&mut self) -> Option<Event> {
+        self.keys.clear();
+        self.candidate = 0;
+        self.spelling = Some(Spelling::default());
+        Some(Event::Spelling(Word::new()))
+    }
+
+    // finishes the word being composed, followed by `suffix`
+    fn decide(&mut self, suffix: &str) -> Option<Event> {
+        let mut decided = match self.spelling.take() {
+            Some(spelling) => {
+                self.learn(&spelling.word);
+                spelling.word
+            }
+            None => self.candidate(self.candidate).unwrap_or_default(),
+        };
+        self.keys.clear();
+        self.candidate = 0;
+        let _ = decided.push_str(suffix);
+        Some(Event::Decided(decided))
+    }
+
+    // call before leaving predictive text so the word being composed is not lost
+    pub fn flush(&mut self) -> Option<Event> {
+        if self.keys.is_empty() && self.spelling.is_none() {
+            None
+        } else {
+            self.decide("")
+        }
+    }
+
+    fn spelling_key(&mut self, key: &Key, at: Instant) -> Option<Event> {
+        let spelling = self.spelling.as_mut()?;
+        match (key, key_index(key)) {
+            (_, Some(k)) => {
+                let letters = LETTERS[k as usize].as_bytes();
+                match spelling.last {
+                    Some((last, letter, last_at)) if last == k && at - last_at <= SPELL_TIMEOUT => {
+                        let letter = (letter + 1) % letters.len();
+                        spelling.word.pop();
+                        let _ = spelling.word.push(letters[letter] as char);
+                        spelling.last = Some((k, letter, at));
+                    }
+                    _ => {
+                        spelling.word.push(letters[0] as char).ok()?;
+                        spelling.last = Some((k, 0, at));
+                    }
+                }
+                Some(Event::Spelling(spelling.word.clone()))
+            }
+            (Key::Cancel, _) if spelling.word.is_empty() => {
+                self.spelling = None;
+                Some(Event::Composing(Word::new()))
+            }
+            (Key::Cancel, _) => {
+                spelling.word.pop();
+                spelling.last = None;
+                Some(Event::Spelling(spelling.word.clone()))
+            }
+            (Key::Select, _) => self.decide(""),
+            (Key::Zero, _) => self.decide(" "),
+            (Key::One, _) => self.decide("."),
+            _ => None,
+        }
+    }
+
+    pub fn key(&mut self, key: &Key, at: Instant) -> Option<Event> {
+        if self.spelling.is_some() {
+            return self.spelling_key(key, at);
+        }
+        match (key, key_index(key)) {
+            (_, Some(k)) => {
+                self.keys.push(k).ok()?;
+                self.candidate = 0;
+                match self.candidate(0) {
+                    Some(candidate) => Some(Event::Composing(candidate)),
+                    None => self.spell(),
+                }
+            }
+            (Key::Asterisk, _) if !self.keys.is_empty() => {
+                self.candidate += 1;
+                match self.candidate(self.candidate) {
+                    Some(candidate) => Some(Event::Composing(candidate)),
+                    None => self.spell(),
+                }
+            }
+            (Key::Cancel, _) if self.keys.is_empty() => Some(Event::Backspace),
+            (Key::Up, _) if self.keys.is_empty() => Some(Event::CursorLeft),
+            (Key::Down, _) if self.keys.is_empty() => Some(Event::CursorRight),
+            (Key::Cancel, _) => {
+                self.keys.pop();
+                self.candidate = 0;
+                Some(Event::Composing(self.candidate(0).unwrap_or_default()))
+            }
+            (Key::Select, _) if !self.keys.is_empty() => self.decide(""),
+            (Key::Zero, _) => self.decide(" "),
+            (Key::One, _) => self.decide("."),
+            _ => None,
+        }
+    }
+
+    pub async fn event(&mut self, keypad: &mut impl Keypad) -> Option<Event> {
+        loop {
+            if let Some(event) = self.event_before(keypad, Instant::MAX).await {
+                return event;
+            }
+        }
+    }
+
+    // what `event` would give, or `None` once `until` passes first. hash carries on being held
+    // across calls, and anything else pressed meanwhile is dropped
+    pub async fn event_before(
+        &mut self,
+        keypad: &mut impl Keypad,
+        until: Instant,
+    ) -> Option<Option<Event>> {
+        let deadline = self.hash_held.map_or(until, |held| held.min(until));
+        let Some(event) = keypad.event_before(deadline).await else {
+            if self.hash_held.is_some_and(|held| held <= Instant::now()) {
+                self.hash_held = None;
+                return Some(Some(Event::TogglePredictive));
+            }
+            return None;
+        };
+        Some(match event {
+            KeyEvent::Down(Key::Hash) => {
+                self.hash_held = Some(Instant::now() + LONG_PRESS);
+                None
+            }
+            KeyEvent::Up(Key::Hash) => {
+                self.hash_held = None;
+                None
+            }
+            _ if self.hash_held.is_some() => None,
+            KeyEvent::Down(key) => self.key(&key, Instant::now()),
+            KeyEvent::Up(_) => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embassy_futures::join::join;
+    use futures_executor::block_on;
+
+    use super::*;
+
+    fn at(millis: u64) -> Instant {
+        Instant::from_millis(millis)
+    }
+
+    #[test]
+    fn test_dictionary() {
+        assert_eq!(
+            dictionary::words(&[2, 4, 4, 1]),
+            Some(&["home", "good", "gone"][..])
+        );
+        assert_eq!(dictionary::words(&[0]), Some(&[][..]));
+        assert_eq!(dictionary::completion(&[0]), Some("back"));
+        assert_eq!(dictionary::words(&[7, 7, 7, 7]), None);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let mut t9: T9<4> = T9::new();
+
+        assert_eq!(t9.key(&Key::Four, at(0)), Some(Event::Composing(word("h"))));
+        assert_eq!(t9.key(&Key::Six, at(0)), Some(Event::Composing(word("in"))));
+        assert_eq!(
+            t9.key(&Key::Six, at(0)),
+            Some(Event::Composing(word("hom")))
+        );
+        assert_eq!(
+            t9.key(&Key::Three, at(0)),
+            Some(Event::Composing(word("home")))
+        );
+        assert_eq!(
+            t9.key(&Key::Asterisk, at(0)),
+            Some(Event::Composing(word("good")))
+        );
+        assert_eq!(
+            t9.key(&Key::Asterisk, at(0)),
+            Some(Event::Composing(word("gone")))
+        );
+        assert_eq!(
+            t9.key(&Key::Zero, at(0)),
+            Some(Event::Decided(word("gone ")))
+        );
+        assert_eq!(t9.key(&Key::Cancel, at(0)), Some(Event::Backspace));
+    }
+
+    #[test]
+    fn test_spelling() {
+        let mut t9: T9<4> = T9::new();
+
+        t9.key(&Key::Two, at(0));
+        t9.key(&Key::Seven, at(0));
+        assert_eq!(t9.key(&Key::Four, at(0)), Some(Event::Spelling(word(""))));
+        for (key, millis) in [
+            (Key::Two, 0),
+            (Key::Two, 100),
+            (Key::Seven, 200),
+            (Key::Seven, 300),
+            (Key::Seven, 400),
+            (Key::Four, 500),
+            (Key::Four, 600),
+            (Key::Four, 700),
+            (Key::Seven, 800),
+            (Key::Seven, 900),
+            (Key::Eight, 1000),
+            (Key::Eight, 1100),
+            (Key::Three, 1200),
+            (Key::Three, 1300),
+        ] {
+            t9.key(&key, at(millis));
+        }
+        assert_eq!(
+            t9.key(&Key::Three, at(5000)),
+            Some(Event::Spelling(word("briqued")))
+        );
+        assert_eq!(
+            t9.key(&Key::Cancel, at(5100)),
+            Some(Event::Spelling(word("brique")))
+        );
+        assert_eq!(
+            t9.key(&Key::Select, at(5200)),
+            Some(Event::Decided(word("brique")))
+        );
+        assert_eq!(t9.learned(), &[word("brique")]);
+
+        for key in [Key::Two, Key::Seven, Key::Four, Key::Seven, Key::Eight] {
+            t9.key(&key, at(6000));
+        }
+        assert_eq!(
+            t9.key(&Key::Three, at(6000)),
+            Some(Event::Composing(word("brique")))
+        );
+    }
+
+    #[test]
+    fn test_learned_are_forgotten() {
+        let mut t9: T9<2> = T9::new();
+
+        t9.learn("brique");
+        t9.learn("nokia");
+        t9.learn("the");
+        t9.learn("clanger");
+        t9.learn("nokia");
+        assert_eq!(t9.learned(), &[word("nokia"), word("clanger")]);
+    }
+
+    #[test]
+    fn test_toggle() {
+        block_on(async {
+            let mut keypad = crate::test::TimedKeypad::new(&[
+                (0, KeyEvent::Down(Key::Hash)),
+                (100, KeyEvent::Up(Key::Hash)),
+                (0, KeyEvent::Down(Key::Hash)),
+            ]);
+            let mut t9: T9<4> = T9::new();
+
+            for _ in 0..3 {
+                assert_eq!(t9.event(&mut keypad).await, None);
+            }
+            assert_eq!(t9.event(&mut keypad).await, Some(Event::TogglePredictive));
+        });
+    }
+
+    #[test]
+    fn test_toggle_before() {
+        block_on(async {
+            let mut keypad = crate::test::Keypad::new(&[KeyEvent::Down(Key::Hash)]);
+            let mut t9: T9<4> = T9::new();
+            let blink = Duration::from_millis(500);
+            assert_eq!(
+                t9.event_before(&mut keypad, Instant::now() + blink).await,
+                Some(None)
+            );
+            keypad.pending();
+            // giving up every so often (to blink the cursor, say) doesn't start the hold again
+            for expected in [None, None, Some(Some(Event::TogglePredictive))] {
+                let (event, ()) = join(
+                    t9.event_before(&mut keypad, Instant::now() + blink),
+                    async { embassy_time::MockDriver::get().advance(blink) },
+                )
+                .await;
+                assert_eq!(event, expected);
+            }
+        });
+    }
+}
@@ -0,0 +1,38 @@
+// the trie is generated by build.rs from t9/words.txt. each node has a child for every key from 2
+// to 9 (0 where no word continues that way) and the range of words spelled by the keys so far.
+struct Node {
+    children: [u16; 8],
+    words: core::ops::Range<u16>,
+}
+
+include!(concat!(env!("OUT_DIR"), "/t9.rs"));
+
+fn node(keys: &[u8]) -> Option<&'static Node> {
+    let mut node = &NODES[0];
+    for key in keys {
+        match node.children[*key as usize] {
+            0 => return None,
+            child => node = &NODES[child as usize],
+        }
+    }
+    Some(node)
+}
+
+// every word typed by exactly `keys`, most common first. `None` when no word even starts that way.
+pub fn words(keys: &[u8]) -> Option<&'static [&'static str]> {
+    node(keys).map(|node| &WORDS[node.words.start as usize..node.words.end as usize])
+}
+
+// the first longer word that starts with `keys`, for showing something while a word is unfinished
+pub fn completion(keys: &[u8]) -> Option<&'static str> {
+    fn first(node: &'static Node) -> Option<&'static str> {
+        if !node.words.is_empty() {
+            return Some(WORDS[node.words.start as usize]);
+        }
+        node.children
+            .iter()
+            .filter(|child| **child != 0)
+            .find_map(|child| first(&NODES[*child as usize]))
+    }
+    node(keys).and_then(first)
+}
@@ -1,15 +1,47 @@
+#[cfg(test)]
+extern crate std;
+
+// every test shares the one mock clock, so fixtures that read or move it hold this for as long as
+// they live and tests that use them run one at a time
+#[cfg(test)]
+static CLOCK: core::sync::atomic::AtomicBool = core::sync::atomic::AtomicBool::new(false);
+
+#[cfg(test)]
+pub struct Clock(());
+
+// waits for any other test using the clock to finish, then starts it back at zero
+#[cfg(test)]
+pub fn clock() -> Clock {
+    use core::sync::atomic::Ordering;
+    while CLOCK
+        .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+        .is_err()
+    {
+        std::thread::yield_now();
+    }
+    embassy_time::MockDriver::get().reset();
+    Clock(())
+}
+
+#[cfg(test)]
+impl Drop for Clock {
+    fn drop(&mut self) {
+        CLOCK.store(false, core::sync::atomic::Ordering::Release);
+    }
+}
+
+#[cfg(test)]
 pub struct Keypad<'a>(
     core::slice::Iter<'a, crate::KeyEvent>,
     Option<embassy_time::Instant>,
     bool,
+    Clock,
 );
 
 #[cfg(test)]
 impl<'a> Keypad<'a> {
     pub fn new(events: &'a [crate::KeyEvent]) -> Self {
-        let driver = embassy_time::MockDriver::get();
-        driver.reset();
-        Self(events.iter(), None, false)
+        Self(events.iter(), None, false, clock())
     }
 
     pub fn pending(&mut self) {
@@ -21,6 +53,7 @@ impl<'a> Keypad<'a> {
     }
 }
 
+#[cfg(test)]
 impl<'a> crate::Keypad for Keypad<'a> {
     async fn event(&mut self) -> crate::KeyEvent {
         if self.2 {
@@ -41,6 +74,226 @@ impl<'a> crate::Keypad for Keypad<'a> {
     }
 }
 
+// like `Keypad` but each event is preceded by a delay in milliseconds. the mock clock is advanced
+// by that delay before the event is handed out, so that any timer due in the meantime goes first
+// (the event is kept for next time if waiting for it is given up on), and once the events run out
+// it is advanced far enough to expire any timer that is still waiting. without any events the
+// clock is left alone (and not held), and waiting for one never ends.
+#[cfg(test)]
+pub struct TimedKeypad<'a>(
+    core::slice::Iter<'a, (u64, crate::KeyEvent)>,
+    bool,
+    Option<Clock>,
+    // whether the clock has already been moved on for the next event
+    bool,
+);
+
+#[cfg(test)]
+impl<'a> TimedKeypad<'a> {
+    pub fn new(events: &'a [(u64, crate::KeyEvent)]) -> Self {
+        let clock = (!events.is_empty()).then(clock);
+        Self(events.iter(), false, clock, false)
+    }
+}
+
+#[cfg(test)]
+impl crate::Keypad for TimedKeypad<'_> {
+    async fn event(&mut self) -> crate::KeyEvent {
+        let driver = embassy_time::MockDriver::get();
+        if let Some((delay, event)) = self.0.as_slice().first() {
+            if *delay > 0 && !self.3 {
+                driver.advance(embassy_time::Duration::from_millis(*delay));
+                self.3 = true;
+                embassy_futures::yield_now().await;
+            }
+            self.0.next();
+            self.3 = false;
+            event.clone()
+        } else {
+            if !self.1 && self.2.is_some() {
+                self.1 = true;
+                driver.advance(embassy_time::Duration::from_secs(3600));
+            }
+            core::future::pending().await
+        }
+    }
+
+    fn last_pressed(&mut self) -> Option<embassy_time::Duration> {
+        None
+    }
+}
+
+// a display smaller than the mock's 64x64 so that snapshots of whole widgets stay readable, with
+// keys from a `TimedKeypad`
+#[cfg(test)]
+pub struct Device<'a> {
+    pub display: embedded_graphics::mock_display::MockDisplay<
+        embedded_graphics_core::pixelcolor::BinaryColor,
+    >,
+    size: embedded_graphics_core::geometry::Size,
+    keypad: TimedKeypad<'a>,
+    pub timestamp: i64,
+    pub backlight: bool,
+    // percent, as last set
+    pub brightness: u8,
+    pub powered_down: bool,
+    // what the buzzer is sounding, if it is
+    pub frequency: Option<u16>,
+    pub vibrating: bool,
+    // percent, as last set
+    pub intensity: u8,
+}
+
+#[cfg(test)]
+impl<'a> Device<'a> {
+    pub fn new(width: u32, height: u32, events: &'a [(u64, crate::KeyEvent)]) -> Self {
+        let mut display = embedded_graphics::mock_display::MockDisplay::new();
+        display.set_allow_overdraw(true);
+        Self {
+            display,
+            size: embedded_graphics_core::geometry::Size::new(width, height),
+            keypad: TimedKeypad::new(events),
+            timestamp: 0,
+            backlight: true,
+            brightness: 100,
+            powered_down: false,
+            frequency: None,
+            vibrating: false,
+            intensity: 100,
+        }
+    }
+}
+
+#[cfg(test)]
+impl crate::Keypad for Device<'_> {
+    async fn event(&mut self) -> crate::KeyEvent {
+        self.keypad.event().await
+    }
+
+    fn last_pressed(&mut self) -> Option<embassy_time::Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+impl crate::Rtc for Device<'_> {
+    type Error = ();
+
+    fn timestamp(&mut self) -> Result<i64, Self::Error> {
+        Ok(self.timestamp)
+    }
+
+    fn set_timestamp(&mut self, time: i64) {
+        self.timestamp = time;
+    }
+}
+
+#[cfg(test)]
+impl crate::Backlight for Device<'_> {
+    fn on(&mut self) {
+        self.backlight = true;
+    }
+
+    fn off(&mut self) {
+        self.backlight = false;
+    }
+
+    fn set_brightness(&mut self, level: u8) {
+        self.brightness = level;
+        self.backlight = level > 0;
+    }
+}
+
+#[cfg(test)]
+impl crate::Buzzer for Device<'_> {
+    type Error = ();
+
+    fn set_frequency(&mut self, frequency: u16) -> Result<(), Self::Error> {
+        self.frequency = Some(frequency);
+        Ok(())
+    }
+
+    fn set_volume(&mut self, _volume: u8) {}
+
+    fn mute_buzzer(&mut self) -> Result<(), Self::Error> {
+        self.frequency = None;
+        Ok(())
+    }
+
+    fn unmute_buzzer(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl crate::VibrationMotor for Device<'_> {
+    fn start_vibrating(&mut self) {
+        self.vibrating = true;
+    }
+
+    fn stop_vibrating(&mut self) {
+        self.vibrating = false;
+    }
+
+    fn set_intensity(&mut self, level: u8) {
+        self.intensity = level;
+    }
+}
+
+#[cfg(test)]
+impl crate::DisplayPower for Device<'_> {
+    fn power_down(&mut self) {
+        self.powered_down = true;
+    }
+
+    fn power_up(&mut self) {
+        self.powered_down = false;
+    }
+}
+
+#[cfg(test)]
+impl crate::Indicators for Device<'_> {}
+
+#[cfg(test)]
+impl crate::Storage for Device<'_> {}
+
+#[cfg(test)]
+impl embedded_graphics_core::geometry::OriginDimensions for Device<'_> {
+    fn size(&self) -> embedded_graphics_core::geometry::Size {
+        self.size
+    }
+}
+
+#[cfg(test)]
+impl embedded_graphics_core::draw_target::DrawTarget for Device<'_> {
+    type Color = embedded_graphics_core::pixelcolor::BinaryColor;
+    // as `crate::Device` wants, so that the system can run over this
+    type Error = ();
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = embedded_graphics_core::Pixel<Self::Color>>,
+    {
+        use embedded_graphics_core::geometry::Dimensions;
+
+        let area = self.bounding_box();
+        let Ok(()) = self.display.draw_iter(
+            pixels
+                .into_iter()
+                .filter(|embedded_graphics_core::Pixel(p, _)| area.contains(*p)),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl crate::PowerButton for Device<'_> {
+    fn clear(&mut self) {}
+}
+
+#[cfg(test)]
+impl crate::Device for Device<'_> {}
+
 #[cfg(test)]
 mod test {
     use futures_executor::block_on;
@@ -72,4 +325,32 @@ mod test {
             );
         });
     }
+
+    #[test]
+    fn test_timed_event() {
+        block_on(async {
+            let mut keypad = super::Keypad::new(&[
+                crate::KeyEvent::Down(crate::Key::Two),
+                crate::KeyEvent::Up(crate::Key::Two),
+            ]);
+
+            let start = embassy_time::Instant::now();
+            assert_eq!(
+                keypad.timed_event().await,
+                crate::TimedKeyEvent {
+                    event: crate::KeyEvent::Down(crate::Key::Two),
+                    at: start,
+                }
+            );
+            keypad.advance_by_millis(250);
+            assert_eq!(
+                keypad.timed_event().await,
+                crate::TimedKeyEvent {
+                    event: crate::KeyEvent::Up(crate::Key::Two),
+                    at: start + embassy_time::Duration::from_millis(250),
+                }
+            );
+            assert_eq!(keypad.bounce_count(&crate::Key::Two), None);
+        });
+    }
 }
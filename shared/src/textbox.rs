@@ -1,102 +1,417 @@
+use core::ops::Range;
+
 use embedded_graphics::{
     draw_target::DrawTarget,
-    mono_font::{MonoTextStyle, ascii::FONT_6X9},
+    mono_font::{MonoFont, MonoTextStyle},
     pixelcolor::BinaryColor,
     prelude::*,
-    primitives::{PrimitiveStyle, Rectangle},
+    primitives::{Line, PrimitiveStyle, Rectangle},
     text::{
-        Text,
+        Baseline, Text,
         renderer::{CharacterStyle, TextRenderer},
     },
 };
 
-pub struct Textbox<'a, A> {
+fn advance<S: TextRenderer>(style: &S, text: &str) -> u32 {
+    style
+        .measure_string(text, Point::zero(), Baseline::Top)
+        .next_position
+        .x
+        .max(0) as u32
+}
+
+// a character and where it goes, counting rows from the top of the text rather than the screen
+struct Placed {
+    bytes: Range<usize>,
+    x: u32,
+    row: u32,
+    width: u32,
+}
+
+// where a row begins and how the layout stood just before it, enough to lay out the text from
+// there without going over the rows above again
+#[derive(Clone, Copy)]
+struct RowStart {
+    index: usize,
+    byte: usize,
+    after_space: bool,
+}
+
+// text with more rows than this picks up from the last row kept
+const ROWS: usize = 32;
+
+struct Rows {
+    width: u32,
+    starts: heapless::Vec<RowStart, ROWS>,
+}
+
+// wraps before any word that would not fit on the rest of the row. words longer than a whole row
+// are split wherever the row runs out and spaces are left hanging off the end of a row rather than
+// starting the next one.
+struct Layout<'t, S> {
+    text: &'t str,
+    style: &'t S,
+    width: u32,
+    chars: core::str::CharIndices<'t>,
+    // where in `text` the characters start
+    base: usize,
+    x: u32,
+    row: u32,
+    after_space: bool,
+}
+
+impl<'t, S: TextRenderer> Layout<'t, S> {
+    fn new(text: &'t str, style: &'t S, width: u32) -> Self {
+        Self {
+            text,
+            style,
+            width,
+            chars: text.char_indices(),
+            base: 0,
+            x: 0,
+            row: 0,
+            after_space: true,
+        }
+    }
+
+    fn resume(text: &'t str, style: &'t S, width: u32, start: RowStart, row: u32) -> Self {
+        Self {
+            chars: text[start.byte..].char_indices(),
+            base: start.byte,
+            row,
+            after_space: start.after_space,
+            ..Self::new(text, style, width)
+        }
+    }
+
+    fn row_starts(mut self) -> heapless::Vec<RowStart, ROWS> {
+        let mut starts = heapless::Vec::new();
+        let mut index = 0;
+        loop {
+            let after_space = self.after_space;
+            let Some(placed) = self.next() else {
+                return starts;
+            };
+            if placed.x == 0 && placed.row as usize == starts.len() {
+                let start = RowStart {
+                    index,
+                    byte: placed.bytes.start,
+                    after_space,
+                };
+                if starts.push(start).is_err() {
+                    return starts;
+                }
+            }
+            index += 1;
+        }
+    }
+
+    fn wrap(&mut self) {
+        self.x = 0;
+        self.row += 1;
+    }
+
+    // where a character after the last one would go
+    fn end(mut self) -> (u32, u32) {
+        for _ in self.by_ref() {}
+        if self.x >= self.width {
+            (0, self.row + 1)
+        } else {
+            (self.x, self.row)
+        }
+    }
+}
+
+impl<S: TextRenderer> Iterator for Layout<'_, S> {
+    type Item = Placed;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, character) = self.chars.next()?;
+        let start = self.base + start;
+        let bytes = start..start + character.len_utf8();
+        if character == '\n' {
+            let placed = Placed {
+                bytes,
+                x: self.x,
+                row: self.row,
+                width: 0,
+            };
+            self.wrap();
+            self.after_space = true;
+            return Some(placed);
+        }
+
+        let width = advance(self.style, &self.text[bytes.clone()]);
+        if character == ' ' {
+            self.after_space = true;
+        } else {
+            if self.after_space {
+                let word = self.text[start..].split([' ', '\n']).next().unwrap_or("");
+                if self.x > 0 && self.x + advance(self.style, word) > self.width {
+                    self.wrap();
+                }
+            }
+            if self.x > 0 && self.x + width > self.width {
+                self.wrap();
+            }
+            self.after_space = false;
+        }
+        let placed = Placed {
+            bytes,
+            x: self.x,
+            row: self.row,
+            width,
+        };
+        self.x += width;
+        Some(placed)
+    }
+}
+
+// `S` can be any text style, proportional fonts included. it is drawn in the colors of the theme
+// at the time the textbox was made
+pub struct Textbox<A, S = MonoTextStyle<'static, BinaryColor>> {
     buffer: A,
-    mono_text_style: MonoTextStyle<'a, BinaryColor>,
-    character_bounding_box: Rectangle,
+    style: S,
+    foreground: BinaryColor,
+    background: BinaryColor,
+    line_height: u32,
     row_index: usize,
-    highlighted_range: Option<core::ops::Range<usize>>,
+    highlighted_range: Option<Range<usize>>,
+    // `None` keeps the cursor after the last character however long the buffer gets
+    cursor: Option<usize>,
+    cursor_shown: bool,
+    // cleared whenever the text or the font changes
+    rows: Option<Rows>,
 }
 
-impl<'a, A> Textbox<'a, A> {
+impl<A> Textbox<A> {
     pub fn new(buffer: A) -> Self {
-        let mono_text_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::Off);
+        Self::with_style(buffer, crate::theme::current().text_style())
+    }
 
+    pub fn set_font(&mut self, font: &'static MonoFont<'static>) {
+        self.style = MonoTextStyle::new(font, self.foreground);
+        self.line_height = self.style.line_height();
+        self.rows = None;
+    }
+}
+
+impl<A, S> Textbox<A, S>
+where
+    S: TextRenderer<Color = BinaryColor> + CharacterStyle<Color = BinaryColor> + Clone,
+{
+    pub fn with_style(buffer: A, mut style: S) -> Self {
+        let theme = crate::theme::current();
+        style.set_text_color(Some(theme.foreground));
         Self {
             buffer,
-            character_bounding_box: mono_text_style
-                .measure_string("A", Point::zero(), embedded_graphics::text::Baseline::Top)
-                .bounding_box,
-            mono_text_style,
+            line_height: style.line_height(),
+            style,
+            foreground: theme.foreground,
+            background: theme.background,
             row_index: 0,
             highlighted_range: None,
+            cursor: None,
+            cursor_shown: false,
+            rows: None,
         }
     }
 
-    fn erase_index<D>(&mut self, device: &mut D, index: usize)
+    fn point(&self, x: u32, row: u32) -> Point {
+        Point::new(
+            x as i32,
+            (row as i32 - self.row_index as i32) * self.line_height as i32,
+        )
+    }
+
+    #[allow(dead_code)]
+    fn scroll_up(&mut self) {
+        self.row_index = (self.row_index - 1).max(0)
+    }
+}
+
+// indices are counted in characters rather than bytes so any letter a font has a glyph for works
+impl<A, S> Textbox<A, S>
+where
+    A: core::ops::Deref<Target = str>,
+    S: TextRenderer<Color = BinaryColor> + CharacterStyle<Color = BinaryColor> + Clone,
+{
+    fn len(&self) -> usize {
+        self.buffer.chars().count()
+    }
+
+    fn byte_index(&self, index: usize) -> usize {
+        self.buffer
+            .char_indices()
+            .nth(index)
+            .map_or(self.buffer.len(), |(byte_index, _)| byte_index)
+    }
+
+    fn layout(&self, width: u32) -> Layout<'_, S> {
+        Layout::new(&self.buffer, &self.style, width)
+    }
+
+    fn update_rows(&mut self, width: u32) {
+        if self.rows.as_ref().is_none_or(|rows| rows.width != width) {
+            let starts = self.layout(width).row_starts();
+            self.rows = Some(Rows { width, starts });
+        }
+    }
+
+    // laid out from the start of the row that `index` is on, along with how far into the row it
+    // is. without `update_rows` for `width` first, this lays out everything before it
+    fn layout_at(&self, width: u32, index: usize) -> (Layout<'_, S>, usize) {
+        let starts = self
+            .rows
+            .as_ref()
+            .filter(|rows| rows.width == width)
+            .map_or(&[][..], |rows| &rows.starts);
+        let row = starts
+            .partition_point(|start| start.index <= index)
+            .saturating_sub(1);
+        match starts.get(row).copied() {
+            Some(start) => (
+                Layout::resume(&self.buffer, &self.style, width, start, row as u32),
+                index - start.index,
+            ),
+            None => (self.layout(width), index),
+        }
+    }
+
+    // on screen, along with the width of the character there
+    fn position<D>(&mut self, device: &D, index: usize) -> (Point, u32)
     where
         D: DrawTarget<Color = BinaryColor>,
     {
-        let _ = Rectangle::new(
-            self.position_from_index(device, index),
-            self.character_bounding_box.size,
-        )
-        .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
-        .draw(device);
+        let width = device.bounding_box().size.width;
+        self.update_rows(width);
+        let (mut layout, index) = self.layout_at(width, index);
+        match layout.by_ref().nth(index) {
+            Some(placed) => (self.point(placed.x, placed.row), placed.width),
+            None => {
+                let (x, row) = layout.end();
+                (self.point(x, row), 0)
+            }
+        }
     }
 
-    fn characters_per_row<D>(&self, device: &mut D) -> u32
+    fn row<D>(&mut self, device: &D, index: usize) -> usize
     where
         D: DrawTarget<Color = BinaryColor>,
     {
-        device.bounding_box().size.width / self.character_bounding_box.size.width
+        let width = device.bounding_box().size.width;
+        self.update_rows(width);
+        let (mut layout, index) = self.layout_at(width, index);
+        match layout.by_ref().nth(index) {
+            Some(placed) => placed.row as usize,
+            None => layout.end().1 as usize,
+        }
     }
 
-    fn row<D>(&self, device: &mut D, index: usize) -> usize
+    fn scroll_to<D>(&mut self, device: &mut D, index: usize) -> bool
     where
         D: DrawTarget<Color = BinaryColor>,
     {
-        index / self.characters_per_row(device) as usize
+        let row_index = self.row(device, index);
+        if row_index == self.row_index {
+            false
+        } else {
+            self.row_index = row_index;
+            true
+        }
     }
 
-    fn position_from_index<D>(&self, device: &mut D, index: usize) -> Point
+    #[cfg_attr(not(test), expect(dead_code))]
+    fn erase_index<D>(&mut self, device: &mut D, index: usize)
     where
         D: DrawTarget<Color = BinaryColor>,
     {
-        let y = self.row(device, index) as u32 * self.character_bounding_box.size.height;
-        let col = index as u32 % self.characters_per_row(device);
-        let x = col * self.character_bounding_box.size.width;
+        let (position, width) = self.position(device, index);
+        let _ = Rectangle::new(position, Size::new(width, self.line_height))
+            .into_styled(PrimitiveStyle::with_fill(self.background))
+            .draw(device);
+    }
 
-        Point::new(
-            x.try_into().unwrap(),
-            y as i32 - self.character_bounding_box.size.height as i32 * self.row_index as i32,
-        )
+    pub fn cursor(&self) -> usize {
+        self.cursor.unwrap_or(self.len())
     }
 
-    #[allow(dead_code)]
-    fn scroll_up(&mut self) {
-        self.row_index = (self.row_index - 1).max(0)
+    // how tall all of the text is once wrapped to `width`
+    pub fn height(&self, width: u32) -> u32 {
+        let (x, row) = self.layout(width).end();
+        let rows = if x == 0 { row } else { row + 1 };
+        rows * self.line_height
     }
 
-    fn scroll_to<D>(&mut self, device: &mut D, index: usize) -> bool
+    fn follow_cursor<D>(&mut self, device: &mut D)
     where
         D: DrawTarget<Color = BinaryColor>,
     {
-        let row_index = index / self.characters_per_row(device) as usize;
-        if row_index == self.row_index {
-            false
+        if self.scroll_to(device, self.cursor()) {
+            let _ = device.clear(self.background);
+            self.draw(device, None, false);
+        }
+    }
+
+    // a line down the left edge of the character after the cursor
+    fn draw_cursor<D>(&mut self, device: &mut D, shown: bool)
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let index = self.cursor();
+        if shown {
+            let (top, _) = self.position(device, index);
+            let bottom = top + Point::new(0, self.line_height as i32 - 1);
+            let _ = Line::new(top, bottom)
+                .into_styled(PrimitiveStyle::with_stroke(self.foreground, 1))
+                .draw(device);
+        } else if index < self.len() {
+            self.draw(device, Some(index..index + 1), false);
         } else {
-            self.row_index = row_index;
-            true
+            let (top, _) = self.position(device, index);
+            let _ = Rectangle::new(top, Size::new(1, self.line_height))
+                .into_styled(PrimitiveStyle::with_fill(self.background))
+                .draw(device);
         }
+        self.cursor_shown = shown;
     }
-}
 
-impl<'a, A> Textbox<'a, A>
-where
-    A: core::ops::Deref<Target = str>,
-{
-    pub fn highlight<D>(&mut self, device: &mut D, range: core::ops::Range<usize>)
+    pub fn blink<D>(&mut self, device: &mut D)
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        self.draw_cursor(device, !self.cursor_shown);
+    }
+
+    fn move_cursor<D>(&mut self, device: &mut D, cursor: usize)
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        self.draw_cursor(device, false);
+        self.cursor = if cursor >= self.len() {
+            None
+        } else {
+            Some(cursor)
+        };
+        self.follow_cursor(device);
+        self.draw_cursor(device, true);
+    }
+
+    pub fn cursor_left<D>(&mut self, device: &mut D)
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        self.move_cursor(device, self.cursor().saturating_sub(1));
+    }
+
+    pub fn cursor_right<D>(&mut self, device: &mut D)
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        self.move_cursor(device, self.cursor() + 1);
+    }
+
+    pub fn highlight<D>(&mut self, device: &mut D, range: Range<usize>)
     where
         D: DrawTarget<Color = BinaryColor>,
     {
@@ -105,38 +420,36 @@ where
         }
         self.highlighted_range = Some(range.clone());
         if self.scroll_to(device, range.end) {
-            let _ = device.clear(BinaryColor::On);
+            let _ = device.clear(self.background);
             self.draw(device, None, false);
         }
         self.draw(device, self.highlighted_range.clone(), true);
     }
 
-    fn draw_character_at_index<D>(&mut self, device: &mut D, index: usize, invert: bool)
+    fn draw_placed<D>(&self, device: &mut D, placed: &Placed, invert: bool)
     where
         D: DrawTarget<Color = BinaryColor>,
     {
-        let _ = Rectangle::new(
-            self.position_from_index(device, index),
-            self.character_bounding_box.size,
-        )
-        .into_styled(if invert {
-            PrimitiveStyle::with_fill(BinaryColor::Off)
-        } else {
-            PrimitiveStyle::with_fill(BinaryColor::On)
-        })
-        .draw(device);
+        let position = self.point(placed.x, placed.row);
+        let _ = Rectangle::new(position, Size::new(placed.width, self.line_height))
+            .into_styled(if invert {
+                PrimitiveStyle::with_fill(self.foreground)
+            } else {
+                PrimitiveStyle::with_fill(self.background)
+            })
+            .draw(device);
 
-        let mut mono_text_style = self.mono_text_style;
+        let mut style = self.style.clone();
         if invert {
-            mono_text_style.set_background_color(Some(BinaryColor::Off));
-            mono_text_style.set_text_color(Some(BinaryColor::On));
+            style.set_background_color(Some(self.foreground));
+            style.set_text_color(Some(self.background));
         }
 
         let _ = Text::with_baseline(
-            self.buffer.get(index..(index + 1)).unwrap(),
-            self.position_from_index(device, index),
-            mono_text_style,
-            embedded_graphics::text::Baseline::Top,
+            &self.buffer[placed.bytes.clone()],
+            position,
+            style,
+            Baseline::Top,
         )
         .draw(device);
     }
@@ -155,23 +468,16 @@ where
     where
         D: DrawTarget<Color = BinaryColor>,
     {
-        let n_rows_on_screen =
-            device.bounding_box().size.width / self.character_bounding_box.size.height - 1;
+        let n_rows_on_screen = device.bounding_box().size.width / self.line_height - 1;
         let rectangle = Rectangle::new(
-            Point::new(
-                0,
-                (self.character_bounding_box.size.height * n_rows_on_screen)
-                    .try_into()
-                    .unwrap(),
-            ),
+            Point::new(0, (self.line_height * n_rows_on_screen).try_into().unwrap()),
             Size::new(
                 device.bounding_box().size.width,
-                device.bounding_box().size.height
-                    - (self.character_bounding_box.size.height * n_rows_on_screen),
+                device.bounding_box().size.height - (self.line_height * n_rows_on_screen),
             ),
         );
         let _ = rectangle
-            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .into_styled(PrimitiveStyle::with_fill(self.background))
             .draw(device);
     }
 
@@ -180,33 +486,115 @@ where
     where
         D: DrawTarget<Color = BinaryColor>,
     {
-        let n_rows_on_screen =
-            device.bounding_box().size.width / self.character_bounding_box.size.height;
-        let n_rows_in_buffer = self
-            .buffer
-            .len()
-            .div_ceil(self.characters_per_row(device).try_into().unwrap());
-        n_rows_in_buffer.saturating_sub(n_rows_on_screen as usize)
+        let n_rows_on_screen = device.bounding_box().size.width / self.line_height;
+        let width = device.bounding_box().size.width;
+        self.update_rows(width);
+        let (layout, _) = self.layout_at(width, usize::MAX);
+        let (x, row) = layout.end();
+        let n_rows_in_buffer = if x == 0 { row } else { row + 1 };
+        (n_rows_in_buffer as usize).saturating_sub(n_rows_on_screen as usize)
     }
 
-    pub fn draw<D>(&mut self, device: &mut D, range: Option<core::ops::Range<usize>>, invert: bool)
+    pub fn draw<D>(&mut self, device: &mut D, range: Option<Range<usize>>, invert: bool)
     where
         D: DrawTarget<Color = BinaryColor>,
     {
-        for i in range.unwrap_or(0..self.buffer.len()) {
-            self.draw_character_at_index(device, i, invert);
+        let range = range.unwrap_or(0..usize::MAX);
+        let width = device.bounding_box().size.width;
+        self.update_rows(width);
+        let (layout, skip) = self.layout_at(width, range.start);
+        for placed in layout.skip(skip).take(range.len()) {
+            self.draw_placed(device, &placed, invert);
         }
     }
+
+    // where the word that `index` is part of starts
+    fn word_start(&self, index: usize) -> usize {
+        match self.buffer[..self.byte_index(index)].rfind([' ', '\n']) {
+            Some(byte_index) => self.buffer[..=byte_index].chars().count(),
+            None => 0,
+        }
+    }
+
+    // blanks everything after `position`, to the end of its row and in all of the rows below
+    fn clear_from<D>(&self, device: &mut D, position: Point)
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let size = device.bounding_box().size;
+        let _ = Rectangle::new(
+            position,
+            Size::new(
+                size.width.saturating_sub(position.x as u32),
+                self.line_height,
+            ),
+        )
+        .into_styled(PrimitiveStyle::with_fill(self.background))
+        .draw(device);
+        let _ = Rectangle::new(
+            Point::new(0, position.y + self.line_height as i32),
+            Size::new(size.width, size.height),
+        )
+        .into_styled(PrimitiveStyle::with_fill(self.background))
+        .draw(device);
+    }
 }
 
-impl<'a, const N: usize> Textbox<'a, heapless::String<N>> {
+impl<const N: usize, S> Textbox<heapless::String<N>, S>
+where
+    S: TextRenderer<Color = BinaryColor> + CharacterStyle<Color = BinaryColor> + Clone,
+{
+    // an edit at `index` can move its word onto another row and change how everything after it
+    // wraps, so that is all redrawn. `tentative` is drawn inverted.
+    fn edit<D>(
+        &mut self,
+        device: &mut D,
+        index: usize,
+        tentative: Option<usize>,
+        edit: impl FnOnce(&mut heapless::String<N>) -> bool,
+    ) where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        self.cursor_shown = false;
+        let start = self.word_start(index);
+        let (start_before, _) = self.position(device, start);
+        let (index_before, _) = self.position(device, index);
+        if !edit(&mut self.buffer) {
+            return;
+        }
+        self.rows = None;
+        let from = if self.position(device, start).0 == start_before {
+            self.clear_from(device, index_before);
+            index
+        } else {
+            self.clear_from(device, start_before);
+            start
+        };
+        self.draw(device, Some(from..self.len()), false);
+        if let Some(tentative) = tentative {
+            self.draw(device, Some(tentative..tentative + 1), true);
+        }
+    }
+
     pub fn backspace<D>(&mut self, device: &mut D)
     where
         D: DrawTarget<Color = BinaryColor>,
     {
-        if !self.buffer.is_empty() {
-            self.erase_index(device, self.buffer.len() - 1);
-            let _ = self.buffer.pop();
+        match self.cursor {
+            None if self.buffer.is_empty() => {}
+            None => self.edit(device, self.len() - 1, None, |buffer| {
+                buffer.pop().is_some()
+            }),
+            Some(0) => {}
+            Some(cursor) => {
+                let byte_index = self.byte_index(cursor - 1);
+                self.edit(device, cursor - 1, None, |buffer| {
+                    buffer.remove(byte_index);
+                    true
+                });
+                self.cursor = Some(cursor - 1);
+                self.follow_cursor(device);
+            }
         }
     }
 
@@ -214,9 +602,34 @@ impl<'a, const N: usize> Textbox<'a, heapless::String<N>> {
     where
         D: DrawTarget<Color = BinaryColor>,
     {
-        if self.buffer.len() < self.buffer.capacity() {
-            let _ = self.buffer.push(character);
-            self.draw_character_at_index(device, self.buffer.len() - 1, invert);
+        match self.cursor {
+            None => {
+                let index = self.len();
+                self.edit(device, index, invert.then_some(index), |buffer| {
+                    let pushed = buffer.push(character).is_ok();
+                    if !pushed {
+                        crate::tones::warn();
+                    }
+                    pushed
+                });
+            }
+            Some(cursor) => {
+                if self.buffer.len() + character.len_utf8() > self.buffer.capacity() {
+                    crate::tones::warn();
+                    return;
+                }
+                let byte_index = self.byte_index(cursor);
+                self.edit(device, cursor, invert.then_some(cursor), |buffer| {
+                    let mut tail = heapless::String::<N>::new();
+                    let _ = tail.push_str(&buffer[byte_index..]);
+                    buffer.truncate(byte_index);
+                    let _ = buffer.push(character);
+                    let _ = buffer.push_str(&tail);
+                    true
+                });
+                self.cursor = Some(cursor + 1);
+                self.follow_cursor(device);
+            }
         }
     }
 
@@ -230,9 +643,21 @@ mod test {
     extern crate std;
     use core::str::FromStr;
 
-    use embedded_graphics::{Pixel, mock_display::MockDisplay};
+    use embedded_graphics::{
+        Drawable, Pixel,
+        mock_display::MockDisplay,
+        mono_font::ascii::FONT_6X9,
+        prelude::{Point, Size},
+        primitives::{PrimitiveStyle, Rectangle, StyledDrawable},
+        text::{
+            Baseline,
+            renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        },
+    };
     use embedded_graphics_core::{draw_target::DrawTarget, pixelcolor::BinaryColor};
 
+    // the snapshots are drawn in the smaller `FONT_6X9` rather than the theme's font, so that more
+    // fits on the mock display
     pub struct Device {
         pub display: MockDisplay<BinaryColor>,
     }
@@ -282,6 +707,7 @@ mod test {
         let mut device = Device::new();
         let _ = device.clear(BinaryColor::On);
         let mut textbox = super::Textbox::new(characters);
+        textbox.set_font(&FONT_6X9);
         textbox.draw(&mut device, None, false);
         textbox.erase_index(&mut device, 1);
         textbox.push(&mut device, 'G', false);
@@ -370,6 +796,7 @@ mod test {
         let mut device = Device::new();
         let _ = device.clear(BinaryColor::On);
         let mut textbox = super::Textbox::new(characters);
+        textbox.set_font(&FONT_6X9);
         textbox.draw(&mut device, None, false);
         textbox.draw(&mut device, Some(4..7), true);
 
@@ -452,6 +879,7 @@ mod test {
         let mut textbox = super::Textbox::new(
             "AAAAAAAAAABBBBBBBBBBCCCCCCCCCCDDDDDDDDDDEEEEEEEEEEFFFFFFFFFFGGGGGGGGGGHH",
         );
+        textbox.set_font(&FONT_6X9);
         textbox.scroll_down(&mut device);
         textbox.draw(&mut device, None, false);
 
@@ -523,6 +951,282 @@ mod test {
         ]);
     }
 
+    #[test]
+    fn test_non_ascii() {
+        let font = &embedded_graphics::mono_font::iso_8859_1::FONT_6X9;
+        let mut device = Device::new();
+        let _ = device.clear(BinaryColor::On);
+        let mut textbox = super::Textbox::new(heapless::String::<4>::new());
+        textbox.set_font(font);
+        textbox.push(&mut device, 'é', false);
+        textbox.push(&mut device, 'ß', false);
+        // no room left for another two bytes
+        textbox.push(&mut device, 'ü', false);
+        textbox.backspace(&mut device);
+        textbox.draw(&mut device, None, false);
+
+        let mut expected = Device::new();
+        let _ = expected.clear(BinaryColor::On);
+        let _ = embedded_graphics::text::Text::with_baseline(
+            "é",
+            embedded_graphics::prelude::Point::zero(),
+            embedded_graphics::mono_font::MonoTextStyle::new(font, BinaryColor::Off),
+            embedded_graphics::text::Baseline::Top,
+        )
+        .draw(&mut expected);
+
+        assert_eq!(device.display, expected.display);
+        assert_eq!(textbox.release(), "é");
+    }
+
+    #[test]
+    fn test_edit_in_middle() {
+        let mut device = Device::new();
+        let _ = device.clear(BinaryColor::On);
+        let mut textbox = super::Textbox::new(heapless::String::<15>::from_str("abcd").unwrap());
+        textbox.draw(&mut device, None, false);
+
+        textbox.cursor_left(&mut device);
+        textbox.cursor_left(&mut device);
+        assert_eq!(textbox.cursor(), 2);
+        textbox.push(&mut device, 'X', false);
+        textbox.push(&mut device, 'Y', false);
+        textbox.backspace(&mut device);
+        textbox.cursor_left(&mut device);
+        textbox.cursor_left(&mut device);
+        textbox.backspace(&mut device);
+        assert_eq!(textbox.cursor(), 0);
+        // nothing before the cursor to delete
+        textbox.backspace(&mut device);
+
+        let mut expected = Device::new();
+        let _ = expected.clear(BinaryColor::On);
+        super::Textbox::new(heapless::String::<15>::from_str("bXcd").unwrap()).draw(
+            &mut expected,
+            None,
+            false,
+        );
+        assert_eq!(device.display, expected.display);
+        assert_eq!(textbox.release(), "bXcd");
+    }
+
+    #[test]
+    fn test_cursor_blink() {
+        let mut device = Device::new();
+        let _ = device.clear(BinaryColor::On);
+        let mut textbox = super::Textbox::new(heapless::String::<15>::from_str("ab").unwrap());
+        textbox.draw(&mut device, None, false);
+
+        // the cursor runs the full height of the character cell, taller than the stem of the b
+        textbox.cursor_left(&mut device);
+        assert_eq!(textbox.cursor(), 1);
+        for y in 0..9 {
+            assert_eq!(
+                device.display.get_pixel(Point::new(6, y)),
+                Some(BinaryColor::Off)
+            );
+        }
+        textbox.blink(&mut device);
+        assert_eq!(
+            device.display.get_pixel(Point::new(6, 0)),
+            Some(BinaryColor::On)
+        );
+        assert_eq!(
+            device.display.get_pixel(Point::new(6, 8)),
+            Some(BinaryColor::On)
+        );
+        textbox.blink(&mut device);
+        assert_eq!(
+            device.display.get_pixel(Point::new(6, 8)),
+            Some(BinaryColor::Off)
+        );
+    }
+
+    #[test]
+    fn test_scroll_follows_cursor() {
+        let mut device = Device::new();
+        let _ = device.clear(BinaryColor::On);
+        let mut textbox = super::Textbox::new(
+            heapless::String::<72>::from_str(
+                "AAAAAAAAAABBBBBBBBBBCCCCCCCCCCDDDDDDDDDDEEEEEEEEEEFFFFFFFFFFGGGGGGGGGG",
+            )
+            .unwrap(),
+        );
+        textbox.row_index = 6;
+        textbox.draw(&mut device, None, false);
+
+        for _ in 0..10 {
+            textbox.cursor_left(&mut device);
+        }
+        assert_eq!(textbox.row_index, 6);
+        textbox.cursor_left(&mut device);
+        assert_eq!(textbox.row_index, 5);
+        for _ in 0..59 {
+            textbox.cursor_left(&mut device);
+        }
+        assert_eq!(textbox.cursor(), 0);
+        assert_eq!(textbox.row_index, 0);
+        textbox.push(&mut device, 'H', false);
+        assert_eq!(textbox.cursor(), 1);
+        assert_eq!(
+            textbox.release().as_str(),
+            "HAAAAAAAAAABBBBBBBBBBCCCCCCCCCCDDDDDDDDDDEEEEEEEEEEFFFFFFFFFFGGGGGGGGGG"
+        );
+    }
+
+    #[test]
+    fn test_row_starts() {
+        let device = Device::new();
+        // wrapped words, a word split across rows, blank rows and a trailing newline
+        let mut textbox = super::Textbox::new("hello there world\n\nsupercalifragilistic end\n");
+        textbox.set_font(&FONT_6X9);
+        let layout = super::Layout::new(textbox.buffer, &textbox.style, 64);
+        let placed: std::vec::Vec<_> = layout.map(|placed| (placed.x, placed.row)).collect();
+        for (index, (x, row)) in placed.iter().enumerate() {
+            assert_eq!(textbox.position(&device, index).0, textbox.point(*x, *row));
+            assert_eq!(textbox.row(&device, index), *row as usize);
+        }
+        let (x, row) = super::Layout::new(textbox.buffer, &textbox.style, 64).end();
+        assert_eq!(
+            textbox.position(&device, placed.len()).0,
+            textbox.point(x, row)
+        );
+        assert_eq!(textbox.rows.as_ref().map(|rows| rows.starts.len()), Some(7));
+    }
+
+    #[test]
+    fn test_word_wrap() {
+        let mut device = Device::new();
+        let _ = device.clear(BinaryColor::On);
+        let mut textbox = super::Textbox::new("hello there world\nend");
+        textbox.set_font(&FONT_6X9);
+
+        assert_eq!(textbox.position(&device, 5).0, Point::new(30, 0));
+        assert_eq!(textbox.position(&device, 6).0, Point::new(0, 9));
+        assert_eq!(textbox.position(&device, 12).0, Point::new(0, 18));
+        assert_eq!(textbox.position(&device, 18).0, Point::new(0, 27));
+
+        textbox.draw(&mut device, None, false);
+        textbox.draw(&mut device, Some(12..17), true);
+        assert_eq!(
+            device.display.get_pixel(Point::new(0, 18)),
+            Some(BinaryColor::Off)
+        );
+        assert_eq!(
+            device.display.get_pixel(Point::new(36, 9)),
+            Some(BinaryColor::On)
+        );
+    }
+
+    // every character is a solid block, narrow for i and l
+    #[derive(Clone)]
+    struct Blocks {
+        text_color: Option<BinaryColor>,
+        background_color: Option<BinaryColor>,
+    }
+
+    impl Blocks {
+        fn width(c: char) -> u32 {
+            if c == 'i' || c == 'l' { 2 } else { 6 }
+        }
+    }
+
+    impl CharacterStyle for Blocks {
+        type Color = BinaryColor;
+
+        fn set_text_color(&mut self, text_color: Option<BinaryColor>) {
+            self.text_color = text_color;
+        }
+
+        fn set_background_color(&mut self, background_color: Option<BinaryColor>) {
+            self.background_color = background_color;
+        }
+    }
+
+    impl TextRenderer for Blocks {
+        type Color = BinaryColor;
+
+        fn draw_string<D: DrawTarget<Color = BinaryColor>>(
+            &self,
+            text: &str,
+            mut position: Point,
+            _baseline: Baseline,
+            target: &mut D,
+        ) -> Result<Point, D::Error> {
+            for c in text.chars() {
+                let width = Self::width(c);
+                if let Some(color) = self.background_color {
+                    Rectangle::new(position, Size::new(width, 9))
+                        .draw_styled(&PrimitiveStyle::with_fill(color), target)?;
+                }
+                if let (Some(color), false) = (self.text_color, c == ' ') {
+                    Rectangle::new(position + Point::new(0, 1), Size::new(width - 1, 7))
+                        .draw_styled(&PrimitiveStyle::with_fill(color), target)?;
+                }
+                position.x += width as i32;
+            }
+            Ok(position)
+        }
+
+        fn draw_whitespace<D: DrawTarget<Color = BinaryColor>>(
+            &self,
+            width: u32,
+            position: Point,
+            _baseline: Baseline,
+            _target: &mut D,
+        ) -> Result<Point, D::Error> {
+            Ok(position + Point::new(width as i32, 0))
+        }
+
+        fn measure_string(&self, text: &str, position: Point, _baseline: Baseline) -> TextMetrics {
+            let width = text.chars().map(Self::width).sum();
+            TextMetrics {
+                bounding_box: Rectangle::new(position, Size::new(width, 9)),
+                next_position: position + Point::new(width as i32, 0),
+            }
+        }
+
+        fn line_height(&self) -> u32 {
+            9
+        }
+    }
+
+    #[test]
+    fn test_proportional() {
+        let mut device = Device::new();
+        let _ = device.clear(BinaryColor::On);
+        let mut textbox = super::Textbox::with_style(
+            "ill wider illicit",
+            Blocks {
+                text_color: Some(BinaryColor::Off),
+                background_color: None,
+            },
+        );
+        textbox.draw(&mut device, None, false);
+
+        assert_eq!(textbox.position(&device, 4).0, Point::new(12, 0));
+        // "wider " ends at 44 and "illicit" is 22 wide
+        assert_eq!(textbox.position(&device, 10).0, Point::new(0, 9));
+
+        textbox.highlight(&mut device, 4..9);
+        assert_eq!(
+            device.display.get_pixel(Point::new(12, 0)),
+            Some(BinaryColor::Off)
+        );
+        assert_eq!(
+            device.display.get_pixel(Point::new(11, 0)),
+            Some(BinaryColor::On)
+        );
+        assert_eq!(
+            device.display.get_pixel(Point::new(38, 0)),
+            Some(BinaryColor::On)
+        );
+        assert_eq!(
+            device.display.get_pixel(Point::new(37, 0)),
+            Some(BinaryColor::Off)
+        );
+    }
+
     #[test]
     fn test_tail_on_push() {
         let mut device = Device::new();
@@ -533,6 +1237,7 @@ mod test {
             )
             .unwrap(),
         );
+        textbox.set_font(&FONT_6X9);
         textbox.draw(&mut device, None, false);
 
         // should tail on push
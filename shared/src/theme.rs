@@ -0,0 +1,234 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use embedded_graphics::{
+    mono_font::{
+        MonoFont, MonoTextStyle,
+        ascii::{FONT_6X9, FONT_6X10, FONT_9X15, FONT_10X20},
+    },
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use enum_iterator::Sequence;
+use strum_macros::IntoStaticStr;
+
+// how selected items stand out from the rest
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Selection {
+    // drawn in the background color on a block of the foreground color
+    Inverted,
+    // a border round it, which keeps large text easier to read
+    Framed,
+}
+
+// colors, fonts and lines for everything shared draws. `BinaryColor::On` is the lit, empty
+// screen, so the usual theme draws `Off` on `On`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub foreground: BinaryColor,
+    pub background: BinaryColor,
+    pub selection: Selection,
+    pub font: &'static MonoFont<'static>,
+    // for the titles over menus and dialogs
+    pub title_font: &'static MonoFont<'static>,
+    // for logs and the labels of soft keys
+    pub small_font: &'static MonoFont<'static>,
+    // for a number or two on their own
+    pub large_font: &'static MonoFont<'static>,
+    pub border_width: u32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Preset::default().theme()
+    }
+}
+
+impl Theme {
+    pub fn text_style(&self) -> MonoTextStyle<'static, BinaryColor> {
+        MonoTextStyle::new(self.font, self.foreground)
+    }
+
+    pub fn title_text_style(&self) -> MonoTextStyle<'static, BinaryColor> {
+        MonoTextStyle::new(self.title_font, self.foreground)
+    }
+
+    pub fn small_text_style(&self) -> MonoTextStyle<'static, BinaryColor> {
+        MonoTextStyle::new(self.small_font, self.foreground)
+    }
+
+    pub fn large_text_style(&self) -> MonoTextStyle<'static, BinaryColor> {
+        MonoTextStyle::new(self.large_font, self.foreground)
+    }
+
+    pub fn fill(&self) -> PrimitiveStyle<BinaryColor> {
+        PrimitiveStyle::with_fill(self.foreground)
+    }
+
+    pub fn border(&self) -> PrimitiveStyle<BinaryColor> {
+        PrimitiveStyle::with_stroke(self.foreground, self.border_width)
+    }
+
+    pub fn clear(&self, target: &mut impl DrawTarget<Color = BinaryColor>) {
+        let _ = target.clear(self.background);
+    }
+
+    // the colors of text, and of what is behind it, for something that may be selected
+    pub fn colors(&self, selected: bool) -> (BinaryColor, BinaryColor) {
+        if selected && self.selection == Selection::Inverted {
+            (self.background, self.foreground)
+        } else {
+            (self.foreground, self.background)
+        }
+    }
+
+    // fills in behind something that may be selected and gives back the color to draw it in
+    pub fn draw_selection(
+        &self,
+        target: &mut impl DrawTarget<Color = BinaryColor>,
+        area: Rectangle,
+        selected: bool,
+    ) -> BinaryColor {
+        let (foreground, background) = self.colors(selected);
+        let _ = area
+            .into_styled(PrimitiveStyle::with_fill(background))
+            .draw(target);
+        if selected && self.selection == Selection::Framed {
+            let _ = area
+                .into_styled(PrimitiveStyle::with_stroke(self.foreground, 1))
+                .draw(target);
+        }
+        foreground
+    }
+}
+
+// the themes to choose from in settings
+#[derive(Clone, Copy, Debug, Default, IntoStaticStr, Sequence, PartialEq)]
+#[repr(u8)]
+pub enum Preset {
+    #[default]
+    Standard,
+    Inverted,
+    #[strum(serialize = "Large text")]
+    LargeText,
+    // the fonts from the `fonts` crate, as the 3310 had them
+    Classic,
+}
+
+impl AsRef<str> for Preset {
+    fn as_ref(&self) -> &str {
+        (*self).into()
+    }
+}
+
+impl Preset {
+    pub fn theme(self) -> Theme {
+        match self {
+            Self::Standard => Theme {
+                foreground: BinaryColor::Off,
+                background: BinaryColor::On,
+                selection: Selection::Inverted,
+                font: &FONT_6X10,
+                title_font: &FONT_6X10,
+                small_font: &FONT_6X9,
+                large_font: &FONT_10X20,
+                border_width: 1,
+            },
+            Self::Inverted => Theme {
+                foreground: BinaryColor::On,
+                background: BinaryColor::Off,
+                ..Self::Standard.theme()
+            },
+            Self::LargeText => Theme {
+                selection: Selection::Framed,
+                font: &FONT_9X15,
+                title_font: &FONT_9X15,
+                small_font: &FONT_6X10,
+                border_width: 2,
+                ..Self::Standard.theme()
+            },
+            Self::Classic => Theme {
+                font: &fonts::LARGE,
+                title_font: &fonts::BOLD,
+                small_font: &fonts::SMALL,
+                ..Self::Standard.theme()
+            },
+        }
+    }
+}
+
+// one theme for the whole device, so that a widget deep inside an app follows a change made in
+// settings without it being handed down to it
+static PRESET: AtomicU8 = AtomicU8::new(Preset::Standard as u8);
+
+pub fn preset() -> Preset {
+    enum_iterator::all::<Preset>()
+        .find(|preset| *preset as u8 == PRESET.load(Ordering::Relaxed))
+        .unwrap_or_default()
+}
+
+pub fn set(preset: Preset) {
+    PRESET.store(preset as u8, Ordering::Relaxed);
+}
+
+pub fn current() -> Theme {
+    preset().theme()
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::mock_display::MockDisplay;
+
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        let standard = Preset::Standard.theme();
+        assert_eq!(standard, Theme::default());
+        let inverted = Preset::Inverted.theme();
+        assert_eq!(
+            (inverted.foreground, inverted.background),
+            (standard.background, standard.foreground)
+        );
+        assert!(
+            Preset::LargeText.theme().font.character_size.height
+                > standard.font.character_size.height
+        );
+        let name: &str = Preset::LargeText.into();
+        assert_eq!(name, "Large text");
+        let classic = Preset::Classic.theme();
+        assert_eq!(
+            (classic.small_font, classic.font, classic.title_font),
+            (&fonts::SMALL, &fonts::LARGE, &fonts::BOLD)
+        );
+    }
+
+    #[test]
+    fn test_selection() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        let area = Rectangle::new(Point::zero(), Size::new(4, 3));
+        let color = Preset::LargeText
+            .theme()
+            .draw_selection(&mut display, area, true);
+        assert_eq!(color, BinaryColor::Off);
+        #[rustfmt::skip]
+        display.assert_pattern(&[
+            "....",
+            ".##.",
+            "....",
+        ]);
+
+        let mut display = MockDisplay::new();
+        let color = Preset::Standard
+            .theme()
+            .draw_selection(&mut display, area, true);
+        assert_eq!(color, BinaryColor::On);
+        #[rustfmt::skip]
+        display.assert_pattern(&[
+            "....",
+            "....",
+            "....",
+        ]);
+    }
+}
@@ -1,4 +1,5 @@
 use chrono::Timelike;
+use embassy_time::Duration;
 
 // TODO: use something better
 pub fn to_char(digit: u32) -> char {
@@ -17,8 +18,17 @@ pub fn to_char(digit: u32) -> char {
     }
 }
 
+// what is left of `timeout` once `elapsed` has gone by, nothing if it is already up
+pub fn remaining(timeout: Duration, elapsed: Duration) -> Duration {
+    timeout.checked_sub(elapsed).unwrap_or(Duration::MIN)
+}
+
 pub fn write_time(rtc: &mut impl crate::Rtc, seconds: bool) -> heapless::String<8> {
-    let now = chrono::DateTime::<chrono::Utc>::from_timestamp(rtc.timestamp().unwrap(), 0).unwrap();
+    format_time(rtc.timestamp().unwrap(), seconds)
+}
+
+pub fn format_time(timestamp: i64, seconds: bool) -> heapless::String<8> {
+    let now = chrono::DateTime::<chrono::Utc>::from_timestamp(timestamp, 0).unwrap();
     let mut text = heapless::String::new();
 
     text.push(to_char(now.hour() / 10)).unwrap();
@@ -0,0 +1,153 @@
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+use embassy_time::Instant;
+use enum_iterator::Sequence;
+use strum_macros::IntoStaticStr;
+
+use crate::{
+    Buzzer,
+    sequencer::{Sequencer, Step},
+};
+
+// a frequency in hertz (0 for a rest) and how long it lasts in milliseconds
+pub type Note = (u16, u16);
+
+const CLICK: &[Note] = &[(4000, 5)];
+const BEEP: &[Note] = &[(2000, 40)];
+const CHIRP: &[Note] = &[(1500, 15), (3000, 15)];
+pub const WARNING: &[Note] = &[(400, 80), (0, 40), (400, 80)];
+
+// how loud each keypad tone level is, and warning tones whenever they are on
+const VOLUME_PER_LEVEL: u8 = 25;
+pub const WARNING_VOLUME: u8 = 40;
+
+// what a key press sounds like
+#[derive(Clone, Copy, Debug, Default, IntoStaticStr, Sequence, PartialEq)]
+pub enum ToneSet {
+    #[default]
+    Click,
+    Beep,
+    Chirp,
+}
+
+impl AsRef<str> for ToneSet {
+    fn as_ref(&self) -> &str {
+        (*self).into()
+    }
+}
+
+impl ToneSet {
+    pub fn notes(self) -> &'static [Note] {
+        match self {
+            Self::Click => CLICK,
+            Self::Beep => BEEP,
+            Self::Chirp => CHIRP,
+        }
+    }
+}
+
+// what a key press is played at for a keypad tone level from the profile
+pub fn key_volume(level: u8) -> u8 {
+    level.saturating_mul(VOLUME_PER_LEVEL)
+}
+
+// widgets call this when asked to do something they can't (scrolling past the end of a menu,
+// typing into a full textbox) and the system beeps, if the profile wants it, next time it gets a
+// look in
+static WARNED: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+
+pub fn warn() {
+    WARNED.signal(());
+}
+
+pub async fn warned() {
+    WARNED.wait().await
+}
+
+impl Step for Note {
+    fn millis(&self) -> u16 {
+        self.1
+    }
+}
+
+// plays a handful of notes on the buzzer as their deadlines come up
+#[derive(Debug, Default)]
+pub struct Tone {
+    notes: Sequencer<Note>,
+}
+
+impl Tone {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn playing(&self) -> bool {
+        self.notes.playing()
+    }
+
+    pub fn start(&mut self, device: &mut impl Buzzer, notes: &'static [Note], now: Instant) {
+        self.notes.start(notes, now);
+        self.update(device, now);
+    }
+
+    pub fn next_update(&self) -> Option<Instant> {
+        self.notes.next_update()
+    }
+
+    // forgets about the tone without touching the buzzer, for when something else has taken it
+    pub fn cancel(&mut self) {
+        self.notes.cancel();
+    }
+
+    // moves on to whichever note is due at `now`, muting the buzzer once they are all done
+    pub fn update(&mut self, device: &mut impl Buzzer, now: Instant) {
+        let finished = self.notes.update(now, |_, (frequency, _)| {
+            if *frequency == 0 {
+                let _ = device.mute_buzzer();
+            } else {
+                let _ = device.set_frequency(*frequency);
+                let _ = device.unmute_buzzer();
+            }
+        });
+        if finished {
+            let _ = device.mute_buzzer();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embassy_time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_tone() {
+        let mut device = crate::test::Device::new(1, 1, &[]);
+        let start = Instant::from_secs(0);
+        let mut tone = Tone::new();
+        tone.start(&mut device, WARNING, start);
+        assert_eq!(device.frequency, Some(400));
+
+        tone.update(&mut device, start + Duration::from_millis(100));
+        assert_eq!(device.frequency, None);
+        assert_eq!(tone.next_update(), Some(start + Duration::from_millis(120)));
+        tone.update(&mut device, start + Duration::from_millis(120));
+        assert_eq!(device.frequency, Some(400));
+
+        tone.update(&mut device, start + Duration::from_millis(200));
+        assert_eq!(device.frequency, None);
+        assert!(!tone.playing());
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut device = crate::test::Device::new(1, 1, &[]);
+        let mut tone = Tone::new();
+        tone.start(&mut device, ToneSet::Beep.notes(), Instant::from_secs(0));
+        tone.cancel();
+        tone.update(&mut device, Instant::from_secs(1));
+        // left sounding for whoever took over
+        assert_eq!(device.frequency, Some(2000));
+        assert_eq!(key_volume(3), 75);
+    }
+}
@@ -0,0 +1,180 @@
+use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+use embassy_time::{Instant, Timer};
+use strum_macros::IntoStaticStr;
+
+use crate::{
+    VibrationMotor,
+    sequencer::{Sequencer, Step},
+};
+
+pub const DEFAULT_INTENSITY: u8 = 100;
+
+// in milliseconds, alternating between buzzing and resting and starting with a buzz
+const SHORT_BUZZ: &[u16] = &[100];
+const LONG_BUZZ: &[u16] = &[600];
+const HEARTBEAT: &[u16] = &[80, 120, 160, 640, 80, 120, 160];
+const SOS: &[u16] = &[
+    100, 100, 100, 100, 100, 300, 300, 100, 300, 100, 300, 300, 100, 100, 100, 100, 100,
+];
+
+#[derive(Clone, Copy, Debug, IntoStaticStr, PartialEq)]
+pub enum Pattern {
+    #[strum(serialize = "Short buzz")]
+    ShortBuzz,
+    #[strum(serialize = "Long buzz")]
+    LongBuzz,
+    Heartbeat,
+    #[strum(serialize = "SOS")]
+    Sos,
+    // milliseconds as for the others
+    Custom(&'static [u16]),
+}
+
+impl AsRef<str> for Pattern {
+    fn as_ref(&self) -> &str {
+        (*self).into()
+    }
+}
+
+impl Pattern {
+    pub fn steps(self) -> &'static [u16] {
+        match self {
+            Self::ShortBuzz => SHORT_BUZZ,
+            Self::LongBuzz => LONG_BUZZ,
+            Self::Heartbeat => HEARTBEAT,
+            Self::Sos => SOS,
+            Self::Custom(steps) => steps,
+        }
+    }
+}
+
+impl Step for u16 {
+    fn millis(&self) -> u16 {
+        *self
+    }
+}
+
+// plays a pattern on the motor as its deadlines come up
+#[derive(Debug, Default)]
+pub struct Player {
+    steps: Sequencer<u16>,
+}
+
+impl Player {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn playing(&self) -> bool {
+        self.steps.playing()
+    }
+
+    // cuts short anything already playing
+    pub fn start(
+        &mut self,
+        device: &mut impl VibrationMotor,
+        pattern: Pattern,
+        intensity: u8,
+        now: Instant,
+    ) {
+        device.set_intensity(intensity);
+        self.steps.start(pattern.steps(), now);
+        self.update(device, now);
+    }
+
+    pub fn stop(&mut self, device: &mut impl VibrationMotor) {
+        if self.steps.playing() {
+            self.steps.cancel();
+            device.stop_vibrating();
+        }
+    }
+
+    pub fn next_update(&self) -> Option<Instant> {
+        self.steps.next_update()
+    }
+
+    // moves on to whichever step is due at `now`
+    pub fn update(&mut self, device: &mut impl VibrationMotor, now: Instant) {
+        let finished = self.steps.update(now, |index, _| {
+            if index % 2 == 0 {
+                device.start_vibrating();
+            } else {
+                device.stop_vibrating();
+            }
+        });
+        if finished {
+            device.stop_vibrating();
+        }
+    }
+}
+
+// plays the whole pattern before returning
+pub async fn play(device: &mut impl VibrationMotor, pattern: Pattern, intensity: u8) {
+    let mut player = Player::new();
+    player.start(device, pattern, intensity, Instant::now());
+    while let Some(until) = player.next_update() {
+        Timer::at(until).await;
+        player.update(device, Instant::now());
+    }
+}
+
+// the system plays whatever was asked for last in the background, next time it gets a look in
+static REQUEST: Signal<CriticalSectionRawMutex, (Pattern, u8)> = Signal::new();
+
+pub fn vibrate(pattern: Pattern, intensity: u8) {
+    REQUEST.signal((pattern, intensity));
+}
+
+pub async fn requested() -> (Pattern, u8) {
+    REQUEST.wait().await
+}
+
+#[cfg(test)]
+mod test {
+    use embassy_time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_player() {
+        let mut device = crate::test::Device::new(1, 1, &[]);
+        let start = Instant::from_secs(0);
+        let mut player = Player::new();
+        player.start(&mut device, Pattern::Heartbeat, 40, start);
+        assert!(device.vibrating && player.playing());
+        assert_eq!(device.intensity, 40);
+
+        player.update(&mut device, start + Duration::from_millis(100));
+        assert!(!device.vibrating);
+        // late updates catch up rather than stretching the pattern out
+        player.update(&mut device, start + Duration::from_millis(400));
+        assert!(!device.vibrating);
+        assert_eq!(
+            player.next_update(),
+            Some(start + Duration::from_millis(1000))
+        );
+
+        player.update(&mut device, start + Duration::from_secs(2));
+        assert!(!device.vibrating && !player.playing());
+        assert_eq!(player.next_update(), None);
+    }
+
+    #[test]
+    fn test_stop() {
+        let mut device = crate::test::Device::new(1, 1, &[]);
+        let mut player = Player::new();
+        player.start(&mut device, Pattern::Sos, 100, Instant::from_secs(0));
+        player.stop(&mut device);
+        assert!(!device.vibrating && !player.playing());
+        player.update(&mut device, Instant::from_secs(1));
+        assert!(!device.vibrating);
+    }
+
+    #[test]
+    fn test_steps() {
+        // as long as an SOS should take, ending on a buzz
+        let sos = Pattern::Sos.steps();
+        assert_eq!(sos.iter().map(|step| u32::from(*step)).sum::<u32>(), 2700);
+        assert_eq!(sos.len() % 2, 1);
+    }
+}
@@ -0,0 +1,54 @@
+// controls that apps share rather than each drawing their own. each draws onto the whole of the
+// device it is given and has an async `process` that runs it until the user is done with it.
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    prelude::*,
+    text::{Alignment, Baseline, Text, TextStyleBuilder, renderer::TextRenderer},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+
+use crate::Key;
+
+pub mod choices;
+pub mod picker;
+pub mod progress;
+pub mod slider;
+pub mod soft_key;
+pub mod spinner;
+pub mod toast;
+
+fn text_style() -> MonoTextStyle<'static, BinaryColor> {
+    crate::theme::current().text_style()
+}
+
+// centred along the top, gives back how tall it is
+fn draw_title(target: &mut impl DrawTarget<Color = BinaryColor>, title: &str) -> u32 {
+    let style = crate::theme::current().title_text_style();
+    let _ = Text::with_text_style(
+        title,
+        Point::new(target.bounding_box().size.width as i32 / 2, 0),
+        style,
+        TextStyleBuilder::new()
+            .alignment(Alignment::Center)
+            .baseline(Baseline::Top)
+            .build(),
+    )
+    .draw(target);
+    style.line_height()
+}
+
+fn digit(key: &Key) -> Option<u32> {
+    match key {
+        Key::Zero => Some(0),
+        Key::One => Some(1),
+        Key::Two => Some(2),
+        Key::Three => Some(3),
+        Key::Four => Some(4),
+        Key::Five => Some(5),
+        Key::Six => Some(6),
+        Key::Seven => Some(7),
+        Key::Eight => Some(8),
+        Key::Nine => Some(9),
+        _ => None,
+    }
+}
@@ -0,0 +1,184 @@
+use embedded_graphics::{mono_font::MonoTextStyle, prelude::*, text::renderer::TextRenderer};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+
+use crate::{
+    Key, Keypad,
+    held_key::{Event, HeldKey},
+    menu::row_render,
+};
+
+// ticks any number of options, one bit each in `chosen`, with a last row to finish with
+pub struct Choices<'a> {
+    title: &'a str,
+    options: &'a [&'a str],
+    chosen: u32,
+    selected: usize,
+    held_key: HeldKey,
+}
+
+impl<'a> Choices<'a> {
+    pub fn new(title: &'a str, options: &'a [&'a str], chosen: u32) -> Self {
+        assert!(options.len() <= 32);
+        Self {
+            title,
+            options,
+            chosen,
+            selected: 0,
+            held_key: HeldKey::new(500, 100),
+        }
+    }
+
+    pub fn chosen(&self) -> u32 {
+        self.chosen
+    }
+
+    fn toggle(&mut self, index: usize) {
+        self.chosen ^= 1 << index;
+    }
+
+    fn is_done(&self) -> bool {
+        self.selected == self.options.len()
+    }
+
+    pub fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>) {
+        let theme = crate::theme::current();
+        theme.clear(target);
+        let top = super::draw_title(target, self.title);
+        let line_height = super::text_style().line_height();
+        let visible =
+            ((target.bounding_box().size.height.saturating_sub(top)) / line_height).max(1) as usize;
+        let first = self.selected.saturating_sub(visible - 1);
+
+        let mut point = Point::new(0, top as i32);
+        for index in first..=self.options.len() {
+            let mut text: heapless::String<32> = heapless::String::new();
+            let _ = match self.options.get(index) {
+                Some(option) if self.chosen & (1 << index) != 0 => {
+                    core::fmt::write(&mut text, format_args!("[x] {option}"))
+                }
+                Some(option) => core::fmt::write(&mut text, format_args!("[ ] {option}")),
+                None => core::fmt::write(&mut text, format_args!("Done")),
+            };
+            let selected = index == self.selected;
+            let style = MonoTextStyle::new(theme.font, theme.colors(selected).0);
+            match row_render(target, selected, &text, point, style) {
+                Some(next) => point = next,
+                None => break,
+            }
+        }
+    }
+
+    // the options ticked when Done is selected, or `None` if cancelled
+    pub async fn process(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> Option<u32> {
+        loop {
+            self.draw(device);
+            match self.held_key.event(device).await {
+                Some(Event::Down(Key::Up) | Event::Delay(Key::Up) | Event::Repeat(Key::Up)) => {
+                    self.selected = self.selected.saturating_sub(1)
+                }
+                Some(
+                    Event::Down(Key::Down) | Event::Delay(Key::Down) | Event::Repeat(Key::Down),
+                ) => self.selected = (self.selected + 1).min(self.options.len()),
+                Some(Event::Down(Key::Select)) if self.is_done() => return Some(self.chosen),
+                Some(Event::Down(Key::Select)) => self.toggle(self.selected),
+                Some(Event::Down(Key::Cancel)) => return None,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+    use crate::KeyEvent;
+
+    #[test]
+    fn test_choices() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                60,
+                48,
+                &[
+                    (0, KeyEvent::Down(Key::Select)),
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Up(Key::Down)),
+                    (0, KeyEvent::Down(Key::Select)),
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Up(Key::Down)),
+                    (0, KeyEvent::Down(Key::Select)),
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Up(Key::Down)),
+                    (0, KeyEvent::Down(Key::Select)),
+                ],
+            );
+            let options = ["Mon", "Tue", "Wed"];
+            let mut choices = Choices::new("Repeat", &options, 0b010);
+            assert_eq!(choices.process(&mut device).await, Some(0b101));
+        });
+    }
+
+    #[test]
+    fn test_cancel() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                60,
+                48,
+                &[
+                    (0, KeyEvent::Down(Key::Select)),
+                    (0, KeyEvent::Down(Key::Cancel)),
+                ],
+            );
+            let options = ["Mon", "Tue"];
+            let mut choices = Choices::new("Repeat", &options, 0);
+            assert_eq!(choices.process(&mut device).await, None);
+            assert_eq!(choices.chosen(), 1);
+        });
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut device = crate::test::Device::new(36, 30, &[]);
+        let options = ["A", "B", "C"];
+        let mut choices = Choices::new("", &options, 0b001);
+        choices.selected = 2;
+        choices.draw(&mut device);
+        device.display.assert_pattern(&[
+            "####################################",
+            "####################################",
+            "####################################",
+            "####################################",
+            "####################################",
+            "####################################",
+            "####################################",
+            "####################################",
+            "####################################",
+            "####################################",
+            "####################################",
+            "#...#########...########....########",
+            "#.#############.#########.##.#######",
+            "#.#############.#########.##.#######",
+            "#.#############.#########...########",
+            "#.#############.#########.##.#######",
+            "#.#############.#########.##.#######",
+            "#...#########...########....########",
+            "####################################",
+            "####################################",
+            "....................................",
+            ".###.........###.........###........",
+            ".#.............#........#...#.......",
+            ".#.............#........#...........",
+            ".#.............#........#...........",
+            ".#.............#........#...........",
+            ".#.............#........#...#.......",
+            ".###.........###.........###........",
+            "....................................",
+            "....................................",
+        ]);
+    }
+}
@@ -0,0 +1,334 @@
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
+use embedded_graphics::{
+    mono_font::MonoTextStyle,
+    prelude::*,
+    primitives::Rectangle,
+    text::{Baseline, Text},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+
+use crate::{
+    Key, Keypad,
+    held_key::{Event, HeldKey},
+};
+
+struct Field {
+    value: u32,
+    min: u32,
+    max: u32,
+    digits: u32,
+}
+
+impl Field {
+    fn new(value: u32, min: u32, max: u32, digits: u32) -> Self {
+        Self {
+            value: value.clamp(min, max),
+            min,
+            max,
+            digits,
+        }
+    }
+}
+
+// numbers edited one after another, such as hours then minutes. up and down change the selected
+// one, wrapping around, or its digits can be typed in. select moves on to the next and cancel
+// goes back.
+struct Fields<const N: usize> {
+    fields: [Field; N],
+    separator: char,
+    selected: usize,
+    // how many digits of the selected field have been typed
+    typed: u32,
+    held_key: HeldKey,
+}
+
+impl<const N: usize> Fields<N> {
+    fn new(fields: [Field; N], separator: char) -> Self {
+        Self {
+            fields,
+            separator,
+            selected: 0,
+            typed: 0,
+            held_key: HeldKey::new(500, 100),
+        }
+    }
+
+    fn values(&self) -> [u32; N] {
+        core::array::from_fn(|i| self.fields[i].value)
+    }
+
+    fn step(&mut self, up: bool) {
+        let field = &mut self.fields[self.selected];
+        field.value = match (up, field.value) {
+            (true, value) if value >= field.max => field.min,
+            (true, value) => value + 1,
+            (false, value) if value <= field.min => field.max,
+            (false, value) => value - 1,
+        };
+        self.typed = 0;
+    }
+
+    // true once the last field is done
+    fn next(&mut self) -> bool {
+        let field = &mut self.fields[self.selected];
+        field.value = field.value.clamp(field.min, field.max);
+        self.typed = 0;
+        if self.selected + 1 == N {
+            true
+        } else {
+            self.selected += 1;
+            false
+        }
+    }
+
+    // true if typing the digit finished the last field
+    fn type_digit(&mut self, digit: u32) -> bool {
+        let field = &mut self.fields[self.selected];
+        field.value = if self.typed == 0 {
+            digit
+        } else {
+            field.value * 10 + digit
+        };
+        self.typed += 1;
+        self.typed == field.digits && self.next()
+    }
+
+    fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>, title: &str) {
+        let theme = crate::theme::current();
+        theme.clear(target);
+        let top = super::draw_title(target, title);
+        let area = target.bounding_box();
+        let font = theme.font;
+        let width: u32 = self.fields.iter().map(|f| f.digits).sum::<u32>() + N as u32 - 1;
+        let mut point = Point::new(
+            (area
+                .size
+                .width
+                .saturating_sub(width * font.character_size.width)
+                / 2) as i32,
+            (top + area
+                .size
+                .height
+                .saturating_sub(top + font.character_size.height)
+                / 2) as i32,
+        );
+        for (i, field) in self.fields.iter().enumerate() {
+            let mut text: heapless::String<8> = heapless::String::new();
+            let _ = core::fmt::write(
+                &mut text,
+                format_args!("{:0digits$}", field.value, digits = field.digits as usize),
+            );
+            let size = Size::new(
+                field.digits * font.character_size.width,
+                font.character_size.height,
+            );
+            let foreground =
+                theme.draw_selection(target, Rectangle::new(point, size), i == self.selected);
+            point = Text::with_baseline(
+                &text,
+                point,
+                MonoTextStyle::new(font, foreground),
+                Baseline::Top,
+            )
+            .draw(target)
+            .unwrap_or(point);
+            if i + 1 < N {
+                let mut separator = [0; 4];
+                point = Text::with_baseline(
+                    self.separator.encode_utf8(&mut separator),
+                    point,
+                    MonoTextStyle::new(font, theme.foreground),
+                    Baseline::Top,
+                )
+                .draw(target)
+                .unwrap_or(point);
+            }
+        }
+    }
+
+    async fn process(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+        title: &str,
+    ) -> Option<[u32; N]> {
+        loop {
+            self.draw(device, title);
+            match self.held_key.event(device).await {
+                Some(Event::Down(Key::Up) | Event::Delay(Key::Up) | Event::Repeat(Key::Up)) => {
+                    self.step(true)
+                }
+                Some(
+                    Event::Down(Key::Down) | Event::Delay(Key::Down) | Event::Repeat(Key::Down),
+                ) => self.step(false),
+                Some(Event::Down(Key::Select)) => {
+                    if self.next() {
+                        return Some(self.values());
+                    }
+                }
+                Some(Event::Down(Key::Cancel)) => {
+                    if self.selected == 0 {
+                        return None;
+                    }
+                    self.selected -= 1;
+                    self.typed = 0;
+                }
+                Some(Event::Down(key)) => {
+                    let finished = super::digit(&key).is_some_and(|digit| self.type_digit(digit));
+                    if finished {
+                        return Some(self.values());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+pub struct TimePicker<'a> {
+    title: &'a str,
+    fields: Fields<2>,
+}
+
+impl<'a> TimePicker<'a> {
+    pub fn new(title: &'a str, time: NaiveTime) -> Self {
+        Self {
+            title,
+            fields: Fields::new(
+                [
+                    Field::new(time.hour(), 0, 23, 2),
+                    Field::new(time.minute(), 0, 59, 2),
+                ],
+                ':',
+            ),
+        }
+    }
+
+    pub fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>) {
+        self.fields.draw(target, self.title);
+    }
+
+    pub async fn process(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> Option<NaiveTime> {
+        let [hour, minute] = self.fields.process(device, self.title).await?;
+        NaiveTime::from_hms_opt(hour, minute, 0)
+    }
+}
+
+pub struct DatePicker<'a> {
+    title: &'a str,
+    fields: Fields<3>,
+}
+
+impl<'a> DatePicker<'a> {
+    pub fn new(title: &'a str, date: NaiveDate) -> Self {
+        Self {
+            title,
+            fields: Fields::new(
+                [
+                    Field::new(date.day(), 1, 31, 2),
+                    Field::new(date.month(), 1, 12, 2),
+                    Field::new(date.year() as u32, 2000, 2099, 4),
+                ],
+                '/',
+            ),
+        }
+    }
+
+    pub fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>) {
+        self.fields.draw(target, self.title);
+    }
+
+    // a day past the end of the month becomes its last day
+    pub async fn process(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> Option<NaiveDate> {
+        let [day, month, year] = self.fields.process(device, self.title).await?;
+        (1..=day)
+            .rev()
+            .find_map(|day| NaiveDate::from_ymd_opt(year as i32, month, day))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+    use crate::KeyEvent;
+
+    fn press(keys: &[Key]) -> heapless::Vec<(u64, KeyEvent), 16> {
+        keys.iter()
+            .flat_map(|key| {
+                [
+                    (0, KeyEvent::Down(key.clone())),
+                    (0, KeyEvent::Up(key.clone())),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_time() {
+        block_on(async {
+            let events = press(&[Key::Down, Key::Select, Key::Three, Key::Five]);
+            let mut device = crate::test::Device::new(40, 20, &events);
+            let mut picker = TimePicker::new("", NaiveTime::from_hms_opt(0, 10, 0).unwrap());
+            assert_eq!(
+                picker.process(&mut device).await,
+                NaiveTime::from_hms_opt(23, 35, 0)
+            );
+        });
+    }
+
+    #[test]
+    fn test_date() {
+        block_on(async {
+            let events = press(&[
+                Key::Three,
+                Key::One,
+                Key::Cancel,
+                Key::Select,
+                Key::Zero,
+                Key::Two,
+                Key::Select,
+            ]);
+            let mut device = crate::test::Device::new(64, 20, &events);
+            let mut picker = DatePicker::new("", NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+            assert_eq!(
+                picker.process(&mut device).await,
+                NaiveDate::from_ymd_opt(2024, 2, 29)
+            );
+        });
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut device = crate::test::Device::new(34, 20, &[]);
+        TimePicker::new("", NaiveTime::from_hms_opt(9, 5, 0).unwrap()).draw(&mut device);
+        device.display.assert_pattern(&[
+            "##################################",
+            "##################################",
+            "##################################",
+            "##################################",
+            "##################################",
+            "##################################",
+            "##################################",
+            "##################################",
+            "##################################",
+            "##################################",
+            "##............####################",
+            "##..#....###..########.###.....###",
+            "##.#.#..#...#.##.####.#.##.#######",
+            "###...#.#..##.#...##.###.#.#..####",
+            "###...#..##.#.##.###.###.#..##.###",
+            "###...#.....#.######.###.#####.###",
+            "##.#.#.....#..##.####.#.##.###.###",
+            "##..#....##...#...####.####...####",
+            "##............##.#################",
+            "##............####################",
+        ]);
+    }
+}
@@ -0,0 +1,155 @@
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{
+    prelude::*,
+    primitives::Rectangle,
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+
+use crate::{Key, KeyEvent, Keypad};
+
+// how often `ProgressBar::process` checks how far along things are
+pub const POLL: Duration = Duration::from_millis(100);
+
+pub struct ProgressBar<'a> {
+    title: &'a str,
+    total: u32,
+}
+
+impl<'a> ProgressBar<'a> {
+    pub fn new(title: &'a str, total: u32) -> Self {
+        Self {
+            title,
+            total: total.max(1),
+        }
+    }
+
+    pub fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>, done: u32) {
+        let theme = crate::theme::current();
+        theme.clear(target);
+        let top = super::draw_title(target, self.title) as i32;
+        let area = target.bounding_box();
+        let done = done.min(self.total);
+
+        let bar = Rectangle::new(Point::new(2, top + 2), Size::new(area.size.width - 4, 8));
+        let _ = bar.into_styled(theme.border()).draw(target);
+        let inside = bar.offset(-2);
+        let _ = Rectangle::new(
+            inside.top_left,
+            Size::new(inside.size.width * done / self.total, inside.size.height),
+        )
+        .into_styled(theme.fill())
+        .draw(target);
+
+        let mut percent: heapless::String<5> = heapless::String::new();
+        let _ = core::fmt::write(&mut percent, format_args!("{}%", done * 100 / self.total));
+        let _ = Text::with_text_style(
+            &percent,
+            Point::new(
+                area.size.width as i32 / 2,
+                bar.bottom_right().unwrap().y + 2,
+            ),
+            theme.text_style(),
+            TextStyleBuilder::new()
+                .alignment(Alignment::Center)
+                .baseline(Baseline::Top)
+                .build(),
+        )
+        .draw(target);
+    }
+
+    // redraws with whatever `done` says until it reaches the total, which gives true, or until
+    // cancelled, which gives false
+    pub async fn process(
+        &self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+        mut done: impl FnMut() -> u32,
+    ) -> bool {
+        loop {
+            let done = done();
+            self.draw(device, done);
+            if done >= self.total {
+                return true;
+            }
+            if let Some(KeyEvent::Down(Key::Cancel)) =
+                device.event_before(Instant::now() + POLL).await
+            {
+                return false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn test_progress() {
+        block_on(async {
+            // any key but cancel is ignored, and moves the clock on for the polls once it's pressed
+            let mut device = crate::test::Device::new(24, 24, &[(0, KeyEvent::Down(Key::Two))]);
+            let mut steps = [1, 4].into_iter();
+            let bar = ProgressBar::new("", 4);
+            assert!(bar.process(&mut device, || steps.next().unwrap()).await);
+        });
+    }
+
+    #[test]
+    fn test_cancel() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                24,
+                24,
+                &[
+                    (0, KeyEvent::Down(Key::Two)),
+                    (0, KeyEvent::Down(Key::Cancel)),
+                ],
+            );
+            let bar = ProgressBar::new("", 4);
+            assert!(!bar.process(&mut device, || 1).await);
+        });
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut device = crate::test::Device::new(24, 32, &[]);
+        ProgressBar::new("", 4).draw(&mut device, 1);
+        device.display.assert_pattern(&[
+            "########################",
+            "########################",
+            "########################",
+            "########################",
+            "########################",
+            "########################",
+            "########################",
+            "########################",
+            "########################",
+            "########################",
+            "########################",
+            "########################",
+            "##....................##",
+            "##.##################.##",
+            "##.#....#############.##",
+            "##.#....#############.##",
+            "##.#....#############.##",
+            "##.#....#############.##",
+            "##.##################.##",
+            "##....................##",
+            "########################",
+            "########################",
+            "#####...##.....##.##.###",
+            "####.###.#.#####.#.#.###",
+            "########.#.#..###.#.####",
+            "######..##..##.###.#####",
+            "#####.########.##.#.####",
+            "####.#####.###.#.#.#.###",
+            "####.....##...##.##.####",
+            "########################",
+            "########################",
+            "########################",
+        ]);
+    }
+}
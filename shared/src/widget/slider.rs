@@ -0,0 +1,137 @@
+use embedded_graphics::{
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+
+use crate::{
+    Key, Keypad,
+    held_key::{Event, HeldKey},
+};
+
+// a level from 0 to `max` shown as a row of bars getting taller, like the volume and contrast
+// settings
+pub struct Slider<'a> {
+    title: &'a str,
+    value: u32,
+    max: u32,
+    held_key: HeldKey,
+}
+
+impl<'a> Slider<'a> {
+    pub fn new(title: &'a str, value: u32, max: u32) -> Self {
+        assert!(max > 0);
+        Self {
+            title,
+            value: value.min(max),
+            max,
+            held_key: HeldKey::new(500, 100),
+        }
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    pub fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>) {
+        let theme = crate::theme::current();
+        theme.clear(target);
+        let top = super::draw_title(target, self.title);
+        let area = target.bounding_box();
+        let height = area.size.height.saturating_sub(top + 2);
+        // each bar and the gap after it
+        let pitch = (area.size.width / self.max).max(2);
+        let left = (area.size.width - pitch * self.max).div_ceil(2);
+        for i in 0..self.max {
+            let bar_height = (height * (i + 1) / self.max).max(1);
+            let bar = Rectangle::new(
+                Point::new(
+                    (left + i * pitch) as i32,
+                    (top + 1 + height - bar_height) as i32,
+                ),
+                Size::new(pitch - 1, bar_height),
+            );
+            let style = if i < self.value {
+                theme.fill()
+            } else {
+                PrimitiveStyle::with_stroke(theme.foreground, 1)
+            };
+            let _ = bar.into_styled(style).draw(target);
+        }
+    }
+
+    // the level chosen with Select, or `None` if cancelled
+    pub async fn process(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> Option<u32> {
+        loop {
+            self.draw(device);
+            match self.held_key.event(device).await {
+                Some(Event::Down(Key::Up) | Event::Delay(Key::Up) | Event::Repeat(Key::Up)) => {
+                    self.value = (self.value + 1).min(self.max)
+                }
+                Some(
+                    Event::Down(Key::Down) | Event::Delay(Key::Down) | Event::Repeat(Key::Down),
+                ) => self.value = self.value.saturating_sub(1),
+                Some(Event::Down(Key::Select)) => return Some(self.value),
+                Some(Event::Down(Key::Cancel)) => return None,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+    use crate::KeyEvent;
+
+    #[test]
+    fn test_slider() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                20,
+                20,
+                &[
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Up(Key::Down)),
+                    (0, KeyEvent::Down(Key::Down)),
+                    (0, KeyEvent::Up(Key::Down)),
+                    (0, KeyEvent::Down(Key::Select)),
+                ],
+            );
+            assert_eq!(Slider::new("", 1, 5).process(&mut device).await, Some(0));
+        });
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut device = crate::test::Device::new(20, 20, &[]);
+        Slider::new("", 2, 5).draw(&mut device);
+        device.display.assert_pattern(&[
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "################...#",
+            "################.#.#",
+            "############...#.#.#",
+            "############.#.#.#.#",
+            "########...#.#.#.#.#",
+            "####...#.#.#.#.#.#.#",
+            "####...#.#.#.#.#.#.#",
+            "...#...#...#...#...#",
+            "####################",
+        ]);
+    }
+}
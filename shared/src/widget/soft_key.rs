@@ -0,0 +1,100 @@
+use embedded_graphics::{
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder, renderer::TextRenderer},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+
+use crate::{Key, KeyEvent, Keypad};
+
+// what Select does, centred along the bottom like the label above the 3310's one soft key
+pub struct SoftKey<'a> {
+    label: &'a str,
+}
+
+impl<'a> SoftKey<'a> {
+    pub fn new(label: &'a str) -> Self {
+        Self { label }
+    }
+
+    // gives back the area left above it
+    pub fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>) -> Rectangle {
+        let area = target.bounding_box();
+        let theme = crate::theme::current();
+        let style = theme.small_text_style();
+        let height = style.line_height().min(area.size.height);
+        let bar = Rectangle::new(
+            area.top_left + Point::new(0, (area.size.height - height) as i32),
+            Size::new(area.size.width, height),
+        );
+        let _ = bar
+            .into_styled(PrimitiveStyle::with_fill(theme.background))
+            .draw(target);
+        let _ = Text::with_text_style(
+            self.label,
+            Point::new(
+                area.top_left.x + area.size.width as i32 / 2,
+                bar.top_left.y + height as i32 - 1,
+            ),
+            style,
+            TextStyleBuilder::new()
+                .alignment(Alignment::Center)
+                .baseline(Baseline::Bottom)
+                .build(),
+        )
+        .draw(target);
+        Rectangle::new(
+            area.top_left,
+            Size::new(area.size.width, bar.top_left.y as u32),
+        )
+    }
+
+    // true for Select and false for Cancel
+    pub async fn process(
+        &self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> bool {
+        self.draw(device);
+        loop {
+            match device.event().await {
+                KeyEvent::Down(Key::Select) => return true,
+                KeyEvent::Down(Key::Cancel) => return false,
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn test_soft_key() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                30,
+                10,
+                &[
+                    (0, KeyEvent::Down(Key::Two)),
+                    (0, KeyEvent::Down(Key::Select)),
+                ],
+            );
+            assert!(SoftKey::new("OK").process(&mut device).await);
+            device.display.assert_pattern(&[
+                "                              ",
+                "##############################",
+                "###########...###.##.#########",
+                "##########.###.##.#.##########",
+                "##########.###.##..###########",
+                "##########.###.##.#.##########",
+                "##########.###.##.##.#########",
+                "###########...###.##.#########",
+                "##############################",
+                "##############################",
+            ]);
+        });
+    }
+}
@@ -0,0 +1,216 @@
+use embedded_graphics::{
+    prelude::*,
+    primitives::Triangle,
+    text::{Alignment, Baseline, Text, TextStyleBuilder},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+
+use crate::{
+    Key, Keypad,
+    held_key::{Event, HeldKey},
+};
+
+// a number between `min` and `max`, changed a step at a time with up and down or typed in
+pub struct Spinner<'a> {
+    title: &'a str,
+    value: i32,
+    min: i32,
+    max: i32,
+    // digits typed so far replace the value rather than adding to it
+    typing: bool,
+    held_key: HeldKey,
+}
+
+impl<'a> Spinner<'a> {
+    pub fn new(title: &'a str, value: i32, min: i32, max: i32) -> Self {
+        assert!(min <= max);
+        Self {
+            title,
+            value: value.clamp(min, max),
+            min,
+            max,
+            typing: false,
+            held_key: HeldKey::new(500, 100),
+        }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    fn step(&mut self, by: i32) {
+        self.value = (self.value + by).clamp(self.min, self.max);
+        self.typing = false;
+    }
+
+    fn type_digit(&mut self, digit: u32) {
+        let typed = if self.typing {
+            self.value.saturating_mul(10).saturating_add(digit as i32)
+        } else {
+            digit as i32
+        };
+        // start again from this digit once the number gets too big
+        self.value = if typed > self.max {
+            digit as i32
+        } else {
+            typed
+        }
+        .clamp(self.min, self.max);
+        self.typing = true;
+    }
+
+    pub fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>) {
+        let theme = crate::theme::current();
+        theme.clear(target);
+        let top = super::draw_title(target, self.title) as i32;
+        let area = target.bounding_box();
+        let middle = area.size.width as i32 / 2;
+        let bottom = area.size.height as i32 - 1;
+        let arrow = theme.fill();
+
+        if self.value < self.max {
+            let _ = Triangle::new(
+                Point::new(middle, top + 1),
+                Point::new(middle - 3, top + 4),
+                Point::new(middle + 3, top + 4),
+            )
+            .into_styled(arrow)
+            .draw(target);
+        }
+        if self.value > self.min {
+            let _ = Triangle::new(
+                Point::new(middle, bottom - 1),
+                Point::new(middle - 3, bottom - 4),
+                Point::new(middle + 3, bottom - 4),
+            )
+            .into_styled(arrow)
+            .draw(target);
+        }
+
+        let mut text: heapless::String<12> = heapless::String::new();
+        let _ = core::fmt::write(&mut text, format_args!("{}", self.value));
+        let _ = Text::with_text_style(
+            &text,
+            Point::new(middle, (top + bottom) / 2),
+            theme.large_text_style(),
+            TextStyleBuilder::new()
+                .alignment(Alignment::Center)
+                .baseline(Baseline::Middle)
+                .build(),
+        )
+        .draw(target);
+    }
+
+    // the number chosen with Select, or `None` if cancelled
+    pub async fn process(
+        &mut self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> Option<i32> {
+        loop {
+            self.draw(device);
+            match self.held_key.event(device).await {
+                Some(Event::Down(Key::Up) | Event::Delay(Key::Up) | Event::Repeat(Key::Up)) => {
+                    self.step(1)
+                }
+                Some(
+                    Event::Down(Key::Down) | Event::Delay(Key::Down) | Event::Repeat(Key::Down),
+                ) => self.step(-1),
+                Some(Event::Down(Key::Select)) => return Some(self.value),
+                Some(Event::Down(Key::Cancel)) => return None,
+                Some(Event::Down(key)) => {
+                    if let Some(digit) = super::digit(&key) {
+                        self.type_digit(digit);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+    use crate::KeyEvent;
+
+    #[test]
+    fn test_spinner() {
+        block_on(async {
+            let mut device = crate::test::Device::new(
+                30,
+                40,
+                &[
+                    (0, KeyEvent::Down(Key::Up)),
+                    (0, KeyEvent::Up(Key::Up)),
+                    (0, KeyEvent::Down(Key::Up)),
+                    (0, KeyEvent::Up(Key::Up)),
+                    (0, KeyEvent::Down(Key::Select)),
+                ],
+            );
+            let mut spinner = Spinner::new("", 7, 0, 9);
+            assert_eq!(spinner.process(&mut device).await, Some(9));
+        });
+    }
+
+    #[test]
+    fn test_typing() {
+        let mut spinner = Spinner::new("", 5, 1, 31);
+        spinner.type_digit(2);
+        assert_eq!(spinner.value(), 2);
+        spinner.type_digit(4);
+        assert_eq!(spinner.value(), 24);
+        spinner.type_digit(7);
+        assert_eq!(spinner.value(), 7);
+        spinner.type_digit(0);
+        assert_eq!(spinner.value(), 1);
+        spinner.step(1);
+        spinner.type_digit(3);
+        assert_eq!(spinner.value(), 3);
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut device = crate::test::Device::new(20, 36, &[]);
+        Spinner::new("", 7, 0, 7).draw(&mut device);
+        device.display.assert_pattern(&[
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "####################",
+            "#######........#####",
+            "#############..#####",
+            "#############..#####",
+            "############..######",
+            "############..######",
+            "###########..#######",
+            "###########..#######",
+            "##########..########",
+            "##########..########",
+            "#########..#########",
+            "#########..#########",
+            "########..##########",
+            "########..##########",
+            "####################",
+            "####################",
+            "#######.......######",
+            "########.....#######",
+            "#########...########",
+            "##########.#########",
+            "####################",
+        ]);
+    }
+}
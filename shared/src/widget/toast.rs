@@ -0,0 +1,141 @@
+use embassy_time::{Duration, Instant};
+use embedded_graphics::{
+    prelude::*,
+    primitives::{PrimitiveStyleBuilder, Rectangle},
+    text::{Alignment, Baseline, Text, TextStyleBuilder, renderer::TextRenderer},
+};
+use embedded_graphics_core::pixelcolor::BinaryColor;
+
+use crate::{KeyEvent, Keypad};
+
+pub const TOAST_DURATION: Duration = Duration::from_secs(2);
+
+// a short message in a box over whatever is on screen, gone after a while or at any key
+pub struct Toast<'a> {
+    text: &'a str,
+    duration: Duration,
+}
+
+impl<'a> Toast<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            duration: TOAST_DURATION,
+        }
+    }
+
+    pub fn with_duration(self, duration: Duration) -> Self {
+        Self { duration, ..self }
+    }
+
+    // lines are split on '\n', leaving the rest of the screen as it was
+    pub fn draw(&self, target: &mut impl DrawTarget<Color = BinaryColor>) {
+        let theme = crate::theme::current();
+        let style = theme.text_style();
+        let line_height = style.line_height();
+        let area = target.bounding_box();
+        let lines = self.text.lines().count().max(1) as u32;
+        let width = self
+            .text
+            .lines()
+            .map(|line| line.chars().count() as u32)
+            .max()
+            .unwrap_or(0)
+            * style.font.character_size.width;
+        let size = Size::new(
+            (width + 4 + 2 * theme.border_width).min(area.size.width),
+            (lines * line_height + 3 + 2 * theme.border_width).min(area.size.height),
+        );
+        let frame = Rectangle::with_center(area.center(), size);
+        let _ = frame
+            .into_styled(
+                PrimitiveStyleBuilder::new()
+                    .stroke_color(theme.foreground)
+                    .stroke_width(theme.border_width)
+                    .fill_color(theme.background)
+                    .build(),
+            )
+            .draw(target);
+        let _ = Text::with_text_style(
+            self.text,
+            Point::new(
+                frame.center().x,
+                frame.top_left.y + 2 + theme.border_width as i32,
+            ),
+            style,
+            TextStyleBuilder::new()
+                .alignment(Alignment::Center)
+                .baseline(Baseline::Top)
+                .build(),
+        )
+        .draw(target);
+    }
+
+    // the key that dismissed it, or `None` once it timed out
+    pub async fn process(
+        &self,
+        device: &mut (impl DrawTarget<Color = BinaryColor> + Keypad),
+    ) -> Option<KeyEvent> {
+        self.draw(device);
+        let deadline = Instant::now() + self.duration;
+        loop {
+            if let event @ KeyEvent::Down(_) = device.event_before(deadline).await? {
+                return Some(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures_executor::block_on;
+
+    use super::*;
+    use crate::Key;
+
+    #[test]
+    fn test_dismiss() {
+        block_on(async {
+            let mut device = crate::test::Device::new(84, 48, &[(500, KeyEvent::Down(Key::Three))]);
+            assert_eq!(
+                Toast::new("Saved").process(&mut device).await,
+                Some(KeyEvent::Down(Key::Three))
+            );
+        });
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut device = crate::test::Device::new(20, 26, &[]);
+        let _ = device.clear(BinaryColor::Off);
+        Toast::new("A\nB").draw(&mut device);
+        device.display.assert_pattern(&[
+            "....................",
+            ".....##########.....",
+            ".....##########.....",
+            ".....##########.....",
+            ".....####.#####.....",
+            ".....###.#.####.....",
+            ".....##.###.###.....",
+            ".....##.###.###.....",
+            ".....##.....###.....",
+            ".....##.###.###.....",
+            ".....##.###.###.....",
+            ".....##########.....",
+            ".....##########.....",
+            ".....##########.....",
+            ".....##....####.....",
+            ".....###.##.###.....",
+            ".....###.##.###.....",
+            ".....###...####.....",
+            ".....###.##.###.....",
+            ".....###.##.###.....",
+            ".....##....####.....",
+            ".....##########.....",
+            ".....##########.....",
+            ".....##########.....",
+            "....................",
+            "....................",
+        ]);
+    }
+}
@@ -1,7 +1,7 @@
 use embedded_graphics::{
     draw_target::DrawTarget,
     geometry::{OriginDimensions, Point, Size},
-    image::{ImageDrawable, ImageRaw},
+    image::ImageDrawable,
     pixelcolor::BinaryColor,
     prelude::*,
     primitives::Rectangle,
@@ -30,8 +30,7 @@ impl ImageDrawable for Cell {
             Cell::Critter(Direction::Right) => Point::new(0, 0),
             Cell::Food => Point::new(0, 20),
         };
-        let raw: ImageRaw<BinaryColor> = ImageRaw::new(DATA, 8);
-        let _ = raw
+        let _ = assets::SNAKE
             .sub_image(&Rectangle::new(top_left, self.size()))
             .draw(display);
         Ok(())
@@ -46,42 +45,6 @@ impl ImageDrawable for Cell {
     }
 }
 
-#[rustfmt::skip]
-const DATA: &[u8] = &[
-    // body
-    0b1111_1001,
-    0b0000_1001,
-    0b0000_1001,
-    0b1111_1001,
-
-    0b1111_1111,
-    0b1000_0001,
-    0b1000_0001,
-    0b1001_1001,
-
-    0b1001_1001,
-    0b1000_1001,
-    0b1000_0001,
-    0b1111_1111,
-
-    // head
-    0b1001_1011,
-    0b1001_0100,
-    0b0101_0000,
-    0b1001_1111,
-
-    0b1101_1001,
-    0b0010_0101,
-    0b0000_1001,
-    0b1111_1001,
-
-    // food
-    0b1011_1111,
-    0b0101_1111,
-    0b1011_1111,
-    0b1111_1111,
-];
-
 #[cfg(test)]
 mod test {
     use embedded_graphics::mock_display::MockDisplay;
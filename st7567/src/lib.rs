@@ -0,0 +1,195 @@
+#![no_std]
+
+// driver for the ST7567, the controller of common 128x64 COG panels. drawing only changes the
+// buffer; nothing reaches the panel until `flush`
+
+use core::cmp;
+
+use display_interface::{DataFormat, DisplayError, WriteOnlyDataCommand};
+use embedded_graphics_core::{
+    Pixel,
+    draw_target::DrawTarget,
+    geometry::{Dimensions, Point},
+    pixelcolor::BinaryColor,
+    primitives::rectangle::Rectangle,
+};
+use embedded_hal::{delay::DelayNs, digital::OutputPin};
+use page_buffer::PageBuffer;
+
+const WIDTH: usize = 128;
+const HEIGHT: usize = 64;
+const DISPLAY_OFF: u8 = 0xae;
+const DISPLAY_ON: u8 = 0xaf;
+const SET_START_LINE: u8 = 0x40;
+const SET_PAGE_ADDR: u8 = 0xb0;
+const SET_COLUMN_ADDR_HIGH: u8 = 0x10;
+const SET_COLUMN_ADDR_LOW: u8 = 0x00;
+const SEG_DIRECTION_NORMAL: u8 = 0xa0;
+const COM_DIRECTION_REVERSE: u8 = 0xc8;
+const DISPLAY_NORMAL: u8 = 0xa6;
+const DISPLAY_INVERTED: u8 = 0xa7;
+const SET_BIAS: u8 = 0xa2;
+const SET_REGULATION_RATIO: u8 = 0x20;
+const SET_EV: u8 = 0x81;
+const POWER_CONTROL: u8 = 0x28;
+const POWER_ALL_ON: u8 = 0x07;
+const RESET: u8 = 0xe2;
+
+#[derive(Debug)]
+pub enum Error<PinE> {
+    DisplayError(DisplayError),
+    Pin(PinE),
+}
+
+pub struct Driver<DI, RST, PinE>
+where
+    DI: WriteOnlyDataCommand,
+    RST: OutputPin<Error = PinE>,
+{
+    display_interface: DI,
+    reset: RST,
+    buffer: PageBuffer<WIDTH, { HEIGHT >> 3 }>,
+}
+
+impl<DI, RST, PinE> Driver<DI, RST, PinE>
+where
+    DI: WriteOnlyDataCommand,
+    RST: OutputPin<Error = PinE>,
+{
+    pub fn new(display_interface: DI, reset: RST) -> Self {
+        Self {
+            display_interface,
+            reset,
+            buffer: PageBuffer::new(),
+        }
+    }
+
+    pub fn set_bias(&mut self, val: u8) -> Result<(), DisplayError> {
+        self.display_interface
+            .send_commands(DataFormat::U8(&[SET_BIAS | cmp::min(0x01, val)]))
+    }
+
+    pub fn set_contrast(&mut self, val: u8) -> Result<(), DisplayError> {
+        self.display_interface
+            .send_commands(DataFormat::U8(&[SET_EV, cmp::min(val, 0x3f)]))
+    }
+
+    pub fn invert_display(&mut self, i: bool) -> Result<(), DisplayError> {
+        if i {
+            self.display_interface
+                .send_commands(DataFormat::U8(&[DISPLAY_INVERTED]))
+        } else {
+            self.display_interface
+                .send_commands(DataFormat::U8(&[DISPLAY_NORMAL]))
+        }
+    }
+
+    pub fn init<D: DelayNs>(&mut self, delay_source: &mut D) -> Result<(), DisplayError> {
+        let _ = self.reset.set_low();
+        delay_source.delay_us(5);
+        let _ = self.reset.set_high();
+        delay_source.delay_us(5);
+
+        self.display_interface
+            .send_commands(DataFormat::U8(&[RESET, DISPLAY_OFF]))?;
+        self.set_bias(0x00)?;
+        self.display_interface.send_commands(DataFormat::U8(&[
+            SEG_DIRECTION_NORMAL,
+            COM_DIRECTION_REVERSE,
+            SET_REGULATION_RATIO | 0x05,
+        ]))?;
+        self.set_contrast(0x20)?;
+        self.display_interface
+            .send_commands(DataFormat::U8(&[POWER_CONTROL | POWER_ALL_ON]))?;
+        self.display_interface.send_commands(DataFormat::U8(&[
+            SET_START_LINE,
+            DISPLAY_NORMAL,
+            DISPLAY_ON,
+        ]))?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), DisplayError> {
+        for (page, data) in self.buffer.pages() {
+            self.display_interface.send_commands(DataFormat::U8(&[
+                SET_PAGE_ADDR | page,
+                SET_COLUMN_ADDR_HIGH,
+                SET_COLUMN_ADDR_LOW,
+            ]))?;
+
+            self.display_interface.send_data(DataFormat::U8(data))?;
+        }
+        Ok(())
+    }
+}
+
+impl<DI, RST, PinE> DrawTarget for Driver<DI, RST, PinE>
+where
+    DI: WriteOnlyDataCommand,
+    RST: OutputPin<Error = PinE>,
+{
+    type Color = BinaryColor;
+
+    type Error = Error<PinE>;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.buffer
+                .set_pixel(point, color)
+                .map_err(|page_buffer::OutOfBounds| {
+                    Error::DisplayError(DisplayError::OutOfBoundsError)
+                })?;
+        }
+        Ok(())
+    }
+}
+
+impl<DI, RST, PinE> Dimensions for Driver<DI, RST, PinE>
+where
+    DI: WriteOnlyDataCommand,
+    RST: OutputPin<Error = PinE>,
+{
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::new(Point::zero(), self.buffer.size())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use std::{vec, vec::Vec};
+
+    use embedded_graphics_core::geometry::Size;
+    use page_buffer::mock::{Interface, Pin};
+
+    use super::*;
+
+    #[test]
+    fn test_flush() {
+        let mut driver = Driver::new(Interface::default(), Pin);
+        assert_eq!(driver.bounding_box().size, Size::new(128, 64));
+        driver
+            .draw_iter([Pixel(Point::new(127, 63), BinaryColor::On)])
+            .unwrap();
+        // drawing alone sends nothing
+        assert!(driver.display_interface.0.is_empty());
+
+        driver.flush().unwrap();
+        let sent = &driver.display_interface.0;
+        assert_eq!(sent.len(), 8 * 2);
+        assert_eq!(
+            sent[14],
+            (
+                true,
+                vec![SET_PAGE_ADDR | 7, SET_COLUMN_ADDR_HIGH, SET_COLUMN_ADDR_LOW]
+            )
+        );
+        let mut page = vec![0; 128];
+        page[127] = 0x80;
+        assert_eq!(sent[15], (false, page));
+    }
+}
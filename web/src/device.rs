@@ -12,7 +12,9 @@ mod backlight;
 mod buzzer;
 mod display;
 mod keypad;
+mod power_button;
 mod rtc;
+mod storage;
 mod vibration_motor;
 
 pub struct Device {
@@ -35,6 +37,7 @@ pub struct Device {
     asterisk: Rc<RefCell<DomB>>,
     zero: Rc<RefCell<DomB>>,
     hash: Rc<RefCell<DomB>>,
+    power: Rc<RefCell<DomB>>,
     vibration_element: Element,
     oscillator: Rc<Mutex<Option<OscillatorNode>>>,
     gain: Rc<Mutex<Option<GainNode>>>,
@@ -67,6 +70,7 @@ impl Device {
         asterisk_id: &'static str,
         zero_id: &'static str,
         hash_id: &'static str,
+        power_id: &'static str,
         vibration_element: Element,
     ) -> Self {
         let output_settings = OutputSettingsBuilder::new()
@@ -101,6 +105,7 @@ impl Device {
             asterisk: crate::DomB::new(asterisk_id),
             zero: crate::DomB::new(zero_id),
             hash: crate::DomB::new(hash_id),
+            power: crate::DomB::new(power_id),
             vibration_element,
             offset: 0,
             mute: true,
@@ -148,5 +153,8 @@ impl Device {
     }
 }
 
-impl shared::Device for Device {
-}
+impl shared::DisplayPower for Device {}
+
+impl shared::Indicators for Device {}
+
+impl shared::Device for Device {}
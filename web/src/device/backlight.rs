@@ -1,15 +1,26 @@
 use shared::Backlight;
 
+// how opaque the backlight's glow is when fully lit, as it was drawn
+const OPACITY: f32 = 0.475;
+
 impl Backlight for super::Device {
     fn on(&mut self) {
-        self.backlight_element
-            .set_attribute("style", "color: lime")
-            .unwrap();
+        self.set_brightness(100);
     }
 
     fn off(&mut self) {
+        self.set_brightness(0);
+    }
+
+    fn set_brightness(&mut self, level: u8) {
+        let style = if level == 0 {
+            "color: black".to_string()
+        } else {
+            let opacity = OPACITY * f32::from(level.min(100)) / 100.0;
+            format!("color: lime; opacity: {opacity}")
+        };
         self.backlight_element
-            .set_attribute("style", "color: black")
+            .set_attribute("style", &style)
             .unwrap();
     }
 }
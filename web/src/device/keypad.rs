@@ -103,6 +103,12 @@ impl super::Device {
                 } else {
                     shared::KeyEvent::Up(Key::Hash)
                 };
+            } else if let Some(e) = (*self.power).borrow_mut().check() {
+                return if e == crate::Event::Down {
+                    shared::KeyEvent::Down(Key::Power)
+                } else {
+                    shared::KeyEvent::Up(Key::Power)
+                };
             } else if let Some(e) = (*self.keyboard).borrow_mut().check() {
                 return match e {
                     crate::KeyEvent::Down('0') => shared::KeyEvent::Down(Key::Zero),
@@ -121,6 +127,7 @@ impl super::Device {
                     crate::KeyEvent::Down('d') => shared::KeyEvent::Down(Key::Down),
                     crate::KeyEvent::Down('E') => shared::KeyEvent::Down(Key::Select),
                     crate::KeyEvent::Down('e') => shared::KeyEvent::Down(Key::Cancel),
+                    crate::KeyEvent::Down('p') => shared::KeyEvent::Down(Key::Power),
                     crate::KeyEvent::Up('0') => shared::KeyEvent::Up(Key::Zero),
                     crate::KeyEvent::Up('1') => shared::KeyEvent::Up(Key::One),
                     crate::KeyEvent::Up('2') => shared::KeyEvent::Up(Key::Two),
@@ -137,6 +144,7 @@ impl super::Device {
                     crate::KeyEvent::Up('d') => shared::KeyEvent::Up(Key::Down),
                     crate::KeyEvent::Up('E') => shared::KeyEvent::Up(Key::Select),
                     crate::KeyEvent::Up('e') => shared::KeyEvent::Up(Key::Cancel),
+                    crate::KeyEvent::Up('p') => shared::KeyEvent::Up(Key::Power),
 
                     _ => shared::KeyEvent::Down(Key::Select),
                 };
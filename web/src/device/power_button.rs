@@ -1,14 +1,7 @@
-pub struct DomPower;
+use shared::PowerButton;
 
-impl DomPower {
-    pub fn new() -> Self {
-        Self
-    }
-}
-
-unsafe impl Send for DomPower {}
-
-impl shared::PowerButton for DomPower {
+// there is nothing to switch off in a browser tab, so start again from scratch
+impl PowerButton for super::Device {
     fn clear(&mut self) {
         let window = web_sys::window().expect("no global `window` exists");
         let location = window.location();
@@ -0,0 +1,32 @@
+use core::fmt::Write;
+
+use shared::Storage;
+
+// local storage only holds strings, so bytes are kept as hex
+impl Storage for super::Device {
+    fn load(&mut self, key: &str, buffer: &mut [u8]) -> Option<usize> {
+        let storage = web_sys::window()?.local_storage().ok()??;
+        let hex = storage.get_item(key).ok()??;
+        let bytes = hex.as_bytes().chunks(2).map(|pair| {
+            core::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+        });
+        let mut length = 0;
+        for (byte, slot) in bytes.zip(buffer.iter_mut()) {
+            *slot = byte?;
+            length += 1;
+        }
+        Some(length)
+    }
+
+    fn store(&mut self, key: &str, data: &[u8]) {
+        let mut hex = String::with_capacity(data.len() * 2);
+        for byte in data {
+            let _ = write!(hex, "{:02x}", byte);
+        }
+        if let Some(Ok(Some(storage))) = web_sys::window().map(|window| window.local_storage()) {
+            let _ = storage.set_item(key, &hex);
+        }
+    }
+}
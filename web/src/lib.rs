@@ -2,38 +2,6 @@
 mod device;
 
 use embassy_executor::Spawner;
-use embedded_graphics::pixelcolor::{BinaryColor, PixelColor};
-use shared::Application;
-
-#[derive(Clone, PartialEq, Copy)]
-enum Inverted {
-    On,
-    Off,
-}
-
-impl PixelColor for Inverted {
-    type Raw = <BinaryColor as PixelColor>::Raw;
-}
-
-impl From<Inverted> for BinaryColor {
-    fn from(val: Inverted) -> Self {
-        match val {
-            Inverted::On => BinaryColor::On,
-            Inverted::Off => BinaryColor::Off,
-        }
-    }
-}
-
-impl From<BinaryColor> for Inverted {
-    fn from(v: BinaryColor) -> Self {
-        match v {
-            BinaryColor::On => Inverted::Off,
-            BinaryColor::Off => Inverted::On,
-        }
-    }
-}
-
-// (draw_target.color_converted::<Inverted>()).color_converted::<BinaryColor>()
 
 #[embassy_executor::main]
 async fn main(_spawner: Spawner) {
@@ -42,7 +10,7 @@ async fn main(_spawner: Spawner) {
     let window = web_sys::window().expect("no global `window` exists");
     let document = window.document().expect("should have a document on window");
 
-    let mut device = device::Device::new(
+    let device = device::Device::new(
         document.get_element_by_id("backlight").unwrap(),
         document.get_element_by_id("body").unwrap(),
         document.get_element_by_id("display").unwrap(),
@@ -62,17 +30,25 @@ async fn main(_spawner: Spawner) {
         "asterisk",
         "zero",
         "hash",
+        "power",
         document.get_element_by_id("svg1").unwrap(),
     );
 
-    let s = heapless::String::<240>::new();
-    let mut keyboard = keyboard::Keyboard::new(s);
-    loop {
-        let _ = keyboard.run(&mut device).await;
-    }
-
-    // let mut ringtones = ringtones::Ringtones::new();
-    // let _ = ringtones.run(&mut device).await;
+    let mut system = shared::system::System::new(device);
+
+    shared::home::home(
+        &mut system,
+        &mut (
+            (
+                "Keyboard",
+                keyboard::Keyboard::new(heapless::String::<240>::new()),
+            ),
+            ("Ringtones", ringtones::Ringtones::new()),
+            ("Clock", clock::Clock::new()),
+            ("Snake", snake::Snake::new(0)),
+        ),
+    )
+    .await
 }
 
 use core::cell::RefCell;
@@ -191,6 +167,7 @@ impl DomK {
                     38 => 'u',
                     13 => 'E',
                     27 => 'e',
+                    80 => 'p',
                     _ => '0',
                 };
                 (*g).borrow_mut().was_clicked = Some(c);
@@ -212,6 +189,7 @@ impl DomK {
                     38 => 'u',
                     13 => 'E',
                     27 => 'e',
+                    80 => 'p',
                     _ => '0',
                 };
                 (*h).borrow_mut().was_unclicked = Some(c);